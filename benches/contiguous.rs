@@ -0,0 +1,86 @@
+//! Compare the contiguous fast paths with the generic chunck loop.
+//!
+//! Run with `cargo bench --bench contiguous`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use bytes::{Buf, BufMut, Bytes};
+
+const ITERATIONS: u32 = 200_000;
+
+/// A contiguous buffer hiding its contiguity so that the generic chunck loop is used
+struct Opaque<'a>(&'a [u8]);
+
+impl Buf for Opaque<'_> {
+    fn remaining(&self) -> usize {
+        self.0.remaining()
+    }
+
+    fn chuncks(&self) -> &[u8] {
+        self.0.chuncks()
+    }
+
+    fn advance(&mut self, cnt: usize) {
+        self.0.advance(cnt)
+    }
+
+    fn fork(&self) -> Bytes {
+        self.0.fork()
+    }
+}
+
+fn bench<F: FnMut()>(name: &str, mut f: F) -> Duration {
+    // Warm up
+    for _ in 0..ITERATIONS / 10 {
+        f();
+    }
+
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    let elapsed = start.elapsed();
+
+    println!(
+        "{:<40} {:>8.1} ns/iter",
+        name,
+        elapsed.as_nanos() as f64 / ITERATIONS as f64
+    );
+    elapsed
+}
+
+fn main() {
+    for size in [16, 256, 4096] {
+        let data = vec![0xab; size];
+        let mut dst = vec![0u8; size];
+
+        println!("--- {} bytes ---", size);
+
+        bench("copy_to_slice contiguous", || {
+            let mut src = black_box(&data[..]);
+            src.copy_to_slice(&mut dst);
+            black_box(&dst);
+        });
+        bench("copy_to_slice chunck loop", || {
+            let mut src = black_box(Opaque(&data[..]));
+            src.copy_to_slice(&mut dst);
+            black_box(&dst);
+        });
+
+        let mut out = Vec::with_capacity(size);
+
+        bench("put contiguous", || {
+            out.clear();
+            let mut limited = (&mut out).limit(size);
+            limited.put(black_box(&data[..]));
+            black_box(&out);
+        });
+        bench("put chunck loop", || {
+            out.clear();
+            let mut limited = (&mut out).limit(size);
+            limited.put(black_box(Opaque(&data[..])));
+            black_box(&out);
+        });
+    }
+}
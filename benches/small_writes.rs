@@ -0,0 +1,88 @@
+//! Measure the small writes of encoders, e.g. the fields of a binary message.
+//!
+//! Run with `cargo bench --bench small_writes`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use bytes::{BufMut, BytesMut};
+
+const ITERATIONS: u32 = 20_000;
+
+/// The number of writes per iteration
+const WRITES: usize = 64;
+
+fn bench<F: FnMut()>(name: &str, mut f: F) -> Duration {
+    // Warm up
+    for _ in 0..ITERATIONS / 10 {
+        f();
+    }
+
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    let elapsed = start.elapsed();
+
+    println!(
+        "{:<40} {:>8.1} ns/iter",
+        name,
+        elapsed.as_nanos() as f64 / ITERATIONS as f64
+    );
+    elapsed
+}
+
+fn main() {
+    let data = [0xab; 16];
+    let mut buf = BytesMut::with_capacity(WRITES * 16);
+
+    for size in [1, 2, 4, 8, 16] {
+        bench(
+            &format!("extend_from_slice {:>2} bytes x{}", size, WRITES),
+            || {
+                unsafe { buf.set_len(0) };
+                for _ in 0..WRITES {
+                    buf.extend_from_slice(black_box(&data[..size]));
+                }
+                black_box(&buf);
+            },
+        );
+    }
+
+    bench(&format!("put_u8 x{}", WRITES), || {
+        unsafe { buf.set_len(0) };
+        for i in 0..WRITES {
+            buf.put_u8(black_box(i as u8));
+        }
+        black_box(&buf);
+    });
+
+    // A padding of 4 KiB
+    let mut padding = BytesMut::with_capacity(4096);
+
+    bench("put_slice 4 bytes x1024", || {
+        unsafe { padding.set_len(0) };
+        for _ in 0..1024 {
+            padding.put_slice(black_box(b"\0\xff\0\xff"));
+        }
+        black_box(&padding);
+    });
+
+    bench("put_slice_repeated 4 bytes x1024", || {
+        unsafe { padding.set_len(0) };
+        padding.put_slice_repeated(black_box(b"\0\xff\0\xff"), 1024);
+        black_box(&padding);
+    });
+
+    // A message made of fields of various sizes
+    bench("mixed fields", || {
+        unsafe { buf.set_len(0) };
+        for i in 0..WRITES / 4 {
+            buf.put_u8(black_box(i as u8));
+            buf.put_slice(black_box(&data[..2]));
+            buf.put_slice(black_box(&data[..4]));
+            buf.put_slice(black_box(&data[..black_box(i % 16)]));
+        }
+        black_box(&buf);
+    });
+}
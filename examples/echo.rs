@@ -0,0 +1,88 @@
+//! A TCP echo server exchanging frames prefixed with their 32-bit length.
+//!
+//! The server reads the stream into a queue of `Bytes`, decodes the frames with `MaxFrameLen`
+//! without copying their bodies and echoes them back in frames written with a
+//! `LengthPrefixedWriter`. A client sends a few messages to it and prints the replies.
+//!
+//! Run with `cargo run --example echo`.
+
+use std::io::{self, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+
+use bytes::codec::frame::{FrameLenError, LengthPrefix, MaxFrameLen};
+use bytes::{Buf, BufMut, Bytes, BytesMut, OffsetTrackingBuf};
+
+const MAX_FRAME_LEN: MaxFrameLen = MaxFrameLen::new(64 * 1024);
+
+/// Read frames from `stream` until a complete frame is decoded, `None` once the peer closed the
+/// connection between two frames.
+fn read_frame(
+    stream: &mut TcpStream,
+    pending: &mut OffsetTrackingBuf,
+) -> io::Result<Option<Bytes>> {
+    let mut chunk = [0; 4096];
+
+    loop {
+        match MAX_FRAME_LEN.decode(pending, LengthPrefix::U32) {
+            Ok(frame) => return Ok(Some(frame)),
+            Err(FrameLenError::Incomplete { .. }) => (),
+            Err(e) => return Err(io::Error::new(io::ErrorKind::InvalidData, e)),
+        }
+
+        let n = stream.read(&mut chunk)?;
+
+        if n == 0 {
+            return match pending.remaining() {
+                0 => Ok(None),
+                _ => Err(io::ErrorKind::UnexpectedEof.into()),
+            };
+        }
+
+        pending.push(Bytes::copy_from_slice(&chunk[..n]));
+    }
+}
+
+fn write_frame(stream: &mut TcpStream, body: &[u8]) -> io::Result<()> {
+    let mut out = BytesMut::with_capacity(LengthPrefix::U32.width() + body.len());
+
+    let mut frame = out.length_prefixed(LengthPrefix::U32);
+    frame.put_slice(body);
+    frame.finish();
+
+    stream.write_all(out.as_ref())
+}
+
+fn serve(mut stream: TcpStream) -> io::Result<()> {
+    let mut pending = OffsetTrackingBuf::new();
+
+    while let Some(frame) = read_frame(&mut stream, &mut pending)? {
+        write_frame(&mut stream, &frame)?;
+    }
+
+    Ok(())
+}
+
+fn main() -> io::Result<()> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let addr = listener.local_addr()?;
+
+    let server = thread::spawn(move || -> io::Result<()> {
+        let (stream, _) = listener.accept()?;
+        serve(stream)
+    });
+
+    let mut client = TcpStream::connect(addr)?;
+    let mut pending = OffsetTrackingBuf::new();
+
+    for message in ["hello", "length-delimited", "world"] {
+        write_frame(&mut client, message.as_bytes())?;
+
+        let reply = read_frame(&mut client, &mut pending)?.expect("the server closed");
+        assert_eq!(reply, message);
+        println!("echoed {:?}", reply);
+    }
+
+    client.shutdown(std::net::Shutdown::Write)?;
+    server.join().expect("the server panicked")
+}
@@ -0,0 +1,49 @@
+//! Print the bytes of a file in the classic hex dump layout: the offset, 16 bytes in hex and
+//! their printable ASCII characters.
+//!
+//! Run with `cargo run --example hexdump -- <path>`, the example dumps its own source if no path
+//! is given.
+
+use std::io::{self, BufWriter, Write};
+use std::{env, fs};
+
+use bytes::Bytes;
+
+const WIDTH: usize = 16;
+
+fn main() -> io::Result<()> {
+    let path = env::args()
+        .nth(1)
+        .unwrap_or_else(|| concat!(env!("CARGO_MANIFEST_DIR"), "/examples/hexdump.rs").into());
+
+    // The file is read once, every line below is a view of this buffer
+    let content = Bytes::from(fs::read(path)?);
+    let mut out = BufWriter::new(io::stdout().lock());
+
+    for (index, line) in content.chunks(WIDTH).enumerate() {
+        write!(out, "{:08x}  ", index * WIDTH)?;
+
+        for (i, byte) in line.iter().enumerate() {
+            let sep = if i == WIDTH / 2 - 1 { "  " } else { " " };
+            write!(out, "{:02x}{}", byte, sep)?;
+        }
+
+        // Align the ASCII column of the last line
+        for i in line.len()..WIDTH {
+            let sep = if i == WIDTH / 2 - 1 { "  " } else { " " };
+            write!(out, "  {}", sep)?;
+        }
+
+        let ascii: String = line
+            .iter()
+            .map(|b| match b {
+                0x20..=0x7e => *b as char,
+                _ => '.',
+            })
+            .collect();
+        writeln!(out, " |{}|", ascii)?;
+    }
+
+    writeln!(out, "{:08x}", content.len())?;
+    out.flush()
+}
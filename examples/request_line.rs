@@ -0,0 +1,110 @@
+//! Parse the request line and the headers of an HTTP/1 request without copying them.
+//!
+//! Every parsed field is a view of the buffer holding the request, the buffer is released once
+//! the fields are dropped.
+//!
+//! Run with `cargo run --example request_line`.
+
+use bytes::{ByteStr, Bytes};
+
+#[derive(Debug)]
+struct RequestLine {
+    method: ByteStr,
+    target: ByteStr,
+    version: ByteStr,
+}
+
+#[derive(Debug)]
+struct Request {
+    line: RequestLine,
+    headers: Vec<(ByteStr, Bytes)>,
+    body: Bytes,
+}
+
+#[derive(Debug)]
+enum ParseError {
+    Incomplete,
+    Invalid(&'static str),
+}
+
+fn parse_request_line(line: Bytes) -> Result<RequestLine, ParseError> {
+    let mut parts = line.split(b' ');
+
+    let mut next = |what| {
+        let part = parts.next().filter(|part| !part.is_empty());
+        ByteStr::try_from(part.ok_or(ParseError::Invalid(what))?)
+            .map_err(|_| ParseError::Invalid(what))
+    };
+
+    let line = RequestLine {
+        method: next("missing method")?,
+        target: next("missing target")?,
+        version: next("missing version")?,
+    };
+
+    if parts.next().is_some() {
+        return Err(ParseError::Invalid("trailing bytes in the request line"));
+    }
+    if !line.version.starts_with("HTTP/1.") {
+        return Err(ParseError::Invalid("unsupported version"));
+    }
+
+    Ok(line)
+}
+
+fn parse(request: &Bytes) -> Result<Request, ParseError> {
+    let end = request.find(b"\r\n\r\n").ok_or(ParseError::Incomplete)?;
+    let head = request.slice(..end);
+    let body = request.slice(end + 4..);
+
+    let mut lines = head.split(b"\r\n");
+    let line = parse_request_line(lines.next().ok_or(ParseError::Incomplete)?)?;
+
+    let headers = lines
+        .map(|header| {
+            let colon = header
+                .find(b":")
+                .ok_or(ParseError::Invalid("header without a colon"))?;
+            let name = ByteStr::try_from(header.slice(..colon))
+                .map_err(|_| ParseError::Invalid("header name is not UTF-8"))?;
+
+            Ok((name, header.slice(colon + 1..).trim_ascii()))
+        })
+        .collect::<Result<_, ParseError>>()?;
+
+    Ok(Request {
+        line,
+        headers,
+        body,
+    })
+}
+
+fn main() {
+    let request = Bytes::from(
+        b"GET /index.html?lang=en HTTP/1.1\r\nHost: example.com\r\nAccept:  */*\r\n\r\nbody"
+            .to_vec(),
+    );
+
+    let parsed = parse(&request).expect("the request is valid");
+
+    println!(
+        "{} {} ({})",
+        parsed.line.method, parsed.line.target, parsed.line.version
+    );
+    for (name, value) in &parsed.headers {
+        println!("  {}: {:?}", name, value);
+
+        // Nothing was copied
+        assert!(value.is_subslice_of(&request));
+    }
+    println!("body: {:?}", parsed.body);
+
+    assert!(matches!(
+        parse(&Bytes::from_static(b"GET / HTTP/1.1\r\nHost")),
+        Err(ParseError::Incomplete)
+    ));
+    assert!(matches!(
+        parse(&Bytes::from_static(b"GET /\r\n\r\n")),
+        Err(ParseError::Invalid("missing version"))
+    ));
+}
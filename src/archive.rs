@@ -0,0 +1,128 @@
+//! `rkyv` archives of `Bytes` and `ByteStr`, enabled by the `rkyv` feature.
+//!
+//! `Bytes` is archived as an `ArchivedVec<u8>` and `ByteStr` as an `ArchivedString`, the same
+//! archived forms as `Vec<u8>` and `String`, so the types can be swapped in an archived
+//! structure without changing its layout. Accessing an archive reads the bytes in place.
+//!
+//! Deserializing copies the bytes into a new buffer. When the archive itself is stored in a
+//! `Bytes`, [`Bytes::slice_ref`] and [`ByteStr::slice_ref`] turn the archived fields into views
+//! of that buffer instead, without copying them. The archive must be aligned, e.g. by creating
+//! the `Bytes` from the `AlignedVec` of the serializer with [`Bytes::from_owner`].
+//!
+//! # Example
+//!
+//! ```
+//! use bytes::{ByteStr, Bytes};
+//! use rkyv::rancor::Error;
+//!
+//! #[derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)]
+//! struct Message {
+//!     topic: ByteStr,
+//!     payload: Bytes,
+//! }
+//!
+//! let message = Message {
+//!     topic: ByteStr::from_static("sensors/1"),
+//!     payload: Bytes::from_static(b"\x00\x2a"),
+//! };
+//!
+//! let archive = Bytes::from_owner(rkyv::to_bytes::<Error>(&message).unwrap());
+//! let archived = rkyv::access::<ArchivedMessage, Error>(&archive).unwrap();
+//!
+//! // Views of the archive, nothing is copied
+//! let payload = archive.slice_ref(&archived.payload);
+//! assert_eq!(payload, b"\x00\x2a"[..]);
+//! assert!(payload.is_subslice_of(&archive));
+//!
+//! // Copies of the archived bytes
+//! let message = rkyv::deserialize::<Message, Error>(archived).unwrap();
+//! assert_eq!(message.topic, "sensors/1");
+//! ```
+
+use rkyv::rancor::{Fallible, Source};
+use rkyv::ser::{Allocator, Writer};
+use rkyv::string::{ArchivedString, StringResolver};
+use rkyv::vec::{ArchivedVec, VecResolver};
+use rkyv::{Archive, Deserialize, Place, Serialize, SerializeUnsized};
+
+use crate::{ByteStr, Bytes};
+
+impl Archive for Bytes {
+    type Archived = ArchivedVec<u8>;
+    type Resolver = VecResolver;
+
+    #[inline]
+    fn resolve(&self, resolver: VecResolver, out: Place<ArchivedVec<u8>>) {
+        ArchivedVec::resolve_from_slice(self.as_slice(), resolver, out);
+    }
+}
+
+impl<S: Fallible + Allocator + Writer + ?Sized> Serialize<S> for Bytes {
+    fn serialize(&self, serializer: &mut S) -> Result<VecResolver, S::Error> {
+        ArchivedVec::<u8>::serialize_from_slice(self.as_slice(), serializer)
+    }
+}
+
+impl<D: Fallible + ?Sized> Deserialize<Bytes, D> for ArchivedVec<u8> {
+    fn deserialize(&self, _: &mut D) -> Result<Bytes, D::Error> {
+        Ok(Bytes::copy_from_slice(self.as_slice()))
+    }
+}
+
+impl Archive for ByteStr {
+    type Archived = ArchivedString;
+    type Resolver = StringResolver;
+
+    #[inline]
+    fn resolve(&self, resolver: StringResolver, out: Place<ArchivedString>) {
+        ArchivedString::resolve_from_str(self.as_str(), resolver, out);
+    }
+}
+
+impl<S: Fallible + ?Sized> Serialize<S> for ByteStr
+where
+    S::Error: Source,
+    str: SerializeUnsized<S>,
+{
+    fn serialize(&self, serializer: &mut S) -> Result<StringResolver, S::Error> {
+        ArchivedString::serialize_from_str(self.as_str(), serializer)
+    }
+}
+
+impl<D: Fallible + ?Sized> Deserialize<ByteStr, D> for ArchivedString {
+    fn deserialize(&self, _: &mut D) -> Result<ByteStr, D::Error> {
+        Ok(ByteStr::from(self.as_str()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rkyv::rancor::Error;
+
+    use super::*;
+
+    #[test]
+    fn roundtrip() {
+        for content in [&b""[..], b"a", &[0xff; 100]] {
+            let bytes = Bytes::copy_from_slice(content);
+
+            let archive = rkyv::to_bytes::<Error>(&bytes).unwrap();
+            let archived = rkyv::access::<ArchivedVec<u8>, Error>(&archive).unwrap();
+            assert_eq!(archived.as_slice(), content);
+
+            let bytes = rkyv::deserialize::<Bytes, Error>(archived).unwrap();
+            assert_eq!(bytes, content);
+        }
+    }
+
+    #[test]
+    fn same_layout_as_std() {
+        let archive = rkyv::to_bytes::<Error>(&ByteStr::from_static("a longer string")).unwrap();
+        let string = rkyv::from_bytes::<String, Error>(&archive).unwrap();
+        assert_eq!(string, "a longer string");
+
+        let archive = rkyv::to_bytes::<Error>(&b"vec".to_vec()).unwrap();
+        let archived = rkyv::access::<ArchivedVec<u8>, Error>(&archive).unwrap();
+        assert_eq!(rkyv::deserialize::<Bytes, Error>(archived).unwrap(), "vec");
+    }
+}
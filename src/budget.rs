@@ -0,0 +1,207 @@
+//! Memory budget of the buffers of the crate.
+//!
+//! A [`Tracker`] accounts the bytes allocated by [`BytesMut`](crate::BytesMut) buffers and
+//! refuses new allocations once its limit is reached. Every `BytesMut` is tracked by the
+//! [global tracker](Tracker::global) unless it was created with its own tracker using
+//! [`BytesMut::with_budget`](crate::BytesMut::with_budget).
+//!
+//! The global tracker is disabled by default and can be toggled at runtime. It only accounts the
+//! buffers which allocated while it was enabled, so that programs not using a budget don't
+//! update its shared counter on every allocation. The trackers created with [`Tracker::new`]
+//! always account their buffers, the limit is only enforced while they are enabled.
+//!
+//! # Example
+//!
+//! ```
+//! use std::sync::Arc;
+//!
+//! use bytes::budget::Tracker;
+//! use bytes::BytesMut;
+//!
+//! let tracker = Arc::new(Tracker::new(1024));
+//! let mut buf = BytesMut::with_budget(tracker.clone());
+//!
+//! buf.try_reserve(1000).unwrap();
+//! assert_eq!(tracker.used(), 1000);
+//! assert!(buf.try_reserve(2000).is_err());
+//!
+//! drop(buf);
+//! assert_eq!(tracker.used(), 0);
+//! ```
+
+use core::fmt;
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+static GLOBAL: Tracker = Tracker::disabled(usize::MAX);
+
+/// A budget of bytes shared by several buffers.
+#[derive(Debug)]
+pub struct Tracker {
+    limit: AtomicUsize,
+    used: AtomicUsize,
+    enabled: AtomicBool,
+}
+
+impl Tracker {
+    /// Create a new enabled `Tracker` allowing at most `limit` bytes.
+    pub const fn new(limit: usize) -> Tracker {
+        Tracker {
+            limit: AtomicUsize::new(limit),
+            used: AtomicUsize::new(0),
+            enabled: AtomicBool::new(true),
+        }
+    }
+
+    /// Create a new disabled `Tracker` allowing at most `limit` bytes once enabled.
+    pub const fn disabled(limit: usize) -> Tracker {
+        Tracker {
+            limit: AtomicUsize::new(limit),
+            used: AtomicUsize::new(0),
+            enabled: AtomicBool::new(false),
+        }
+    }
+
+    /// Return the global tracker used by the buffers without their own tracker.
+    #[inline]
+    pub fn global() -> &'static Tracker {
+        &GLOBAL
+    }
+
+    /// Return the maximum number of bytes allowed by `self`.
+    #[inline]
+    pub fn limit(&self) -> usize {
+        self.limit.load(Ordering::Relaxed)
+    }
+
+    /// Set the maximum number of bytes allowed by `self`. Lowering the limit below the used
+    /// bytes doesn't free anything, it only refuses the next allocations.
+    #[inline]
+    pub fn set_limit(&self, limit: usize) {
+        self.limit.store(limit, Ordering::Relaxed)
+    }
+
+    /// Return the number of bytes currently accounted by `self`.
+    #[inline]
+    pub fn used(&self) -> usize {
+        self.used.load(Ordering::Relaxed)
+    }
+
+    /// Return the number of bytes that can still be allocated.
+    #[inline]
+    pub fn available(&self) -> usize {
+        self.limit().saturating_sub(self.used())
+    }
+
+    /// Return `true` if the limit of `self` is enforced.
+    #[inline]
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    /// Enable or disable the enforcement of the limit of `self`.
+    #[inline]
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Relaxed)
+    }
+
+    /// Account `n` more bytes. If `self` is enabled and the limit would be exceeded nothing is
+    /// accounted and an error is returned.
+    pub fn try_acquire(&self, n: usize) -> Result<(), BudgetExceeded> {
+        if !self.is_enabled() {
+            self.used.fetch_add(n, Ordering::Relaxed);
+            return Ok(());
+        }
+
+        let limit = self.limit();
+
+        self.used
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |used| {
+                used.checked_add(n).filter(|total| *total <= limit)
+            })
+            .map(|_| ())
+            .map_err(|used| BudgetExceeded {
+                requested: n,
+                available: limit.saturating_sub(used),
+            })
+    }
+
+    /// Stop accounting `n` bytes.
+    #[inline]
+    pub fn release(&self, n: usize) {
+        self.used.fetch_sub(n, Ordering::Relaxed);
+    }
+}
+
+/// The heap memory attributed to a buffer.
+///
+/// Returned by [`Bytes::memory_usage`](crate::Bytes::memory_usage) and
+/// [`BytesMut::memory_usage`](crate::BytesMut::memory_usage).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoryUsage {
+    /// The size of the allocation holding the bytes, including the unused capacity and the bytes
+    /// outside of the view of the buffer.
+    pub allocation: usize,
+    /// The size of the bookkeeping allocations, e.g. the reference count of a shared buffer.
+    pub overhead: usize,
+    /// `true` if the allocations are currently shared with other buffers. A pool should only
+    /// attribute a share of the memory to each of them.
+    pub shared: bool,
+}
+
+impl MemoryUsage {
+    /// Return the total number of heap bytes, `self.allocation + self.overhead`.
+    #[inline]
+    pub const fn total(&self) -> usize {
+        self.allocation + self.overhead
+    }
+}
+
+/// The error returned when an allocation would exceed the limit of a [`Tracker`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BudgetExceeded {
+    /// The number of bytes requested.
+    pub requested: usize,
+    /// The number of bytes that were still available.
+    pub available: usize,
+}
+
+impl fmt::Display for BudgetExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "memory budget exceeded: requested ({}) > available ({})",
+            self.requested, self.available
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for BudgetExceeded {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn toggle() {
+        let tracker = Tracker::new(10);
+
+        assert!(tracker.try_acquire(8).is_ok());
+        assert_eq!(
+            tracker.try_acquire(4),
+            Err(BudgetExceeded {
+                requested: 4,
+                available: 2
+            })
+        );
+
+        tracker.set_enabled(false);
+        assert!(tracker.try_acquire(4).is_ok());
+        assert_eq!(tracker.used(), 12);
+        assert_eq!(tracker.available(), 0);
+
+        tracker.set_enabled(true);
+        tracker.release(12);
+        assert!(tracker.try_acquire(10).is_ok());
+    }
+}
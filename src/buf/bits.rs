@@ -0,0 +1,291 @@
+use super::{Buf, BufMut};
+
+/// The order in which bits are read from or written into a byte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BitOrder {
+    /// The first bit is the most significant bit of the byte and the first bit of a field is its
+    /// most significant bit. This is the order used by most network protocols and by H.264.
+    MsbFirst,
+    /// The first bit is the least significant bit of the byte and the first bit of a field is its
+    /// least significant bit. This is the order used by DEFLATE.
+    LsbFirst,
+}
+
+/// A reader of bit fields on top of a `Buf`.
+///
+/// # Example
+///
+/// ```
+/// use bytes::{BitOrder, BitReader};
+///
+/// // H.264 NAL header: forbidden_zero_bit (1), nal_ref_idc (2), nal_unit_type (5)
+/// let mut reader = BitReader::new(&[0x67u8][..], BitOrder::MsbFirst);
+///
+/// assert_eq!(reader.read_bits(1), Some(0));
+/// assert_eq!(reader.read_bits(2), Some(3));
+/// assert_eq!(reader.read_bits(5), Some(7));
+/// assert_eq!(reader.read_bits(1), None);
+/// ```
+#[derive(Debug)]
+pub struct BitReader<B> {
+    inner: B,
+    order: BitOrder,
+    /// The unread bits of the current byte, stored in the low bits
+    cache: u8,
+    /// The number of unread bits in `self.cache`
+    bits: u32,
+}
+
+impl<B: Buf> BitReader<B> {
+    /// Create a new `BitReader` reading from `inner` in the given bit `order`.
+    #[inline]
+    pub fn new(inner: B, order: BitOrder) -> BitReader<B> {
+        BitReader {
+            inner,
+            order,
+            cache: 0,
+            bits: 0,
+        }
+    }
+
+    /// Return the number of bits that can still be read.
+    pub fn remaining_bits(&self) -> usize {
+        self.inner
+            .remaining()
+            .saturating_mul(8)
+            .saturating_add(self.bits as usize)
+    }
+
+    /// Read a field of `n` bits and return it. If there is less than `n` bits remaining
+    /// `Option::None` is returned and nothing is consumed.
+    ///
+    /// # Panics
+    ///
+    /// This panics if `n > 64`.
+    pub fn read_bits(&mut self, mut n: u32) -> Option<u64> {
+        assert!(n <= 64, "cannot read more than 64 bits: n ({})", n);
+
+        if self.remaining_bits() < n as usize {
+            return None;
+        }
+
+        let mut value = 0u64;
+        let mut shift = 0;
+
+        while n > 0 {
+            if self.bits == 0 {
+                self.cache = self.inner.get_u8();
+                self.bits = 8;
+            }
+
+            let k = n.min(self.bits);
+
+            match self.order {
+                BitOrder::MsbFirst => {
+                    let chunck = (self.cache >> (self.bits - k)) & mask8(k);
+                    value = (value << k) | chunck as u64;
+                }
+                BitOrder::LsbFirst => {
+                    let chunck = self.cache & mask8(k);
+                    self.cache = (self.cache as u16 >> k) as u8;
+                    value |= (chunck as u64) << shift;
+                    shift += k;
+                }
+            }
+
+            self.bits -= k;
+            n -= k;
+        }
+
+        Some(value)
+    }
+
+    /// Read a single bit.
+    #[inline]
+    pub fn read_bit(&mut self) -> Option<bool> {
+        self.read_bits(1).map(|b| b == 1)
+    }
+
+    /// Discard the unread bits of the current byte so that the next read starts at a byte
+    /// boundary.
+    #[inline]
+    pub fn align(&mut self) {
+        self.cache = 0;
+        self.bits = 0;
+    }
+
+    /// Return `true` if the reader is positioned at a byte boundary.
+    #[inline]
+    pub fn is_aligned(&self) -> bool {
+        self.bits == 0
+    }
+
+    /// Consume `self` and return the inner buffer. The unread bits of the current byte are lost.
+    #[inline]
+    pub fn into_inner(self) -> B {
+        self.inner
+    }
+}
+
+/// A writer of bit fields on top of a `BufMut`.
+///
+/// Bits are buffered until a full byte is available. Use [`BitWriter::flush`] or
+/// [`BitWriter::into_inner`] to write the last partial byte, padded with zeros.
+///
+/// # Example
+///
+/// ```
+/// use bytes::{BitOrder, BitWriter};
+///
+/// let mut writer = BitWriter::new(Vec::new(), BitOrder::MsbFirst);
+///
+/// writer.write_bits(0, 1);
+/// writer.write_bits(3, 2);
+/// writer.write_bits(7, 5);
+/// writer.write_bits(1, 1);
+///
+/// assert_eq!(writer.into_inner(), [0x67, 0x80]);
+/// ```
+#[derive(Debug)]
+pub struct BitWriter<B> {
+    inner: B,
+    order: BitOrder,
+    /// The pending bits of the current byte, already at their final position
+    cache: u8,
+    /// The number of pending bits in `self.cache`
+    bits: u32,
+}
+
+impl<B: BufMut> BitWriter<B> {
+    /// Create a new `BitWriter` writing into `inner` in the given bit `order`.
+    #[inline]
+    pub fn new(inner: B, order: BitOrder) -> BitWriter<B> {
+        BitWriter {
+            inner,
+            order,
+            cache: 0,
+            bits: 0,
+        }
+    }
+
+    /// Write the `n` low bits of `value`.
+    ///
+    /// # Panics
+    ///
+    /// This panics if `n > 64` or if the inner buffer is full.
+    pub fn write_bits(&mut self, mut value: u64, mut n: u32) {
+        assert!(n <= 64, "cannot write more than 64 bits: n ({})", n);
+
+        while n > 0 {
+            let k = n.min(8 - self.bits);
+
+            match self.order {
+                BitOrder::MsbFirst => {
+                    let chunck = (value >> (n - k)) as u8 & mask8(k);
+                    self.cache |= chunck << (8 - self.bits - k);
+                }
+                BitOrder::LsbFirst => {
+                    let chunck = value as u8 & mask8(k);
+                    self.cache |= chunck << self.bits;
+                    value >>= k;
+                }
+            }
+
+            self.bits += k;
+            n -= k;
+
+            if self.bits == 8 {
+                self.flush();
+            }
+        }
+    }
+
+    /// Write a single bit.
+    #[inline]
+    pub fn write_bit(&mut self, bit: bool) {
+        self.write_bits(bit as u64, 1)
+    }
+
+    /// Write the pending bits, padded with zeros up to the next byte boundary. This does nothing
+    /// if the writer is already positioned at a byte boundary.
+    pub fn flush(&mut self) {
+        if self.bits != 0 {
+            self.inner.put_u8(self.cache);
+            self.cache = 0;
+            self.bits = 0;
+        }
+    }
+
+    /// Return `true` if the writer is positioned at a byte boundary.
+    #[inline]
+    pub fn is_aligned(&self) -> bool {
+        self.bits == 0
+    }
+
+    /// Flush the pending bits and return the inner buffer.
+    pub fn into_inner(mut self) -> B {
+        self.flush();
+        self.inner
+    }
+}
+
+#[inline]
+fn mask8(k: u32) -> u8 {
+    (u16::MAX >> (16 - k)) as u8
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn lsb_first() {
+        let mut writer = BitWriter::new(Vec::new(), BitOrder::LsbFirst);
+
+        writer.write_bits(0b1, 1);
+        writer.write_bits(0b10, 2);
+        writer.write_bits(0x1ff, 9);
+        let buf = writer.into_inner();
+
+        assert_eq!(buf, [0b1111_1101, 0b0000_1111]);
+
+        let mut reader = BitReader::new(&buf[..], BitOrder::LsbFirst);
+
+        assert_eq!(reader.read_bit(), Some(true));
+        assert_eq!(reader.read_bits(2), Some(0b10));
+        assert_eq!(reader.read_bits(9), Some(0x1ff));
+        assert_eq!(reader.remaining_bits(), 4);
+    }
+
+    #[test]
+    fn wide_fields() {
+        for order in [BitOrder::MsbFirst, BitOrder::LsbFirst] {
+            let mut writer = BitWriter::new(Vec::new(), order);
+
+            writer.write_bits(0b101, 3);
+            writer.write_bits(0xdead_beef_cafe_f00d, 64);
+            writer.write_bits(0x3_ffff_ffff, 34);
+            let buf = writer.into_inner();
+
+            assert_eq!(buf.len(), 13);
+
+            let mut reader = BitReader::new(&buf[..], order);
+
+            assert_eq!(reader.read_bits(3), Some(0b101));
+            assert_eq!(reader.read_bits(64), Some(0xdead_beef_cafe_f00d));
+            assert_eq!(reader.read_bits(34), Some(0x3_ffff_ffff));
+            assert_eq!(reader.read_bits(4), None);
+            assert_eq!(reader.read_bits(3), Some(0));
+        }
+    }
+
+    #[test]
+    fn align() {
+        let mut reader = BitReader::new(&[0xc0u8, 0x01][..], BitOrder::MsbFirst);
+
+        assert_eq!(reader.read_bits(2), Some(0b11));
+        reader.align();
+        assert!(reader.is_aligned());
+        assert_eq!(reader.read_bits(8), Some(1));
+    }
+}
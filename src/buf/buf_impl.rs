@@ -1,4 +1,13 @@
+use core::cmp;
+
+use super::{Chain, Take};
+use crate::Bytes;
+
 pub trait Buf {
+    /// Return the number of bytes left to read from `self`.
+    ///
+    /// This never exceeds `isize::MAX`, implementations adding the remaining bytes of several
+    /// buffers must not return more.
     fn remaining(&self) -> usize;
 
     fn chuncks(&self) -> &[u8];
@@ -9,6 +18,179 @@ pub trait Buf {
         self.remaining() > 0
     }
 
+    /// Return all the remaining bytes of `self` if they are stored in a single contiguous slice.
+    ///
+    /// This is the mechanism behind the contiguous fast paths of the crate: when it returns a
+    /// slice, [`Buf::copy_to_slice`] and `BufMut::put` copy it with a single `memcpy` and a
+    /// single `advance` instead of iterating over the chunks. `&[u8]`, `Bytes`, `RcBytes` and
+    /// `MaybeOwnedBytes` return their bytes, the adapters forward the call to the buffer they
+    /// wrap. The default implementation returns `Option::None`.
+    #[doc(hidden)]
+    #[inline]
+    fn remaining_slice(&self) -> Option<&[u8]> {
+        None
+    }
+
+    /// Copy bytes from `self` into `dst` and advance `self` by `dst.len()`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::Buf;
+    ///
+    /// let mut buf = &b"hello world"[..];
+    /// let mut dst = [0; 5];
+    ///
+    /// buf.copy_to_slice(&mut dst);
+    ///
+    /// assert_eq!(&dst, b"hello");
+    /// assert_eq!(buf, b" world");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This panics if `self.remaining() < dst.len()`.
+    fn copy_to_slice(&mut self, dst: &mut [u8]) {
+        assert!(
+            self.remaining() >= dst.len(),
+            "not enough remaining bytes: remaining ({}) < needed ({})",
+            self.remaining(),
+            dst.len()
+        );
+
+        if let Some(src) = self.remaining_slice() {
+            dst.copy_from_slice(&src[..dst.len()]);
+            self.advance(dst.len());
+            return;
+        }
+
+        let mut off = 0;
+
+        while off < dst.len() {
+            let chunck = self.chuncks();
+            let count = cmp::min(chunck.len(), dst.len() - off);
+
+            dst[off..off + count].copy_from_slice(&chunck[..count]);
+            self.advance(count);
+            off += count;
+        }
+    }
+
+    /// Copy the first `dst.len()` remaining bytes of `self` into `dst` without advancing `self`.
+    ///
+    /// Like `remaining_slice`, this is a hook for the decoders of the crate and not part of the
+    /// API. The current chunk is the only one reachable without advancing, so the default
+    /// implementation copies from it and forks `self` only if `dst` spans several chunks. The
+    /// buffers of the crate made of several chunks override it to walk their chunks instead.
+    ///
+    /// # Panics
+    ///
+    /// This panics if `self.remaining() < dst.len()`.
+    #[doc(hidden)]
+    fn peek_to_slice(&self, dst: &mut [u8]) {
+        assert!(
+            self.remaining() >= dst.len(),
+            "not enough remaining bytes: remaining ({}) < needed ({})",
+            self.remaining(),
+            dst.len()
+        );
+
+        let chunck = self.chuncks();
+
+        if chunck.len() >= dst.len() {
+            dst.copy_from_slice(&chunck[..dst.len()]);
+        } else {
+            self.fork().copy_to_slice(dst);
+        }
+    }
+
+    /// Copy as many bytes as possible from `self` into `dst`, advance `self` accordingly and
+    /// return the number of bytes copied.
+    ///
+    /// Unlike [`Buf::copy_to_slice`] this never panics, it behaves like `io::Read::read`: `0` is
+    /// returned once `self` has no remaining bytes or if `dst` is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::Buf;
+    ///
+    /// let mut buf = &b"hello"[..];
+    /// let mut dst = [0; 4];
+    ///
+    /// assert_eq!(buf.get_slice_until_full(&mut dst), 4);
+    /// assert_eq!(&dst, b"hell");
+    /// assert_eq!(buf.get_slice_until_full(&mut dst), 1);
+    /// assert_eq!(&dst[..1], b"o");
+    /// assert_eq!(buf.get_slice_until_full(&mut dst), 0);
+    /// ```
+    #[must_use = "the bytes of `dst` past the returned count are left untouched"]
+    fn get_slice_until_full(&mut self, dst: &mut [u8]) -> usize {
+        let count = cmp::min(self.remaining(), dst.len());
+
+        self.copy_to_slice(&mut dst[..count]);
+        count
+    }
+
+    /// Consume `len` bytes of `self` and return them as a `Bytes`.
+    ///
+    /// The default implementation copies the bytes. Buffers backed by a `Bytes` override it to
+    /// return a slice of their buffer instead, and the adapters of the crate forward the call to
+    /// the buffer they wrap whenever the `len` bytes come from it only, so decoders generic over
+    /// `Buf` extract the bytes without copying when the source allows it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::{Buf, Bytes};
+    ///
+    /// let mut buf = Bytes::from(b"hello world".to_vec()).take(8);
+    /// let ptr = buf.chuncks().as_ptr();
+    ///
+    /// let hello = buf.copy_to_bytes(5);
+    /// assert_eq!(hello, "hello");
+    /// assert_eq!(hello.as_ptr(), ptr);
+    /// assert_eq!(buf.remaining(), 3);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This panics if `self.remaining() < len`.
+    fn copy_to_bytes(&mut self, len: usize) -> Bytes {
+        let mut vec = vec![0; len];
+        self.copy_to_slice(&mut vec);
+        Bytes::from(vec)
+    }
+
+    /// Return a `Bytes` holding the remaining bytes of `self`, without advancing `self`.
+    ///
+    /// The fork is an independent cursor over the same data: a parser can speculatively read
+    /// from it and fall back to `self` if the branch fails. Forking a `Bytes` backed buffer
+    /// shares its buffer, other buffers copy their remaining bytes.
+    ///
+    /// There is no default implementation: only the buffer knows how to reach its chunks past
+    /// the current one without advancing. A buffer which can't share its storage copies its
+    /// remaining bytes, e.g. by walking the chunks of a copy of its cursor.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::{Buf, Bytes};
+    ///
+    /// let mut buf = Bytes::from_static(b"\x02hi!");
+    /// let mut fork = buf.fork();
+    ///
+    /// let len = fork.get_u8() as usize;
+    /// assert_eq!(&fork[..len], b"hi");
+    /// assert_eq!(buf.remaining(), 4);
+    ///
+    /// // The branch succeeded, commit it
+    /// buf.advance(1 + len);
+    /// assert_eq!(buf, "!");
+    /// ```
+    #[must_use]
+    fn fork(&self) -> Bytes;
+
     fn get_u8(&mut self) -> u8 {
         assert!(
             self.has_remaining(),
@@ -26,6 +208,83 @@ pub trait Buf {
         );
         self.chuncks()[0]
     }
+
+    /// Create an adapter which reads at most `limit` bytes from `self`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::Buf;
+    ///
+    /// let mut buf = (&b"hello world"[..]).take(5);
+    ///
+    /// assert_eq!(buf.remaining(), 5);
+    /// assert_eq!(buf.chuncks(), b"hello");
+    /// ```
+    #[must_use]
+    fn take(self, limit: usize) -> Take<Self>
+    where
+        Self: Sized,
+    {
+        Take::new(self, limit)
+    }
+
+    /// Create an adapter which chains `self` with `next`. The bytes of `next` are read once all
+    /// the bytes of `self` are consumed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::Buf;
+    ///
+    /// let mut buf = (&b"hello "[..]).chain(&b"world"[..]);
+    ///
+    /// assert_eq!(buf.remaining(), 11);
+    /// ```
+    #[must_use]
+    fn chain<U: Buf>(self, next: U) -> Chain<Self, U>
+    where
+        Self: Sized,
+    {
+        Chain::new(self, next)
+    }
+}
+
+impl<T: Buf + ?Sized> Buf for &mut T {
+    #[inline]
+    fn remaining(&self) -> usize {
+        (**self).remaining()
+    }
+
+    #[inline]
+    fn chuncks(&self) -> &[u8] {
+        (**self).chuncks()
+    }
+
+    #[inline]
+    fn advance(&mut self, cnt: usize) {
+        (**self).advance(cnt)
+    }
+
+    #[inline]
+    fn remaining_slice(&self) -> Option<&[u8]> {
+        (**self).remaining_slice()
+    }
+
+    #[inline]
+    fn peek_to_slice(&self, dst: &mut [u8]) {
+        (**self).peek_to_slice(dst)
+    }
+
+    #[inline]
+    fn fork(&self) -> Bytes {
+        (**self).fork()
+    }
+
+    #[inline]
+    fn copy_to_bytes(&mut self, len: usize) -> Bytes {
+        (**self).copy_to_bytes(len)
+    }
 }
 
 impl Buf for &[u8] {
@@ -40,4 +299,38 @@ impl Buf for &[u8] {
     fn chuncks(&self) -> &[u8] {
         self
     }
+
+    fn fork(&self) -> Bytes {
+        Bytes::copy_from_slice(self)
+    }
+
+    #[inline]
+    fn remaining_slice(&self) -> Option<&[u8]> {
+        Some(self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Buf, BufMut, Bytes};
+
+    #[test]
+    fn contiguous_fast_path_matches_chunck_loop() {
+        let mut contiguous = Bytes::from_static(b"hello world").take(8);
+        let mut chained = Bytes::from_static(b"hel").chain(Bytes::from_static(b"lo world"));
+
+        assert!(contiguous.remaining_slice().is_some());
+        assert!(chained.remaining_slice().is_none());
+
+        let mut a = [0; 7];
+        let mut b = [0; 7];
+        contiguous.copy_to_slice(&mut a);
+        chained.copy_to_slice(&mut b);
+        assert_eq!(a, b);
+
+        let mut dst = Vec::new().limit(16);
+        dst.put(contiguous);
+        dst.put(chained);
+        assert_eq!(dst.into_inner(), b"oorld");
+    }
 }
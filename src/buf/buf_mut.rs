@@ -2,9 +2,14 @@ use alloc::vec::Vec;
 use core::{cmp, ptr};
 
 use super::uninit_slice::UninitSlice;
-use super::Buf;
+use super::{Buf, Chain, Limit};
 
 pub trait BufMut {
+    /// Return the number of bytes which can still be written into `self`.
+    ///
+    /// Growable buffers return the space left before reaching `isize::MAX` bytes, the greatest
+    /// allocation size. This never exceeds `isize::MAX`, so it can be added to a len without
+    /// overflowing.
     fn remaining_mut(&self) -> usize;
 
     fn has_remaining_mut(&self) -> bool {
@@ -21,6 +26,40 @@ pub trait BufMut {
     /// `put` like functions.
     unsafe fn advance(&mut self, count: usize);
 
+    /// Return the writable chunk starting `offset` bytes after the current position.
+    ///
+    /// The returned chunk can be split into disjoint [`UninitSlice`]s with
+    /// [`UninitSlice::split_at_mut`] or [`UninitSlice::chunks_mut`] to be filled by several
+    /// threads, then [`BufMut::commit_parallel`] marks the written bytes as initialized.
+    ///
+    /// # Panics
+    ///
+    /// This panics if `offset` is greater than the len of the current chunk.
+    fn chunk_mut_at(&mut self, offset: usize) -> &mut UninitSlice {
+        let chunk = self.chuncks_mut();
+
+        assert!(
+            offset <= chunk.len(),
+            "offset out of bounds: offset ({}) > chunk len ({})",
+            offset,
+            chunk.len()
+        );
+
+        &mut chunk[offset..]
+    }
+
+    /// Advance the buffer of `total` bytes once they were written in parallel in the slices
+    /// obtained from [`BufMut::chunk_mut_at`].
+    ///
+    /// # Safety
+    ///
+    /// All the `total` bytes following the current position must have been initialized, and
+    /// the threads writing them must have been joined.
+    #[inline]
+    unsafe fn commit_parallel(&mut self, total: usize) {
+        self.advance(total)
+    }
+
     fn put<T>(&mut self, mut src: T)
     where
         T: Buf,
@@ -33,6 +72,14 @@ pub trait BufMut {
             src.remaining()
         );
 
+        if let Some(chunck) = src.remaining_slice() {
+            let len = chunck.len();
+
+            self.put_slice(chunck);
+            src.advance(len);
+            return;
+        }
+
         while src.has_remaining() {
             let chunck = src.chuncks();
             let dst = self.chuncks_mut();
@@ -72,16 +119,207 @@ pub trait BufMut {
         }
     }
 
+    /// Write `count` repetitions of `pattern` into `self`, e.g. for padding or test vectors.
+    ///
+    /// The pattern is copied once into each chunk, then the written bytes are copied after
+    /// themselves doubling their len every time. A large fill takes a few large copies instead
+    /// of one small copy per repetition.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::{BufMut, BytesMut};
+    ///
+    /// let mut buf = BytesMut::new();
+    /// buf.put_slice_repeated(b"ab", 3);
+    ///
+    /// assert_eq!(buf.as_ref(), b"ababab");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This panics if `pattern.len() * count` overflows or if `self` does not have enough
+    /// remaining space for it.
+    fn put_slice_repeated(&mut self, pattern: &[u8], count: usize) {
+        let total = pattern
+            .len()
+            .checked_mul(count)
+            .expect("repeat len overflow");
+
+        assert!(
+            self.remaining_mut() >= total,
+            "not enough space remaining in BufMut: remaining ({}) < needed ({})",
+            self.remaining_mut(),
+            total
+        );
+
+        self.reserve_hint(total);
+
+        let mut written = 0;
+
+        while written < total {
+            let dst = self.chuncks_mut();
+            let len = cmp::min(dst.len(), total - written);
+            let phase = written % pattern.len();
+
+            unsafe {
+                let dst = dst.as_mut_ptr();
+
+                // Seed the chunk with one period of the pattern, starting where the previous
+                // chunk stopped
+                let head = cmp::min(pattern.len() - phase, len);
+                ptr::copy_nonoverlapping(pattern[phase..].as_ptr(), dst, head);
+
+                let tail = cmp::min(phase, len - head);
+                ptr::copy_nonoverlapping(pattern.as_ptr(), dst.add(head), tail);
+
+                // The filled len is a multiple of the period, the copies keep the bytes periodic
+                let mut filled = head + tail;
+
+                while filled < len {
+                    let count = cmp::min(filled, len - filled);
+                    ptr::copy_nonoverlapping(dst, dst.add(filled), count);
+                    filled += count;
+                }
+
+                self.advance(len);
+            }
+
+            written += len;
+        }
+    }
+
+    /// Write all the buffers of `iter` into `self`.
+    ///
+    /// The total number of bytes is computed with [`Buf::remaining`] on a copy of the iterator
+    /// before copying anything, so that growable buffers reserve their capacity once instead of
+    /// once per item. The iterator must be cheap to clone, e.g. an iterator over slices or
+    /// `Bytes`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::{BufMut, BytesMut};
+    ///
+    /// let mut buf = BytesMut::new();
+    /// buf.put_many([&b"hello"[..], b" ", b"world"]);
+    ///
+    /// assert_eq!(buf.as_ref(), b"hello world");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This panics if the total len of the buffers overflows or if `self` does not have enough
+    /// remaining space for all of them.
+    fn put_many<I>(&mut self, iter: I)
+    where
+        I: IntoIterator,
+        I::IntoIter: Clone,
+        I::Item: Buf,
+        Self: Sized,
+    {
+        let iter = iter.into_iter();
+        let total = iter
+            .clone()
+            .try_fold(0usize, |total, buf| total.checked_add(buf.remaining()))
+            .filter(|total| *total <= crate::MAX_LEN)
+            .expect("put_many len overflow");
+
+        assert!(
+            self.remaining_mut() >= total,
+            "not enough space remaining in BufMut: remaining ({}) < needed ({})",
+            self.remaining_mut(),
+            total
+        );
+
+        self.reserve_hint(total);
+
+        for buf in iter {
+            self.put(buf);
+        }
+    }
+
+    /// Reserve room for at least `additional` more bytes if `self` is able to grow.
+    ///
+    /// This is used by the buffers of the crate to allocate once when the number of bytes to be
+    /// written is known ahead, the default implementation does nothing.
+    #[doc(hidden)]
+    #[inline]
+    fn reserve_hint(&mut self, additional: usize) {
+        let _ = additional;
+    }
+
     fn put_u8(&mut self, byte: u8) {
         let slice = [byte];
         self.put_slice(&slice);
     }
+
+    /// Create an adapter which writes at most `limit` bytes into `self`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::BufMut;
+    ///
+    /// let buf = Vec::new().limit(10);
+    ///
+    /// assert_eq!(buf.remaining_mut(), 10);
+    /// ```
+    #[must_use]
+    fn limit(self, limit: usize) -> Limit<Self>
+    where
+        Self: Sized,
+    {
+        Limit::new(self, limit)
+    }
+
+    /// Create an adapter which chains `self` with `next`. The bytes are written into `next` once
+    /// `self` is full.
+    #[must_use]
+    fn chain_mut<U: BufMut>(self, next: U) -> Chain<Self, U>
+    where
+        Self: Sized,
+    {
+        Chain::new(self, next)
+    }
+}
+
+impl<T: BufMut + ?Sized> BufMut for &mut T {
+    #[inline]
+    fn remaining_mut(&self) -> usize {
+        (**self).remaining_mut()
+    }
+
+    #[inline]
+    fn chuncks_mut(&mut self) -> &mut UninitSlice {
+        (**self).chuncks_mut()
+    }
+
+    #[inline]
+    unsafe fn advance(&mut self, count: usize) {
+        (**self).advance(count)
+    }
+
+    #[inline]
+    fn put_slice(&mut self, src: &[u8]) {
+        (**self).put_slice(src)
+    }
+
+    #[inline]
+    fn put_slice_repeated(&mut self, pattern: &[u8], count: usize) {
+        (**self).put_slice_repeated(pattern, count)
+    }
+
+    #[inline]
+    fn reserve_hint(&mut self, additional: usize) {
+        (**self).reserve_hint(additional)
+    }
 }
 
 impl BufMut for Vec<u8> {
     fn remaining_mut(&self) -> usize {
         // `alloc::vec` ensures that vectors don't allocate more than `isize::MAX` bytes
-        core::isize::MAX as usize - self.len()
+        crate::MAX_LEN - self.len()
     }
 
     unsafe fn advance(&mut self, count: usize) {
@@ -99,6 +337,11 @@ impl BufMut for Vec<u8> {
     }
 
     fn chuncks_mut(&mut self) -> &mut UninitSlice {
+        // The buffer grows on demand so we make sure that the returned chunck is never empty
+        if self.capacity() == self.len() {
+            self.reserve(64);
+        }
+
         let cap = self.capacity();
         let len = self.len();
         let ptr = self.as_mut_ptr();
@@ -125,4 +368,9 @@ impl BufMut for Vec<u8> {
     fn put_slice(&mut self, src: &[u8]) {
         self.extend_from_slice(src);
     }
+
+    #[inline]
+    fn reserve_hint(&mut self, additional: usize) {
+        self.reserve(additional);
+    }
 }
@@ -0,0 +1,217 @@
+use alloc::vec::Vec;
+use core::cmp;
+
+use super::{Buf, BufMut, UninitSlice};
+use crate::Bytes;
+
+/// A `Buf` or `BufMut` adapter which chains two buffers together. All the bytes of the first
+/// buffer are read (or written) before the second one is used.
+///
+/// This struct is created by [`Buf::chain`].
+///
+/// # Example
+///
+/// ```
+/// use bytes::{Buf, Bytes};
+///
+/// let mut chain = Bytes::from_static(b"hello ").chain(Bytes::from_static(b"world"));
+///
+/// assert_eq!(chain.remaining(), 11);
+/// assert_eq!(chain.chuncks(), b"hello ");
+///
+/// chain.advance(6);
+/// assert_eq!(chain.chuncks(), b"world");
+/// ```
+#[derive(Debug)]
+pub struct Chain<A, B> {
+    a: A,
+    b: B,
+}
+
+impl<A, B> Chain<A, B> {
+    #[inline]
+    pub(crate) fn new(a: A, b: B) -> Chain<A, B> {
+        Chain { a, b }
+    }
+
+    /// Get a reference to the first buffer.
+    #[inline]
+    pub fn first_ref(&self) -> &A {
+        &self.a
+    }
+
+    /// Get a mutable reference to the first buffer.
+    #[inline]
+    pub fn first_mut(&mut self) -> &mut A {
+        &mut self.a
+    }
+
+    /// Get a reference to the last buffer.
+    #[inline]
+    pub fn last_ref(&self) -> &B {
+        &self.b
+    }
+
+    /// Get a mutable reference to the last buffer.
+    #[inline]
+    pub fn last_mut(&mut self) -> &mut B {
+        &mut self.b
+    }
+
+    /// Consume `self` and return the two inner buffers.
+    #[inline]
+    pub fn into_inner(self) -> (A, B) {
+        (self.a, self.b)
+    }
+}
+
+impl<A: Buf, B: Buf> Buf for Chain<A, B> {
+    fn remaining(&self) -> usize {
+        // The buffers may generate their bytes instead of holding them in memory, each can
+        // claim up to `isize::MAX` bytes
+        cmp::min(
+            self.a.remaining().saturating_add(self.b.remaining()),
+            crate::MAX_LEN,
+        )
+    }
+
+    fn chuncks(&self) -> &[u8] {
+        if self.a.has_remaining() {
+            self.a.chuncks()
+        } else {
+            self.b.chuncks()
+        }
+    }
+
+    fn peek_to_slice(&self, dst: &mut [u8]) {
+        let (first, last) = dst.split_at_mut(cmp::min(self.a.remaining(), dst.len()));
+
+        self.a.peek_to_slice(first);
+        self.b.peek_to_slice(last);
+    }
+
+    fn fork(&self) -> Bytes {
+        let a = self.a.fork();
+        let b = self.b.fork();
+
+        if b.is_empty() {
+            return a;
+        }
+        if a.is_empty() {
+            return b;
+        }
+
+        let mut vec = Vec::with_capacity(a.len() + b.len());
+        vec.extend_from_slice(&a);
+        vec.extend_from_slice(&b);
+        Bytes::from(vec)
+    }
+
+    fn copy_to_bytes(&mut self, len: usize) -> Bytes {
+        let a_rem = self.a.remaining();
+
+        // Forward to the buffer holding all the bytes so that it can avoid the copy
+        if a_rem >= len {
+            return self.a.copy_to_bytes(len);
+        }
+        if a_rem == 0 {
+            return self.b.copy_to_bytes(len);
+        }
+
+        let mut vec = vec![0; len];
+        self.copy_to_slice(&mut vec);
+        Bytes::from(vec)
+    }
+
+    fn advance(&mut self, mut cnt: usize) {
+        let a_rem = self.a.remaining();
+
+        if a_rem != 0 {
+            if a_rem >= cnt {
+                self.a.advance(cnt);
+                return;
+            }
+
+            // Consume what is left in `self.a`
+            self.a.advance(a_rem);
+            cnt -= a_rem;
+        }
+
+        self.b.advance(cnt);
+    }
+}
+
+impl<A: BufMut, B: BufMut> BufMut for Chain<A, B> {
+    fn remaining_mut(&self) -> usize {
+        // Two growable buffers can each claim `isize::MAX` bytes of space
+        cmp::min(
+            self.a
+                .remaining_mut()
+                .saturating_add(self.b.remaining_mut()),
+            crate::MAX_LEN,
+        )
+    }
+
+    fn chuncks_mut(&mut self) -> &mut UninitSlice {
+        if self.a.has_remaining_mut() {
+            self.a.chuncks_mut()
+        } else {
+            self.b.chuncks_mut()
+        }
+    }
+
+    unsafe fn advance(&mut self, mut count: usize) {
+        let a_rem = self.a.remaining_mut();
+
+        if a_rem != 0 {
+            if a_rem >= count {
+                self.a.advance(count);
+                return;
+            }
+
+            // Fill what is left in `self.a`
+            self.a.advance(a_rem);
+            count -= a_rem;
+        }
+
+        self.b.advance(count);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// A `Buf` generating `self.0` zero bytes
+    struct Zeros(usize);
+
+    impl Buf for Zeros {
+        fn remaining(&self) -> usize {
+            self.0
+        }
+
+        fn chuncks(&self) -> &[u8] {
+            &[0; 64][..cmp::min(self.0, 64)]
+        }
+
+        fn advance(&mut self, cnt: usize) {
+            self.0 -= cnt;
+        }
+
+        fn fork(&self) -> Bytes {
+            Bytes::from(vec![0; self.0])
+        }
+    }
+
+    #[test]
+    fn remaining_overflow() {
+        let mut chain = Zeros(crate::MAX_LEN).chain(Zeros(crate::MAX_LEN));
+        assert_eq!(chain.remaining(), crate::MAX_LEN);
+
+        chain.advance(crate::MAX_LEN);
+        assert_eq!(chain.remaining(), crate::MAX_LEN);
+
+        chain.advance(10);
+        assert_eq!(chain.remaining(), crate::MAX_LEN - 10);
+    }
+}
@@ -0,0 +1,290 @@
+use super::{Buf, BufMut, UninitSlice};
+use crate::Bytes;
+
+/// A `Buf` adapter which counts the number of bytes consumed from the inner buffer.
+///
+/// The count is the logical offset of the reader in the stream, it can be used to skip padding
+/// relative to the start of the stream with [`CountingBuf::skip_padding`].
+///
+/// # Example
+///
+/// ```
+/// use bytes::{Buf, CountingBuf};
+///
+/// let mut buf = CountingBuf::new(&b"\x01\0\0\0\x02"[..]);
+///
+/// assert_eq!(buf.get_u8(), 1);
+/// assert_eq!(buf.skip_padding(4), 3);
+/// assert_eq!(buf.get_u8(), 2);
+/// assert_eq!(buf.count(), 5);
+/// ```
+#[derive(Debug)]
+pub struct CountingBuf<B> {
+    inner: B,
+    count: usize,
+}
+
+impl<B> CountingBuf<B> {
+    /// Create a new `CountingBuf` wrapping `inner` with a count of `0`.
+    #[inline]
+    pub fn new(inner: B) -> CountingBuf<B> {
+        CountingBuf { inner, count: 0 }
+    }
+
+    /// Return the number of bytes consumed since the creation of `self`.
+    #[inline]
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Get a reference to the inner buffer.
+    #[inline]
+    pub fn get_ref(&self) -> &B {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the inner buffer.
+    ///
+    /// Bytes consumed directly from the inner buffer are not counted.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut B {
+        &mut self.inner
+    }
+
+    /// Consume `self` and return the inner buffer.
+    #[inline]
+    pub fn into_inner(self) -> B {
+        self.inner
+    }
+}
+
+impl<B: Buf> CountingBuf<B> {
+    /// Skip the padding bytes needed to align the current offset on `align` bytes and return the
+    /// number of skipped bytes.
+    ///
+    /// # Panics
+    ///
+    /// This panics if `align` is `0` or if there is not enough remaining bytes to skip.
+    pub fn skip_padding(&mut self, align: usize) -> usize {
+        let pad = padding_len(self.count, align);
+
+        assert!(
+            self.remaining() >= pad,
+            "not enough remaining bytes to skip padding: remaining ({}) < padding ({})",
+            self.remaining(),
+            pad
+        );
+
+        self.advance(pad);
+        pad
+    }
+}
+
+impl<B: Buf> Buf for CountingBuf<B> {
+    #[inline]
+    fn remaining(&self) -> usize {
+        self.inner.remaining()
+    }
+
+    #[inline]
+    fn chuncks(&self) -> &[u8] {
+        self.inner.chuncks()
+    }
+
+    #[inline]
+    fn remaining_slice(&self) -> Option<&[u8]> {
+        self.inner.remaining_slice()
+    }
+
+    #[inline]
+    fn peek_to_slice(&self, dst: &mut [u8]) {
+        self.inner.peek_to_slice(dst)
+    }
+
+    #[inline]
+    fn fork(&self) -> Bytes {
+        self.inner.fork()
+    }
+
+    fn copy_to_bytes(&mut self, len: usize) -> Bytes {
+        let bytes = self.inner.copy_to_bytes(len);
+        self.count += len;
+        bytes
+    }
+
+    fn advance(&mut self, cnt: usize) {
+        self.inner.advance(cnt);
+        self.count += cnt;
+    }
+}
+
+/// A `BufMut` adapter which counts the number of bytes written into the inner buffer.
+///
+/// The count is the logical offset of the writer in the stream, it can be used to write padding
+/// relative to the start of the stream with [`CountingBufMut::put_padding`].
+///
+/// # Example
+///
+/// ```
+/// use bytes::{BufMut, CountingBufMut};
+///
+/// let mut buf = CountingBufMut::new(Vec::new());
+///
+/// buf.put_slice(b"abcde");
+/// assert_eq!(buf.put_padding(4, 0), 3);
+/// assert_eq!(buf.count(), 8);
+/// assert_eq!(buf.into_inner(), b"abcde\0\0\0");
+/// ```
+#[derive(Debug)]
+pub struct CountingBufMut<B> {
+    inner: B,
+    count: usize,
+}
+
+impl<B> CountingBufMut<B> {
+    /// Create a new `CountingBufMut` wrapping `inner` with a count of `0`.
+    #[inline]
+    pub fn new(inner: B) -> CountingBufMut<B> {
+        CountingBufMut { inner, count: 0 }
+    }
+
+    /// Return the number of bytes written since the creation of `self`.
+    #[inline]
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Get a reference to the inner buffer.
+    #[inline]
+    pub fn get_ref(&self) -> &B {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the inner buffer.
+    ///
+    /// Bytes written directly into the inner buffer are not counted.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut B {
+        &mut self.inner
+    }
+
+    /// Consume `self` and return the inner buffer.
+    #[inline]
+    pub fn into_inner(self) -> B {
+        self.inner
+    }
+}
+
+impl<B: BufMut> CountingBufMut<B> {
+    /// Write `fill` bytes until the current offset is aligned on `align` bytes and return the
+    /// number of written bytes.
+    ///
+    /// # Panics
+    ///
+    /// This panics if `align` is `0` or if there is not enough space remaining to write the
+    /// padding.
+    pub fn put_padding(&mut self, align: usize, fill: u8) -> usize {
+        let pad = padding_len(self.count, align);
+
+        for _ in 0..pad {
+            self.put_u8(fill);
+        }
+
+        pad
+    }
+}
+
+impl<B: BufMut> BufMut for CountingBufMut<B> {
+    #[inline]
+    fn remaining_mut(&self) -> usize {
+        self.inner.remaining_mut()
+    }
+
+    #[inline]
+    fn chuncks_mut(&mut self) -> &mut UninitSlice {
+        self.inner.chuncks_mut()
+    }
+
+    unsafe fn advance(&mut self, count: usize) {
+        self.inner.advance(count);
+        self.count += count;
+    }
+
+    #[inline]
+    fn reserve_hint(&mut self, additional: usize) {
+        self.inner.reserve_hint(additional)
+    }
+}
+
+/// Return the number of bytes needed to align `offset` on `align` bytes.
+fn padding_len(offset: usize, align: usize) -> usize {
+    assert!(align > 0, "invalid alignment: align must be greater than 0");
+
+    (align - offset % align) % align
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn padding_len() {
+        assert_eq!(super::padding_len(0, 4), 0);
+        assert_eq!(super::padding_len(1, 4), 3);
+        assert_eq!(super::padding_len(4, 4), 0);
+        assert_eq!(super::padding_len(13, 8), 3);
+        assert_eq!(super::padding_len(7, 1), 0);
+    }
+
+    #[test]
+    fn put_padding() {
+        let mut buf = CountingBufMut::new(Vec::new());
+
+        assert_eq!(buf.put_padding(4, 0xff), 0);
+        buf.put_u8(1);
+        assert_eq!(buf.put_padding(8, 0xff), 7);
+        assert_eq!(buf.put_padding(8, 0xff), 0);
+        assert_eq!(
+            buf.into_inner(),
+            [1, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]
+        );
+    }
+
+    #[test]
+    fn compose_with_take_and_chain() {
+        use crate::Bytes;
+
+        let chain = Bytes::from_static(b"abc").chain(Bytes::from_static(b"defgh"));
+        let mut buf = CountingBuf::new(chain.take(6));
+
+        assert_eq!(buf.get_u8(), b'a');
+        buf.advance(4);
+        assert_eq!(buf.count(), 5);
+        assert_eq!(buf.chuncks(), b"f");
+
+        let mut inner = CountingBuf::new(&b"abcdef"[..]).take(2);
+        inner.advance(2);
+        assert_eq!(inner.get_ref().count(), 2);
+        assert!(!inner.has_remaining());
+    }
+
+    #[test]
+    fn compose_with_limit() {
+        let mut buf = CountingBufMut::new(Vec::new().limit(4));
+
+        buf.put_slice(b"ab");
+        assert_eq!(buf.put_padding(4, 0), 2);
+        assert_eq!(buf.remaining_mut(), 0);
+        assert_eq!(buf.count(), 4);
+        assert_eq!(buf.into_inner().into_inner(), b"ab\0\0");
+    }
+
+    #[test]
+    #[should_panic]
+    fn skip_padding_not_enough() {
+        let mut buf = CountingBuf::new(&b"\x01\0"[..]);
+
+        buf.get_u8();
+        buf.skip_padding(4);
+    }
+}
@@ -0,0 +1,90 @@
+use alloc::vec::Vec;
+
+use super::{CountingBufMut, Limit};
+use crate::{Bytes, BytesMut};
+
+/// A sink which must be finalized once everything was written into it.
+///
+/// Finishing a sink completes what it wrote, e.g. the length of a frame or the closing quote
+/// of a string, and returns its output. Generic encoding code can take any `BufMut + Finish`
+/// and hand the finished output over without knowing how the sink is built.
+///
+/// The adapters of the crate finish their inner sink.
+///
+/// # Example
+///
+/// ```
+/// use bytes::codec::frame::LengthPrefix;
+/// use bytes::{BufMut, BytesMut, Finish};
+///
+/// fn encode<S: BufMut + Finish>(mut sink: S) -> S::Output {
+///     sink.put_slice(b"hello");
+///     sink.finish()
+/// }
+///
+/// assert_eq!(encode(BytesMut::new()), "hello");
+///
+/// let mut buf = BytesMut::new();
+/// assert_eq!(encode(buf.length_prefixed(LengthPrefix::U8)), 5);
+/// assert_eq!(buf.as_ref(), b"\x05hello");
+/// ```
+pub trait Finish {
+    /// The value returned once the sink is finished.
+    type Output;
+
+    /// Finalize the sink and return its output.
+    fn finish(self) -> Self::Output;
+}
+
+impl Finish for BytesMut {
+    type Output = Bytes;
+
+    #[inline]
+    fn finish(self) -> Bytes {
+        self.freeze()
+    }
+}
+
+impl Finish for Vec<u8> {
+    type Output = Vec<u8>;
+
+    #[inline]
+    fn finish(self) -> Vec<u8> {
+        self
+    }
+}
+
+impl<B: Finish> Finish for Limit<B> {
+    type Output = B::Output;
+
+    #[inline]
+    fn finish(self) -> B::Output {
+        self.into_inner().finish()
+    }
+}
+
+impl<B: Finish> Finish for CountingBufMut<B> {
+    type Output = B::Output;
+
+    #[inline]
+    fn finish(self) -> B::Output {
+        self.into_inner().finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::BufMut;
+
+    #[test]
+    fn adapters() {
+        let mut sink = BytesMut::new().limit(4);
+        sink.put_slice(b"abc");
+        assert_eq!(sink.finish(), "abc");
+
+        let mut sink = CountingBufMut::new(Vec::new());
+        sink.put_slice(&[1, 2]);
+        assert_eq!(sink.finish(), [1, 2]);
+    }
+}
@@ -0,0 +1,81 @@
+use core::cmp;
+
+use super::{BufMut, UninitSlice};
+
+/// A `BufMut` adapter which limits the amount of bytes that can be written to an underlying
+/// buffer.
+///
+/// This struct is created by [`BufMut::limit`].
+#[derive(Debug)]
+pub struct Limit<B> {
+    inner: B,
+    limit: usize,
+}
+
+impl<B> Limit<B> {
+    #[inline]
+    pub(crate) fn new(inner: B, limit: usize) -> Limit<B> {
+        Limit { inner, limit }
+    }
+
+    /// Return the maximum number of bytes that can still be written.
+    #[inline]
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Set the maximum number of bytes that can be written.
+    #[inline]
+    pub fn set_limit(&mut self, limit: usize) {
+        self.limit = limit
+    }
+
+    /// Get a reference to the inner buffer.
+    #[inline]
+    pub fn get_ref(&self) -> &B {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the inner buffer.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut B {
+        &mut self.inner
+    }
+
+    /// Consume `self` and return the inner buffer.
+    #[inline]
+    pub fn into_inner(self) -> B {
+        self.inner
+    }
+}
+
+impl<B: BufMut> BufMut for Limit<B> {
+    #[inline]
+    fn remaining_mut(&self) -> usize {
+        cmp::min(self.inner.remaining_mut(), self.limit)
+    }
+
+    fn chuncks_mut(&mut self) -> &mut UninitSlice {
+        let chunck = self.inner.chuncks_mut();
+        let len = cmp::min(chunck.len(), self.limit);
+
+        &mut chunck[..len]
+    }
+
+    unsafe fn advance(&mut self, count: usize) {
+        assert!(
+            count <= self.limit,
+            "cannot advance past the limit: limit ({}) < count ({})",
+            self.limit,
+            count
+        );
+
+        self.inner.advance(count);
+        self.limit -= count;
+    }
+
+    #[inline]
+    fn reserve_hint(&mut self, additional: usize) {
+        self.inner.reserve_hint(cmp::min(additional, self.limit))
+    }
+}
@@ -1,7 +1,21 @@
+mod bits;
 mod buf_impl;
 mod buf_mut;
+mod chain;
+mod counting;
+mod finish;
+mod limit;
+mod offset;
+mod take;
 mod uninit_slice;
 
+pub use bits::{BitOrder, BitReader, BitWriter};
 pub use buf_impl::Buf;
 pub use buf_mut::BufMut;
-pub use uninit_slice::UninitSlice;
+pub use chain::Chain;
+pub use counting::{CountingBuf, CountingBufMut};
+pub use finish::Finish;
+pub use limit::Limit;
+pub use offset::OffsetTrackingBuf;
+pub use take::Take;
+pub use uninit_slice::{TrackedUninitSlice, UninitSlice};
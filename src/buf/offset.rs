@@ -0,0 +1,230 @@
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+use core::cmp;
+
+use super::Buf;
+use crate::Bytes;
+
+/// A `Buf` reading the buffers fed into it as a single stream and tracking the absolute offset
+/// of the reader since the start of the stream.
+///
+/// Only the buffers which were not fully consumed are kept, so a decoder can report the stream
+/// offset of an error while the data before it was released long ago.
+///
+/// # Example
+///
+/// ```
+/// use bytes::{Buf, Bytes, OffsetTrackingBuf};
+///
+/// let mut stream = OffsetTrackingBuf::new();
+/// stream.push(Bytes::from_static(b"GET / HTTP/1.1\r\n"));
+/// stream.advance(16);
+///
+/// stream.push(Bytes::from_static(b"Host: \x00"));
+/// stream.advance(6);
+///
+/// assert_eq!(stream.get_u8(), 0);
+/// assert_eq!(stream.offset(), 23);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct OffsetTrackingBuf {
+    bufs: VecDeque<Bytes>,
+    remaining: usize,
+    offset: u64,
+}
+
+impl OffsetTrackingBuf {
+    /// Create a new `OffsetTrackingBuf` at the start of a stream.
+    #[inline]
+    pub fn new() -> OffsetTrackingBuf {
+        OffsetTrackingBuf::with_offset(0)
+    }
+
+    /// Create a new `OffsetTrackingBuf` whose next byte is at `offset` in the stream, e.g. when
+    /// resuming a stream.
+    #[inline]
+    pub fn with_offset(offset: u64) -> OffsetTrackingBuf {
+        OffsetTrackingBuf {
+            bufs: VecDeque::new(),
+            remaining: 0,
+            offset,
+        }
+    }
+
+    /// Append `buf` at the end of the stream. Empty buffers are ignored.
+    pub fn push(&mut self, buf: Bytes) {
+        if buf.is_empty() {
+            return;
+        }
+
+        self.remaining += buf.len();
+        self.bufs.push_back(buf);
+    }
+
+    /// Return the absolute offset of the next byte to read since the start of the stream.
+    #[inline]
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Return the absolute offset of the byte `pos` bytes after the next byte to read, e.g. to
+    /// report the position of an error found while peeking at the remaining bytes.
+    #[inline]
+    pub fn offset_of(&self, pos: usize) -> u64 {
+        self.offset + pos as u64
+    }
+
+    /// Return the total number of bytes fed into the stream, i.e. the offset of its end.
+    #[inline]
+    pub fn stream_len(&self) -> u64 {
+        self.offset + self.remaining as u64
+    }
+}
+
+impl Buf for OffsetTrackingBuf {
+    #[inline]
+    fn remaining(&self) -> usize {
+        self.remaining
+    }
+
+    #[inline]
+    fn chuncks(&self) -> &[u8] {
+        self.bufs.front().map_or(&[], |buf| buf.as_slice())
+    }
+
+    fn peek_to_slice(&self, dst: &mut [u8]) {
+        assert!(
+            self.remaining >= dst.len(),
+            "not enough remaining bytes: remaining ({}) < needed ({})",
+            self.remaining,
+            dst.len()
+        );
+
+        let mut off = 0;
+
+        for buf in &self.bufs {
+            if off == dst.len() {
+                break;
+            }
+
+            let count = cmp::min(buf.len(), dst.len() - off);
+            dst[off..off + count].copy_from_slice(&buf[..count]);
+            off += count;
+        }
+    }
+
+    fn fork(&self) -> Bytes {
+        match self.bufs.len() {
+            0 => Bytes::new(),
+            1 => self.bufs[0].clone(),
+            _ => {
+                let mut vec = Vec::with_capacity(self.remaining);
+                for buf in &self.bufs {
+                    vec.extend_from_slice(buf);
+                }
+                Bytes::from(vec)
+            }
+        }
+    }
+
+    fn copy_to_bytes(&mut self, len: usize) -> Bytes {
+        match self.bufs.front_mut() {
+            Some(front) if len <= front.len() => {
+                let bytes = front.split_to(len);
+                self.remaining -= len;
+                self.offset += len as u64;
+
+                if front.is_empty() {
+                    self.bufs.pop_front();
+                }
+                bytes
+            }
+            _ => {
+                let mut vec = vec![0; len];
+                self.copy_to_slice(&mut vec);
+                Bytes::from(vec)
+            }
+        }
+    }
+
+    fn advance(&mut self, mut cnt: usize) {
+        assert!(
+            cnt <= self.remaining,
+            "cannot advance past remaining: cnt ({}) > remaining ({})",
+            cnt,
+            self.remaining
+        );
+
+        self.remaining -= cnt;
+        self.offset += cnt as u64;
+
+        while cnt > 0 {
+            let front = self.bufs.front_mut().unwrap();
+
+            if cnt < front.len() {
+                front.advance(cnt);
+                return;
+            }
+
+            // Release the buffers as soon as they are consumed
+            cnt -= front.len();
+            self.bufs.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn stream() -> OffsetTrackingBuf {
+        let mut stream = OffsetTrackingBuf::with_offset(1_000);
+
+        stream.push(Bytes::from_static(b"hello"));
+        stream.push(Bytes::new());
+        stream.push(Bytes::from_static(b" wonderful"));
+        stream.push(Bytes::from_static(b" world"));
+        stream
+    }
+
+    #[test]
+    fn offsets() {
+        let mut stream = stream();
+
+        assert_eq!(stream.stream_len(), 1_021);
+
+        stream.advance(7);
+        assert_eq!(stream.offset(), 1_007);
+        assert_eq!(stream.chuncks(), b"onderful");
+
+        let pos = stream.chuncks().iter().position(|b| *b == b'f').unwrap();
+        assert_eq!(stream.offset_of(pos), 1_012);
+
+        stream.advance(14);
+        assert_eq!(stream.offset(), 1_021);
+        assert!(stream.bufs.is_empty());
+
+        stream.push(Bytes::from_static(b"!"));
+        assert_eq!(stream.get_u8(), b'!');
+        assert_eq!(stream.offset(), 1_022);
+    }
+
+    #[test]
+    fn copy_to_bytes() {
+        let mut stream = stream();
+
+        let hello = stream.copy_to_bytes(5);
+        assert_eq!(hello, "hello"[..]);
+        assert_eq!(hello.as_ptr(), b"hello".as_ptr());
+        assert_eq!(stream.offset(), 1_005);
+
+        assert_eq!(stream.copy_to_bytes(13), " wonderful wo"[..]);
+        assert_eq!(stream.offset(), 1_018);
+        assert_eq!(stream.remaining(), 3);
+    }
+
+    #[test]
+    fn laws() {
+        crate::testsuite::assert_buf_laws(stream, b"hello wonderful world");
+    }
+}
@@ -0,0 +1,109 @@
+use core::cmp;
+
+use super::Buf;
+use crate::Bytes;
+
+/// A `Buf` adapter which limits the bytes read from an underlying buffer.
+///
+/// This struct is created by [`Buf::take`].
+#[derive(Debug)]
+pub struct Take<B> {
+    inner: B,
+    limit: usize,
+}
+
+impl<B> Take<B> {
+    #[inline]
+    pub(crate) fn new(inner: B, limit: usize) -> Take<B> {
+        Take { inner, limit }
+    }
+
+    /// Return the maximum number of bytes that can still be read.
+    #[inline]
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Set the maximum number of bytes that can be read.
+    #[inline]
+    pub fn set_limit(&mut self, limit: usize) {
+        self.limit = limit
+    }
+
+    /// Get a reference to the inner buffer.
+    #[inline]
+    pub fn get_ref(&self) -> &B {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the inner buffer.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut B {
+        &mut self.inner
+    }
+
+    /// Consume `self` and return the inner buffer.
+    #[inline]
+    pub fn into_inner(self) -> B {
+        self.inner
+    }
+}
+
+impl<B: Buf> Buf for Take<B> {
+    #[inline]
+    fn remaining(&self) -> usize {
+        cmp::min(self.inner.remaining(), self.limit)
+    }
+
+    fn chuncks(&self) -> &[u8] {
+        let chunck = self.inner.chuncks();
+        &chunck[..cmp::min(chunck.len(), self.limit)]
+    }
+
+    fn remaining_slice(&self) -> Option<&[u8]> {
+        let src = self.inner.remaining_slice()?;
+        Some(&src[..cmp::min(src.len(), self.limit)])
+    }
+
+    fn peek_to_slice(&self, dst: &mut [u8]) {
+        assert!(
+            dst.len() <= self.limit,
+            "cannot peek past the limit: limit ({}) < needed ({})",
+            self.limit,
+            dst.len()
+        );
+
+        self.inner.peek_to_slice(dst)
+    }
+
+    fn fork(&self) -> Bytes {
+        let mut bytes = self.inner.fork();
+        bytes.truncate(self.limit);
+        bytes
+    }
+
+    fn copy_to_bytes(&mut self, len: usize) -> Bytes {
+        assert!(
+            len <= self.limit,
+            "cannot advance past the limit: limit ({}) < count ({})",
+            self.limit,
+            len
+        );
+
+        let bytes = self.inner.copy_to_bytes(len);
+        self.limit -= len;
+        bytes
+    }
+
+    fn advance(&mut self, cnt: usize) {
+        assert!(
+            cnt <= self.limit,
+            "cannot advance past the limit: limit ({}) < count ({})",
+            self.limit,
+            cnt
+        );
+
+        self.inner.advance(cnt);
+        self.limit -= cnt;
+    }
+}
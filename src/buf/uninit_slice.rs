@@ -1,5 +1,8 @@
+use core::fmt;
 use core::mem::MaybeUninit;
-use core::ops::{Index, IndexMut, Range, RangeFrom, RangeFull};
+use core::ops::{
+    Index, IndexMut, Range, RangeFrom, RangeFull, RangeInclusive, RangeTo, RangeToInclusive,
+};
 
 pub struct UninitSlice([MaybeUninit<u8>]);
 
@@ -16,6 +19,12 @@ impl UninitSlice {
         unsafe { &mut *(slice as *mut [MaybeUninit<u8>] as *mut UninitSlice) }
     }
 
+    /// Create a `&mut UninitSlice` from a pointer and a len.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be valid for writes of `len` bytes for the whole lifetime `'a`, and the memory
+    /// must not be accessed through any other pointer during this lifetime.
     pub unsafe fn from_raw_parts<'a>(ptr: *mut u8, len: usize) -> &'a mut UninitSlice {
         let slice: &mut [MaybeUninit<u8>] = core::slice::from_raw_parts_mut(ptr as _, len);
         UninitSlice::from_slice(slice)
@@ -55,9 +64,199 @@ impl UninitSlice {
         unsafe { self[index..].as_mut_ptr().write(byte) }
     }
 
+    /// Return a raw pointer to the start of the slice.
+    ///
+    /// # Safety
+    ///
+    /// The bytes may be uninitialized, they must not be read through the returned pointer before
+    /// being written. Only the `self.len()` bytes of the slice may be accessed.
     pub unsafe fn as_mut_ptr(&mut self) -> *mut u8 {
         self.0.as_mut_ptr() as *mut u8
     }
+
+    /// Split `self` into two disjoint slices at `mid`. The first one contains the bytes in
+    /// `0..mid` and the second one the bytes in `mid..len`.
+    ///
+    /// # Panics
+    ///
+    /// This panics if `mid > self.len()`.
+    pub fn split_at_mut(&mut self, mid: usize) -> (&mut UninitSlice, &mut UninitSlice) {
+        assert!(
+            mid <= self.len(),
+            "index out of bounds: mid ({}) > len ({})",
+            mid,
+            self.len()
+        );
+
+        let (head, tail) = self.0.split_at_mut(mid);
+        (UninitSlice::from_slice(head), UninitSlice::from_slice(tail))
+    }
+
+    /// Return an iterator over disjoint slices of `chunk_size` bytes, the last one may be
+    /// shorter. The slices can be sent to different threads to be filled in parallel.
+    ///
+    /// # Panics
+    ///
+    /// This panics if `chunk_size` is `0`.
+    pub fn chunks_mut(&mut self, chunk_size: usize) -> impl Iterator<Item = &mut UninitSlice> {
+        self.0.chunks_mut(chunk_size).map(UninitSlice::from_slice)
+    }
+}
+
+impl fmt::Debug for UninitSlice {
+    /// Format the len of the slice only, its bytes may be uninitialized so they are never read.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UninitSlice")
+            .field("len", &self.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl fmt::Display for UninitSlice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} uninitialized bytes", self.len())
+    }
+}
+
+/// A wrapper around an `UninitSlice` checking, in debug builds, that the bytes committed by a
+/// `BufMut` were written first.
+///
+/// The writes go through the wrapper, which records the high-water mark of the written bytes,
+/// i.e. the end of the furthest write. [`check_commit`] then panics if more bytes are about to
+/// be committed with [`BufMut::advance`] than were written. In release builds nothing is
+/// recorded and the check does nothing.
+///
+/// [`check_commit`]: TrackedUninitSlice::check_commit
+/// [`BufMut::advance`]: crate::BufMut::advance
+///
+/// # Example
+///
+/// ```
+/// use bytes::{BufMut, BytesMut, TrackedUninitSlice};
+///
+/// let mut buf = BytesMut::with_capacity(16);
+///
+/// let mut slice = TrackedUninitSlice::new(buf.chuncks_mut());
+/// slice.write_slice(0, b"hello");
+/// slice.check_commit(5);
+///
+/// // Safety: the 5 bytes were written
+/// unsafe { buf.advance(5) };
+/// assert_eq!(buf.as_ref(), b"hello");
+/// ```
+pub struct TrackedUninitSlice<'a> {
+    slice: &'a mut UninitSlice,
+    #[cfg(debug_assertions)]
+    written: usize,
+}
+
+impl<'a> TrackedUninitSlice<'a> {
+    /// Wrap `slice`, none of its bytes are considered written.
+    #[inline]
+    pub fn new(slice: &'a mut UninitSlice) -> TrackedUninitSlice<'a> {
+        TrackedUninitSlice {
+            slice,
+            #[cfg(debug_assertions)]
+            written: 0,
+        }
+    }
+
+    /// Return the number of bytes of the wrapped slice, written or not.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.slice.len()
+    }
+
+    /// Return `true` if the wrapped slice has no bytes.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.slice.is_empty()
+    }
+
+    /// Return the end of the furthest write, or `None` in release builds where the writes are
+    /// not recorded.
+    #[inline]
+    pub fn high_water_mark(&self) -> Option<usize> {
+        #[cfg(debug_assertions)]
+        return Some(self.written);
+
+        #[cfg(not(debug_assertions))]
+        None
+    }
+
+    /// Write `byte` at `index`.
+    ///
+    /// # Panics
+    ///
+    /// This panics if `index >= self.len()`.
+    #[inline]
+    pub fn write_byte(&mut self, index: usize, byte: u8) {
+        self.slice.write_byte(index, byte);
+        self.record(index + 1);
+    }
+
+    /// Copy `src` into the slice at `offset`.
+    ///
+    /// # Panics
+    ///
+    /// This panics if the end of the write is greater than `self.len()`.
+    #[inline]
+    pub fn write_slice(&mut self, offset: usize, src: &[u8]) {
+        let end = offset
+            .checked_add(src.len())
+            .filter(|end| *end <= self.len())
+            .expect("write out of bounds");
+
+        self.slice[offset..end].copy_from_slice(src);
+        self.record(end);
+    }
+
+    /// Check that the first `count` bytes can be committed, i.e. that bytes were written up to
+    /// `count`. This does nothing in release builds.
+    ///
+    /// Only the end of the furthest write is recorded, so a gap left between two writes is not
+    /// detected.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, this panics if `count` is greater than the high-water mark.
+    #[inline]
+    #[track_caller]
+    pub fn check_commit(&self, count: usize) {
+        #[cfg(debug_assertions)]
+        assert!(
+            count <= self.written,
+            "committing more bytes than written: count ({}) > written ({})",
+            count,
+            self.written
+        );
+
+        #[cfg(not(debug_assertions))]
+        let _ = count;
+    }
+
+    #[inline]
+    fn record(&mut self, end: usize) {
+        #[cfg(debug_assertions)]
+        {
+            self.written = core::cmp::max(self.written, end);
+        }
+
+        #[cfg(not(debug_assertions))]
+        let _ = end;
+    }
+}
+
+impl fmt::Debug for TrackedUninitSlice<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug = f.debug_struct("TrackedUninitSlice");
+        debug.field("len", &self.len());
+
+        #[cfg(debug_assertions)]
+        debug.field("written", &self.written);
+
+        debug.finish_non_exhaustive()
+    }
 }
 
 macro_rules! impl_index {
@@ -82,4 +281,79 @@ macro_rules! impl_index {
     };
 }
 
-impl_index!(Range<usize>, RangeFull, RangeFrom<usize>);
+impl_index!(
+    Range<usize>,
+    RangeFull,
+    RangeFrom<usize>,
+    RangeTo<usize>,
+    RangeInclusive<usize>,
+    RangeToInclusive<usize>
+);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{BufMut, BytesMut};
+
+    #[test]
+    fn fmt() {
+        let mut buf = [MaybeUninit::uninit(); 12];
+        let slice = UninitSlice::from_slice(&mut buf);
+
+        assert_eq!(format!("{:?}", slice), "UninitSlice { len: 12, .. }");
+        assert_eq!(slice.to_string(), "12 uninitialized bytes");
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    fn tracked() {
+        let mut buf = [MaybeUninit::uninit(); 8];
+        let mut slice = TrackedUninitSlice::new(UninitSlice::from_slice(&mut buf));
+
+        slice.write_slice(0, b"abc");
+        slice.write_byte(5, b'f');
+        assert_eq!(slice.high_water_mark(), Some(6));
+        assert_eq!(
+            format!("{:?}", slice),
+            "TrackedUninitSlice { len: 8, written: 6, .. }"
+        );
+
+        slice.check_commit(6);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "committing more bytes than written")]
+    fn tracked_overcommit() {
+        let mut buf = BytesMut::with_capacity(8);
+        let mut slice = TrackedUninitSlice::new(buf.chuncks_mut());
+
+        slice.write_slice(0, b"abc");
+        slice.check_commit(4);
+    }
+
+    #[test]
+    fn parallel_fill() {
+        let mut buf = BytesMut::with_capacity(1000);
+        buf.extend_from_slice(b"header");
+
+        std::thread::scope(|s| {
+            let spare = buf.chunk_mut_at(0);
+            let (rows, _) = spare.split_at_mut(900);
+
+            for (i, row) in rows.chunks_mut(100).enumerate() {
+                s.spawn(move || row.copy_from_slice(&[i as u8; 100]));
+            }
+        });
+
+        // Safety: the 900 bytes were written by the threads
+        unsafe { buf.commit_parallel(900) };
+
+        assert_eq!(buf.len(), 906);
+        assert_eq!(&buf.as_ref()[..6], b"header");
+        assert!(buf.as_ref()[6..]
+            .chunks(100)
+            .enumerate()
+            .all(|(i, row)| row.iter().all(|b| *b == i as u8)));
+    }
+}
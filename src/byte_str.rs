@@ -1,6 +1,10 @@
-use core::{fmt, ops, str};
+use core::borrow::Borrow;
+use core::fmt::{self, Write};
+use core::ops::{Bound, RangeBounds};
+use core::{ops, str};
 
-use crate::Bytes;
+use crate::iter::{ByteStrLines, Fields, SplitWhitespace};
+use crate::{Bytes, FromUtf8Error};
 
 /// This reprensent a `Bytes` but with only valid utf8.
 ///
@@ -18,7 +22,8 @@ impl ByteStr {
     /// # Invariant
     ///
     /// The inner buffer is empty so it's made of valid utf8
-    pub fn new() -> ByteStr {
+    #[must_use]
+    pub const fn new() -> ByteStr {
         ByteStr {
             inner: Bytes::new(),
         }
@@ -29,6 +34,7 @@ impl ByteStr {
     /// # Invariant
     ///
     /// Rust ensures that strings are made of valid utf8 so `src.as_bytes()` is made of valid utf8
+    #[must_use]
     #[inline]
     pub const fn from_static(src: &'static str) -> ByteStr {
         ByteStr {
@@ -48,7 +54,7 @@ impl ByteStr {
     /// In debug mode this function will panic if the given bytes are invalid utf8. In release mode
     /// this will result in undefined behaviour.
     pub unsafe fn from_utf8_unchecked(src: &[u8]) -> ByteStr {
-        if cfg!(debug_assert) {
+        if cfg!(debug_assertions) {
             match str::from_utf8(src) {
                 Ok(_) => ByteStr {
                     inner: Bytes::copy_from_slice(src),
@@ -74,7 +80,7 @@ impl ByteStr {
     /// In debug mode this function will panic if the given bytes are invalid utf8. In release mode
     /// this will result in undefined behaviour.
     pub unsafe fn from_shared_unchecked(src: Bytes) -> ByteStr {
-        if cfg!(debug_assert) {
+        if cfg!(debug_assertions) {
             match str::from_utf8(&src) {
                 Ok(_) => ByteStr { inner: src },
                 Err(e) => panic!("invalid utf8: {}", e),
@@ -84,13 +90,314 @@ impl ByteStr {
         }
     }
 
+    #[must_use]
     #[inline]
-    pub fn as_str(&self) -> &str {
+    pub const fn as_str(&self) -> &str {
         // Safety: the invariant of `ByteStr` ensures that inner is made of valid utf8
-        unsafe { str::from_utf8_unchecked(&self.inner) }
+        unsafe { str::from_utf8_unchecked(self.inner.as_slice()) }
     }
+
+    /// Return an iterator over the chars of `self` escaped with [`char::escape_default`].
+    ///
+    /// Unlike the `Debug` output, every non ASCII char is escaped, so the result is printable
+    /// ASCII whatever the content of the `ByteStr`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::ByteStr;
+    ///
+    /// let s = ByteStr::from_static("GET /\tcafé\r\n");
+    ///
+    /// assert_eq!(s.escape_default().to_string(), "GET /\\tcaf\\u{e9}\\r\\n");
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn escape_default(&self) -> str::EscapeDefault<'_> {
+        self.as_str().escape_default()
+    }
+
+    /// Return a slice of `self` for the provided range, sharing the same underlying buffer.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::ByteStr;
+    ///
+    /// let s = ByteStr::from_static("Host: café.fr");
+    ///
+    /// assert_eq!(s.slice(6..), "café.fr");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Like the slicing of a `str`, this panics if the range is out of bounds or if one of its
+    /// bounds is not on a char boundary.
+    #[must_use]
+    pub fn slice(&self, range: impl RangeBounds<usize>) -> ByteStr {
+        let bounds = (range.start_bound().cloned(), range.end_bound().cloned());
+        let inner = self.inner.slice(bounds);
+
+        // The bounds were checked by `Bytes::slice` so they don't overflow
+        let start = match bounds.0 {
+            Bound::Included(start) => start,
+            Bound::Excluded(start) => start + 1,
+            Bound::Unbounded => 0,
+        };
+        let end = start + inner.len();
+
+        assert!(
+            self.is_char_boundary(start) && self.is_char_boundary(end),
+            "range {}..{} is not on a char boundary",
+            start,
+            end
+        );
+
+        ByteStr { inner }
+    }
+
+    /// Return the slice of `self` equivalent to `subset`, which must be a substring of
+    /// `self.as_str()`, e.g. one returned by a method of `str`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::ByteStr;
+    ///
+    /// let s = ByteStr::from_static("  key = value ");
+    /// let key = s.slice_ref(s.trim().split(" = ").next().unwrap());
+    ///
+    /// assert_eq!(key, "key");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This panics if `subset` is not empty and is not contained in `self`.
+    #[must_use]
+    pub fn slice_ref(&self, subset: &str) -> ByteStr {
+        // A `&str` always starts and ends on a char boundary
+        ByteStr {
+            inner: self.inner.slice_ref(subset.as_bytes()),
+        }
+    }
+
+    /// Return an iterator over the substrings of `self` separated by whitespace, as defined by
+    /// [`char::is_whitespace`]. The substrings share the same underlying buffer as `self`.
+    ///
+    /// Like [`str::split_whitespace`], the substrings are never empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::ByteStr;
+    ///
+    /// let s = ByteStr::from_static("  PING  :irc.example.com\r\n");
+    /// let words: Vec<_> = s.split_whitespace().collect();
+    ///
+    /// assert_eq!(words, ["PING", ":irc.example.com"]);
+    /// ```
+    #[inline]
+    pub fn split_whitespace(&self) -> SplitWhitespace {
+        SplitWhitespace::new(self.clone())
+    }
+
+    /// Return an iterator over the lines of `self`, sharing the same underlying buffer as `self`.
+    ///
+    /// The lines are split like [`str::lines`]: a line ends with either `\n` or `\r\n`, the
+    /// line ending is not part of the yielded line and an empty last line is not yielded.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::ByteStr;
+    ///
+    /// let s = ByteStr::from_static("[server]\r\nport = 80\n");
+    /// let lines: Vec<_> = s.lines().collect();
+    ///
+    /// assert_eq!(lines, ["[server]", "port = 80"]);
+    /// ```
+    #[inline]
+    pub fn lines(&self) -> ByteStrLines {
+        ByteStrLines::new(self.clone())
+    }
+
+    /// Return an iterator over the fields of `self` separated by `separator`, sharing the same
+    /// underlying buffer as `self`.
+    ///
+    /// Like [`str::split`], consecutive separators yield empty fields and a separator at the
+    /// start or the end of `self` yields an empty first or last field.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::ByteStr;
+    ///
+    /// let s = ByteStr::from_static("root:x:0:0::/root");
+    /// let fields: Vec<_> = s.fields(':').collect();
+    ///
+    /// assert_eq!(fields, ["root", "x", "0", "0", "", "/root"]);
+    /// ```
+    #[inline]
+    pub fn fields(&self, separator: char) -> Fields {
+        Fields::new(self.clone(), separator)
+    }
+
+    /// Create a new `ByteStr` from `bytes` if they satisfy `policy`, a charset stricter than
+    /// UTF-8 or equal to it. The bytes are scanned once, e.g. to validate a header field while
+    /// building its `ByteStr`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::{ByteStr, Bytes, CharPolicy, PolicyViolation};
+    ///
+    /// let name = ByteStr::from_bytes_with(Bytes::from_static(b"Content-Type"), CharPolicy::Token);
+    /// assert_eq!(name.unwrap(), "Content-Type");
+    ///
+    /// let err = ByteStr::from_bytes_with(Bytes::from_static(b"bad name"), CharPolicy::Token)
+    ///     .unwrap_err();
+    /// assert_eq!((err.index(), err.violation()), (3, PolicyViolation::NotTokenChar));
+    /// assert_eq!(err.into_bytes(), "bad name");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`PolicyError`] giving back `bytes` and locating the first rejected byte.
+    pub fn from_bytes_with(bytes: Bytes, policy: CharPolicy) -> Result<ByteStr, PolicyError> {
+        match policy.check(&bytes) {
+            // Safety: every policy only accepts valid utf8
+            Ok(()) => Ok(ByteStr { inner: bytes }),
+            Err((index, violation)) => Err(PolicyError {
+                bytes,
+                index,
+                violation,
+            }),
+        }
+    }
+
+    /// Consume `self` and return the inner `Bytes`.
+    #[must_use]
+    #[inline]
+    pub fn into_bytes(self) -> Bytes {
+        self.inner
+    }
+}
+
+/// The charset accepted by [`ByteStr::from_bytes_with`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CharPolicy {
+    /// Any valid UTF-8, like `ByteStr::try_from`.
+    Utf8,
+    /// ASCII only.
+    Ascii,
+    /// Valid UTF-8 without control chars, except the horizontal tab. This matches the values of
+    /// the HTTP header fields.
+    NoControl,
+    /// A non-empty token as defined by RFC 7230, e.g. an HTTP method or a header field name:
+    /// ASCII letters, digits and ``!#$%&'*+-.^_`|~``.
+    Token,
 }
 
+impl CharPolicy {
+    /// Return the index and the reason of the first byte of `bytes` rejected by `self`.
+    fn check(self, bytes: &[u8]) -> Result<(), (usize, PolicyViolation)> {
+        let reject =
+            |violation, accept: fn(&u8) -> bool| match bytes.iter().position(|b| !accept(b)) {
+                Some(index) => Err((index, violation)),
+                None => Ok(()),
+            };
+
+        match self {
+            CharPolicy::Utf8 => str::from_utf8(bytes)
+                .map(|_| ())
+                .map_err(|e| (e.valid_up_to(), PolicyViolation::InvalidUtf8)),
+            CharPolicy::Ascii => reject(PolicyViolation::NonAscii, u8::is_ascii),
+            CharPolicy::NoControl => {
+                CharPolicy::Utf8.check(bytes)?;
+                reject(PolicyViolation::ControlChar, |b| {
+                    *b == b'\t' || !b.is_ascii_control()
+                })
+            }
+            CharPolicy::Token if bytes.is_empty() => Err((0, PolicyViolation::Empty)),
+            CharPolicy::Token => reject(PolicyViolation::NotTokenChar, |b| {
+                b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(b)
+            }),
+        }
+    }
+}
+
+/// The reason why a byte is rejected by a [`CharPolicy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PolicyViolation {
+    /// The bytes are not valid UTF-8.
+    InvalidUtf8,
+    /// The byte is not ASCII.
+    NonAscii,
+    /// The byte is a control char.
+    ControlChar,
+    /// The byte is not allowed in a token.
+    NotTokenChar,
+    /// A token is empty.
+    Empty,
+}
+
+impl fmt::Display for PolicyViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            PolicyViolation::InvalidUtf8 => "invalid utf8",
+            PolicyViolation::NonAscii => "non ASCII byte",
+            PolicyViolation::ControlChar => "control char",
+            PolicyViolation::NotTokenChar => "byte not allowed in a token",
+            PolicyViolation::Empty => "empty token",
+        })
+    }
+}
+
+/// The error returned by [`ByteStr::from_bytes_with`] when the bytes don't satisfy the policy.
+///
+/// The original `Bytes` can be retrieved with [`PolicyError::into_bytes`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PolicyError {
+    bytes: Bytes,
+    index: usize,
+    violation: PolicyViolation,
+}
+
+impl PolicyError {
+    /// Return the index of the first rejected byte. The bytes before it satisfy the policy.
+    #[inline]
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Return the reason why the byte at [`PolicyError::index`] is rejected.
+    #[inline]
+    pub fn violation(&self) -> PolicyViolation {
+        self.violation
+    }
+
+    /// Return the bytes that failed to be converted.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Consume `self` and return the bytes that failed to be converted.
+    #[inline]
+    pub fn into_bytes(self) -> Bytes {
+        self.bytes
+    }
+}
+
+impl fmt::Display for PolicyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at index {}", self.violation, self.index)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PolicyError {}
+
 impl Default for ByteStr {
     fn default() -> ByteStr {
         ByteStr::new()
@@ -98,9 +405,11 @@ impl Default for ByteStr {
 }
 
 impl From<String> for ByteStr {
+    /// Convert a `String` into a `ByteStr`, reusing its allocation.
     fn from(value: String) -> ByteStr {
-        // Safety: Rust ensures that all string are made of valid utf8 bytes
-        unsafe { ByteStr::from_utf8_unchecked(value.as_bytes()) }
+        ByteStr {
+            inner: Bytes::from(value),
+        }
     }
 }
 
@@ -111,17 +420,75 @@ impl<'a> From<&'a str> for ByteStr {
     }
 }
 
+impl TryFrom<Bytes> for ByteStr {
+    type Error = FromUtf8Error;
+
+    fn try_from(value: Bytes) -> Result<ByteStr, FromUtf8Error> {
+        match str::from_utf8(&value) {
+            Ok(_) => Ok(ByteStr { inner: value }),
+            Err(error) => Err(FromUtf8Error::new(value, error)),
+        }
+    }
+}
+
+impl From<ByteStr> for String {
+    /// Convert a `ByteStr` into a `String`, reusing the allocation if the `ByteStr` is the only
+    /// owner of its buffer.
+    fn from(value: ByteStr) -> String {
+        // Safety: the invariant of `ByteStr` ensures that inner is made of valid utf8
+        unsafe { String::from_utf8_unchecked(value.inner.into_vec()) }
+    }
+}
+
 impl AsRef<str> for ByteStr {
     fn as_ref(&self) -> &str {
         self.as_str()
     }
 }
 
+impl AsRef<Bytes> for ByteStr {
+    fn as_ref(&self) -> &Bytes {
+        &self.inner
+    }
+}
+
+impl Borrow<str> for ByteStr {
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl PartialEq<str> for ByteStr {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl<'a> PartialEq<&'a str> for ByteStr {
+    fn eq(&self, other: &&'a str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl PartialEq<ByteStr> for str {
+    fn eq(&self, other: &ByteStr) -> bool {
+        self == other.as_str()
+    }
+}
+
+impl PartialEq<ByteStr> for &str {
+    fn eq(&self, other: &ByteStr) -> bool {
+        *self == other.as_str()
+    }
+}
+
 impl fmt::Debug for ByteStr {
+    /// Format the string between double quotes, escaping the control chars, the quotes and the
+    /// backslashes with [`str::escape_debug`].
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct("ByteStr")
-            .field("inner", &self.as_str())
-            .finish()
+        f.write_char('"')?;
+        write!(f, "{}", self.as_str().escape_debug())?;
+        f.write_char('"')
     }
 }
 
@@ -149,6 +516,44 @@ impl ops::Deref for ByteStr {
 mod test {
     use super::*;
 
+    #[test]
+    fn from_bytes_with() {
+        let check = |bytes: &'static [u8], policy| {
+            ByteStr::from_bytes_with(Bytes::from_static(bytes), policy)
+                .map(|s| s.len())
+                .map_err(|e| (e.index(), e.violation()))
+        };
+
+        assert_eq!(check(b"caf\xc3\xa9", CharPolicy::Utf8), Ok(5));
+        assert_eq!(
+            check(b"caf\xc3", CharPolicy::Utf8),
+            Err((3, PolicyViolation::InvalidUtf8))
+        );
+        assert_eq!(
+            check(b"caf\xc3\xa9", CharPolicy::Ascii),
+            Err((3, PolicyViolation::NonAscii))
+        );
+        assert_eq!(check(b"text/html;\tq=1", CharPolicy::NoControl), Ok(14));
+        assert_eq!(
+            check(b"value\r\n", CharPolicy::NoControl),
+            Err((5, PolicyViolation::ControlChar))
+        );
+        assert_eq!(
+            check(b"\x7f", CharPolicy::NoControl),
+            Err((0, PolicyViolation::ControlChar))
+        );
+        assert_eq!(check(b"X-Custom_1~", CharPolicy::Token), Ok(11));
+        assert_eq!(
+            check(b"Host:", CharPolicy::Token),
+            Err((4, PolicyViolation::NotTokenChar))
+        );
+        assert_eq!(
+            check(b"", CharPolicy::Token),
+            Err((0, PolicyViolation::Empty))
+        );
+        assert_eq!(check(b"", CharPolicy::Ascii), Ok(0));
+    }
+
     #[test]
     fn from_static() {
         let bytes = ByteStr::from_static("this is valid utf8");
@@ -161,6 +566,42 @@ mod test {
         let bytes = ByteStr::from(String::from("this is a string"));
 
         assert_eq!("this is a string", bytes.as_str());
+
+        let string = String::from("no copy");
+        let ptr = string.as_ptr();
+        assert_eq!(ByteStr::from(string).as_ptr(), ptr);
+    }
+
+    #[test]
+    fn into_string() {
+        let bytes = ByteStr::try_from(Bytes::from(b"hello".to_vec())).unwrap();
+        let ptr = bytes.as_ptr();
+        let string = String::from(bytes);
+
+        assert_eq!(string, "hello");
+        assert_eq!(string.as_ptr(), ptr);
+
+        let err = ByteStr::try_from(Bytes::from_static(b"\xffhello")).unwrap_err();
+        assert_eq!(err.utf8_error().valid_up_to(), 0);
+        assert_eq!(err.as_bytes(), b"\xffhello");
+    }
+
+    #[test]
+    fn slice() {
+        let s = ByteStr::from(String::from("café au lait"));
+
+        let word = s.slice(..5);
+        assert_eq!(word, "café");
+        assert_eq!(word.as_ptr(), s.as_ptr());
+        assert_eq!(s.slice(6..8), "au");
+        assert_eq!(s.slice_ref(&s[9..]), "lait");
+        assert!(s.slice(5..5).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "not on a char boundary")]
+    fn slice_char_boundary() {
+        let _ = ByteStr::from_static("café").slice(..4);
     }
 
     #[test]
@@ -169,4 +610,18 @@ mod test {
 
         assert_eq!("this is a ByteStr", format!("{}", bytes));
     }
+
+    #[test]
+    fn debug() {
+        let bytes = ByteStr::from_static("key=\"v\\al\"\0\x1b[0m\u{7f}é\n");
+
+        assert_eq!(
+            format!("{:?}", bytes),
+            r#""key=\"v\\al\"\0\u{1b}[0m\u{7f}é\n""#
+        );
+        assert_eq!(
+            bytes.escape_default().to_string(),
+            r#"key=\"v\\al\"\u{0}\u{1b}[0m\u{7f}\u{e9}\n"#
+        );
+    }
 }
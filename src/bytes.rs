@@ -1,13 +1,23 @@
-use core::ops::{Deref, RangeBounds};
-use core::sync::atomic::{AtomicPtr, AtomicUsize, Ordering};
-use core::{mem, ptr, slice};
+use core::ops::{Deref, Range, RangeBounds};
+use core::sync::atomic::{self, AtomicPtr, AtomicUsize, Ordering};
+use core::{cmp, fmt, mem, ptr, slice, str};
 
 use alloc::{
     alloc::{dealloc, Layout},
-    borrow::Borrow,
+    borrow::{Borrow, Cow},
+    string::String,
+    sync::Arc,
     vec::Vec,
 };
 
+use crate::budget::MemoryUsage;
+use crate::bytes_list::{self, BytesList, Gather};
+use crate::bytes_mut::BytesMut;
+use crate::iter::{Chunks, Delimiter, Lines, Split, Utf8Chunks, Windows};
+use crate::simd;
+use crate::trace::lifecycle;
+use crate::Buf;
+
 pub struct Bytes {
     /// A pointer to the underlying data
     ptr: *const u8,
@@ -26,20 +36,51 @@ pub struct Bytes {
 pub struct Vtable {
     pub(crate) clone: unsafe fn(&AtomicPtr<()>, *const u8, usize) -> Bytes,
     pub(crate) drop: unsafe fn(&mut AtomicPtr<()>, *const u8, usize),
+    /// Consume the `Bytes` and return its content as a `Vec`, reusing the allocation if possible
+    pub(crate) into_vec: unsafe fn(&mut AtomicPtr<()>, *const u8, usize) -> Vec<u8>,
+    /// Return the number of `Bytes` sharing the buffer, `None` if the buffer isn't counted
+    pub(crate) ref_count: unsafe fn(&AtomicPtr<()>) -> Option<usize>,
 }
 
 // === Bytes ===
 
 impl Bytes {
-    const EMPTY: &[u8] = &[];
+    /// The pointer of every empty `Bytes`.
+    ///
+    /// All the operations resulting in an empty `Bytes` return this canonical representation,
+    /// which doesn't keep any buffer alive, instead of a pointer into the buffer they sliced.
+    const EMPTY: *const u8 = ptr::NonNull::<u8>::dangling().as_ptr();
 
+    /// Create a new empty `Bytes`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// let mut bytes = Bytes::from(b"hello".to_vec());
+    /// bytes.truncate(0);
+    ///
+    /// assert_eq!(bytes.as_ptr(), Bytes::new().as_ptr());
+    /// ```
+    #[must_use]
     #[inline]
-    pub fn new() -> Bytes {
-        Bytes::from_static(Bytes::EMPTY)
+    pub const fn new() -> Bytes {
+        Bytes {
+            ptr: Bytes::EMPTY,
+            len: 0,
+            data: AtomicPtr::new(ptr::null_mut()),
+            vtable: &STATIC_VTABLE,
+        }
     }
 
+    #[must_use]
     #[inline]
     pub const fn from_static(src: &'static [u8]) -> Bytes {
+        if src.is_empty() {
+            return Bytes::new();
+        }
+
         Bytes {
             ptr: src.as_ptr(),
             len: src.len(),
@@ -48,6 +89,42 @@ impl Bytes {
         }
     }
 
+    /// Create a new `Bytes` from the `start..end` range of `src`.
+    ///
+    /// This is the `const` equivalent of `Bytes::from_static(&src[start..end])`, it allows to
+    /// build tables of tokens sliced out of a single static buffer at compile time.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// const METHODS: &[u8] = b"GETPUTPOST";
+    /// const TOKENS: [Bytes; 3] = [
+    ///     Bytes::slice_static(METHODS, 0, 3),
+    ///     Bytes::slice_static(METHODS, 3, 6),
+    ///     Bytes::slice_static(METHODS, 6, 10),
+    /// ];
+    ///
+    /// assert_eq!(TOKENS[2], "POST");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This panics if `start > end` or if `end > src.len()`. When used in a `const` context the
+    /// panic is a compilation error.
+    #[must_use]
+    #[inline]
+    pub const fn slice_static(src: &'static [u8], start: usize, end: usize) -> Bytes {
+        assert!(start <= end, "range start must not be greater than end");
+        assert!(end <= src.len(), "range end out of bounds");
+
+        let (head, _) = src.split_at(end);
+        let (_, slice) = head.split_at(start);
+
+        Bytes::from_static(slice)
+    }
+
     /// Return the len of the inner bytes buffer
     ///
     /// # Example
@@ -58,6 +135,7 @@ impl Bytes {
     ///
     /// assert!(bytes.is_empty());
     /// ```
+    #[must_use]
     #[inline]
     pub const fn len(&self) -> usize {
         self.len
@@ -73,11 +151,32 @@ impl Bytes {
     ///
     /// assert!(bytes.is_empty());
     /// ```
+    #[must_use]
     #[inline]
     pub const fn is_empty(&self) -> bool {
         self.len == 0
     }
 
+    /// Replace `self` with the canonical empty `Bytes` once it has no bytes left, releasing the
+    /// buffer it points into.
+    #[inline]
+    fn normalize_empty(&mut self) {
+        if self.len == 0 && !ptr::eq(self.ptr, Bytes::EMPTY) {
+            *self = Bytes::new();
+        }
+
+        self.debug_assert_canonical();
+    }
+
+    #[inline]
+    fn debug_assert_canonical(&self) {
+        debug_assert!(
+            self.len != 0
+                || (ptr::eq(self.ptr, Bytes::EMPTY) && ptr::eq(self.vtable, &STATIC_VTABLE)),
+            "empty `Bytes` must use the canonical representation"
+        );
+    }
+
     /// Get the raw pointer of the inner bytes array
     ///
     /// # Safety
@@ -90,10 +189,174 @@ impl Bytes {
         self.ptr
     }
 
+    #[must_use]
+    #[inline]
     pub fn copy_from_slice(src: &[u8]) -> Bytes {
         src.to_vec().into()
     }
 
+    /// Create a new `Bytes` made of the bytes of `self` repeated `n` times.
+    ///
+    /// The new buffer is allocated once with its exact len, the bytes are then copied by
+    /// doubling the filled part of the buffer.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// let padding = Bytes::from_static(b"ab").repeat(3);
+    ///
+    /// assert_eq!(padding, "ababab");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This panics if the len of the new buffer would overflow.
+    #[must_use]
+    pub fn repeat(&self, n: usize) -> Bytes {
+        if self.is_empty() || n == 0 {
+            return Bytes::new();
+        }
+
+        let len = self
+            .len
+            .checked_mul(n)
+            .filter(|len| *len <= crate::MAX_LEN)
+            .expect("repeat len overflow");
+
+        let mut vec = Vec::with_capacity(len);
+        vec.extend_from_slice(self);
+        while vec.len() <= len / 2 {
+            vec.extend_from_within(..);
+        }
+        vec.extend_from_within(..len - vec.len());
+
+        Bytes::from(vec)
+    }
+
+    /// Create a new `Bytes` viewing the `range` of the bytes of `arc` without copying them.
+    ///
+    /// The `Arc` is cloned and kept alive until the returned `Bytes` and all its clones are
+    /// dropped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use bytes::Bytes;
+    ///
+    /// let blob: Arc<[u8]> = Arc::from(&b"HTTP/1.1 200 OK"[..]);
+    /// let status = Bytes::from_slice_ref_counted(&blob, 9..12);
+    ///
+    /// assert_eq!(status, "200");
+    /// assert_eq!(Arc::strong_count(&blob), 2);
+    ///
+    /// drop(status);
+    /// assert_eq!(Arc::strong_count(&blob), 1);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This panics if the range is invalid, see [`Bytes::slice`].
+    #[must_use]
+    pub fn from_slice_ref_counted<T>(arc: &Arc<T>, range: impl RangeBounds<usize>) -> Bytes
+    where
+        T: AsRef<[u8]> + ?Sized + Send + Sync + 'static,
+    {
+        let slice = (**arc).as_ref();
+
+        // Safety: the content of an `Arc` doesn't move and can't be mutated while it's shared
+        let bytes = unsafe { Bytes::with_owner(arc.clone(), slice.as_ptr(), slice.len()) };
+        bytes.slice(range)
+    }
+
+    /// Create a new `Bytes` viewing the bytes of `owner` without copying them.
+    ///
+    /// The owner is moved to the heap and dropped once the returned `Bytes` and all its clones
+    /// are dropped. This wraps memory managed by other types, e.g. a buffer of a pool which is
+    /// released on drop.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// struct Pooled(Vec<u8>);
+    ///
+    /// impl AsRef<[u8]> for Pooled {
+    ///     fn as_ref(&self) -> &[u8] {
+    ///         &self.0
+    ///     }
+    /// }
+    ///
+    /// let bytes = Bytes::from_owner(Pooled(b"pooled".to_vec()));
+    ///
+    /// assert_eq!(bytes.clone(), "pooled");
+    /// ```
+    #[must_use]
+    pub fn from_owner<T>(owner: T) -> Bytes
+    where
+        T: AsRef<[u8]> + Send + 'static,
+    {
+        /// Drop the boxed owner if its `AsRef` implementation panics.
+        struct Guard(*mut OwnedHeader);
+
+        impl Drop for Guard {
+            fn drop(&mut self) {
+                unsafe { ((*self.0).drop)(self.0) }
+            }
+        }
+
+        let owned = box_owner(owner);
+        let guard = Guard(owned.cast());
+
+        // Safety: the owner is boxed so its bytes don't move, they are borrowed from the box
+        // which is kept alive by the returned `Bytes`
+        let slice = unsafe { (*owned).owner.as_ref() };
+        mem::forget(guard);
+
+        if slice.is_empty() {
+            // Safety: `owned` was just leaked from a box and nothing else points to it
+            drop(unsafe { Box::from_raw(owned) });
+            return Bytes::new();
+        }
+
+        Bytes {
+            ptr: slice.as_ptr(),
+            len: slice.len(),
+            data: AtomicPtr::new(owned.cast()),
+            vtable: &OWNED_VTABLE,
+        }
+    }
+
+    /// Create a new `Bytes` viewing `len` bytes at `ptr`, kept alive by `owner`. The owner is
+    /// dropped once the returned `Bytes` and all its clones are dropped.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be valid for reads of `len` bytes and the bytes must not be mutated nor moved
+    /// until `owner` is dropped.
+    pub(crate) unsafe fn with_owner<T: Send + 'static>(
+        owner: T,
+        ptr: *const u8,
+        len: usize,
+    ) -> Bytes {
+        if len == 0 {
+            return Bytes::new();
+        }
+
+        let owned = box_owner(owner);
+
+        Bytes {
+            ptr,
+            len,
+            data: AtomicPtr::new(owned.cast()),
+            vtable: &OWNED_VTABLE,
+        }
+    }
+
     /// Retrieve the byte at the given index
     ///
     /// # Example
@@ -112,7 +375,8 @@ impl Bytes {
     ///
     /// # Panics
     ///
-    /// If the preconditions are not met than this function will panic
+    /// If the preconditions are not met than this function will panic, see [`Bytes::try_get`]
+    /// for a non-panicking version.
     ///
     /// ```should_panic
     /// # use bytes::Bytes;
@@ -133,759 +397,3905 @@ impl Bytes {
         unsafe { offset.read() }
     }
 
-    /// Get a subslice of the `Bytes` object
-    /// This create a cloned `Bytes` object with the given subslice
+    /// Retrieve the byte at the given index, or `None` if it's out of bounds.
     ///
     /// # Example
     ///
     /// ```
-    /// # use bytes::Bytes;
-    /// let bytes = Bytes::from_static(b"toto toto");
-    /// let slice = bytes.slice(..4);
+    /// use bytes::Bytes;
     ///
-    /// assert_eq!(slice.as_slice(), b"toto");
-    /// assert_eq!(bytes.as_slice(), b"toto toto");
+    /// let bytes = Bytes::from_static(b"toto");
+    ///
+    /// assert_eq!(bytes.try_get(1), Some(b'o'));
+    /// assert_eq!(bytes.try_get(4), None);
     /// ```
+    #[must_use]
+    #[inline]
+    pub fn try_get(&self, index: usize) -> Option<u8> {
+        self.as_slice().get(index).copied()
+    }
+
+    /// Return the first byte, or `None` if `self` is empty.
     ///
-    /// # Panics
+    /// # Example
     ///
-    /// This panics if there is an invalid range given e.g if the start is superior to the end
-    /// or if the end is superior to the len of the `Bytes`
-    pub fn slice(&self, range: impl RangeBounds<usize>) -> Bytes {
-        use core::ops::Bound::*;
-
-        let len = self.len;
-
-        let start = match range.start_bound() {
-            Included(&start) => start,
-            Excluded(&start) => start + 1,
-            Unbounded => 0,
-        };
-        let end = match range.end_bound() {
-            Included(&end) => end + 1,
-            Excluded(&end) => end,
-            Unbounded => 0,
-        };
-
-        assert!(
-            start <= end,
-            "invalid bounds: start ({}) > end ({})",
-            start,
-            end
-        );
-
-        assert!(end <= len, "invalid bounds: end out of bounds ({})", end);
-
-        // If start == end we don't care about slicing the good ptr range
-        // we just return an empty value
-        if start == end {
-            return Bytes::new();
-        }
-
-        let mut slice = self.clone();
-
-        slice.len = end - start;
-        slice.ptr = unsafe { slice.ptr.add(start) };
-
-        slice
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// assert_eq!(Bytes::from_static(b"+OK").first(), Some(b'+'));
+    /// assert_eq!(Bytes::new().first(), None);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn first(&self) -> Option<u8> {
+        self.as_slice().first().copied()
     }
 
-    /// Split the bytes into two at the given position. Afterwards, `self` contains elements from
-    /// `0` to `at` (i.e. `[0..at]`) and the returned value contains the elements from `at` to the
-    /// end (i.e. `[at..]`).
+    /// Return the last byte, or `None` if `self` is empty.
     ///
     /// # Example
     ///
     /// ```
     /// use bytes::Bytes;
     ///
-    /// let mut bytes = Bytes::from(b"hello world" as &[u8]);
-    /// let b = bytes.split_off(5);
+    /// assert_eq!(Bytes::from_static(b"line\n").last(), Some(b'\n'));
+    /// assert_eq!(Bytes::new().last(), None);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn last(&self) -> Option<u8> {
+        self.as_slice().last().copied()
+    }
+
+    /// Return the first byte and the rest of the bytes, or `None` if `self` is empty. The rest
+    /// shares the same underlying buffer as `self`.
+    ///
+    /// # Example
     ///
-    /// assert_eq!(&bytes[..], b"hello");
-    /// assert_eq!(&b[..], b" world");
     /// ```
+    /// use bytes::Bytes;
     ///
-    /// # Panics
+    /// let bytes = Bytes::from_static(b"+OK");
+    /// let (kind, rest) = bytes.split_first().unwrap();
     ///
-    /// This method will panic if `at` > `self.len()`
-    pub fn split_off(&mut self, at: usize) -> Bytes {
-        assert!(
-            at <= self.len,
-            "index out of bounds: at ({}) > len ({})",
-            at,
-            self.len
-        );
-
-        let mut ret = self.clone();
-
-        self.len = at;
-
-        unsafe { ret.inc_start(at) };
-
-        ret
+    /// assert_eq!(kind, b'+');
+    /// assert_eq!(rest, "OK");
+    /// ```
+    #[must_use]
+    pub fn split_first(&self) -> Option<(u8, Bytes)> {
+        let first = self.first()?;
+        Some((first, self.slice(1..)))
     }
 
-    /// Split the bytes into two at the given position. Afterwards, `self` contains elements from
-    /// `at` to the end (i.e. `[at..]`) and the returned value contains the elements from `0` to `at`
-    /// (i.e. `[0..at]`).
+    /// Return the last byte and the rest of the bytes, or `None` if `self` is empty. The rest
+    /// shares the same underlying buffer as `self`.
     ///
     /// # Example
     ///
     /// ```
     /// use bytes::Bytes;
     ///
-    /// let mut bytes = Bytes::from(b"hello world" as &[u8]);
-    /// let b = bytes.split_to(5);
+    /// let bytes = Bytes::from_static(b"line\n");
+    /// let (end, line) = bytes.split_last().unwrap();
     ///
-    /// assert_eq!(&bytes[..], b" world");
-    /// assert_eq!(&b[..], b"hello");
+    /// assert_eq!(end, b'\n');
+    /// assert_eq!(line, "line");
     /// ```
+    #[must_use]
+    pub fn split_last(&self) -> Option<(u8, Bytes)> {
+        let last = self.last()?;
+        Some((last, self.slice(..self.len - 1)))
+    }
+
+    /// Return the bytes after `prefix` if `self` starts with it, or `None` otherwise. The
+    /// returned value shares the same underlying buffer as `self`.
     ///
-    /// # Panics
+    /// The `starts_with` and `ends_with` checks of `[u8]` are available through `Deref`.
     ///
-    /// This method will panic if `at` > `self.len()`
-    pub fn split_to(&mut self, at: usize) -> Bytes {
-        assert!(
-            at <= self.len,
-            "index out of bounds: at ({}) > len ({})",
-            at,
-            self.len
-        );
-
-        let mut ret = self.clone();
-
-        unsafe { self.inc_start(at) };
-
-        ret.len = at;
-        ret
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// let bytes = Bytes::from_static(b"\x89PNG\r\n\x1a\nIHDR");
+    ///
+    /// assert!(bytes.starts_with(b"\x89PNG"));
+    /// assert_eq!(bytes.strip_prefix(b"\x89PNG\r\n\x1a\n").unwrap(), "IHDR");
+    /// assert_eq!(bytes.strip_prefix(b"GIF8"), None);
+    /// ```
+    #[must_use]
+    pub fn strip_prefix(&self, prefix: &[u8]) -> Option<Bytes> {
+        if self.starts_with(prefix) {
+            Some(self.slice(prefix.len()..))
+        } else {
+            None
+        }
     }
 
-    /// Shorten the buffer to keep the first `len` bytes and dropping the rest. If `len` is greater
-    /// than the current len of the buffer, nothing is done.
+    /// Return the bytes before `suffix` if `self` ends with it, or `None` otherwise. The
+    /// returned value shares the same underlying buffer as `self`.
     ///
     /// # Example
     ///
     /// ```
     /// use bytes::Bytes;
     ///
-    /// let mut bytes = Bytes::from(b"hello world" as &[u8]);
-    /// bytes.truncate(5);
+    /// let bytes = Bytes::from_static(b"line\r\n");
     ///
-    /// assert_eq!(bytes, b"hello"[..]);
+    /// assert!(bytes.ends_with(b"\n"));
+    /// assert_eq!(bytes.strip_suffix(b"\r\n").unwrap(), "line");
+    /// assert_eq!(bytes.strip_suffix(b"\0"), None);
     /// ```
-    pub fn truncate(&mut self, len: usize) {
-        if len < self.len {
-            self.len = len
+    #[must_use]
+    pub fn strip_suffix(&self, suffix: &[u8]) -> Option<Bytes> {
+        if self.ends_with(suffix) {
+            Some(self.slice(..self.len - suffix.len()))
+        } else {
+            None
         }
     }
 
-    /// Clear the buffer by removing all data.
+    /// Return `self` without its leading and trailing ASCII whitespace, as defined by
+    /// [`u8::is_ascii_whitespace`]. The returned value shares the same underlying buffer as
+    /// `self`.
     ///
     /// # Example
     ///
     /// ```
     /// use bytes::Bytes;
     ///
-    /// let mut bytes = Bytes::from(b"hello world" as &[u8]);
-    /// bytes.clear();
+    /// let value = Bytes::from_static(b" \t text/html \r\n");
     ///
-    /// assert!(bytes.is_empty());
+    /// assert_eq!(value.trim_ascii(), "text/html");
+    /// assert_eq!(value.trim_ascii_start(), "text/html \r\n");
+    /// assert_eq!(value.trim_ascii_end(), " \t text/html");
     /// ```
+    #[must_use]
     #[inline]
-    pub fn clear(&mut self) {
-        self.truncate(0)
+    pub fn trim_ascii(&self) -> Bytes {
+        self.slice_ref(self.as_slice().trim_ascii())
     }
 
+    /// Return `self` without its leading ASCII whitespace. The returned value shares the same
+    /// underlying buffer as `self`.
+    #[must_use]
     #[inline]
-    unsafe fn inc_start(&mut self, inc: usize) {
-        assert!(inc <= self.len());
+    pub fn trim_ascii_start(&self) -> Bytes {
+        self.slice_ref(self.as_slice().trim_ascii_start())
+    }
 
-        self.len -= inc;
-        self.ptr = self.ptr.add(inc)
+    /// Return `self` without its trailing ASCII whitespace. The returned value shares the same
+    /// underlying buffer as `self`.
+    #[must_use]
+    #[inline]
+    pub fn trim_ascii_end(&self) -> Bytes {
+        self.slice_ref(self.as_slice().trim_ascii_end())
     }
 
-    /// Retrive the inner bytes as a slice
+    /// Return `true` if the bytes of `self` are a part of the bytes of `parent` in memory, e.g.
+    /// if `self` was sliced or split from `parent` or from one of its clones.
+    ///
+    /// This only compares the addresses of the two views, never their content. An empty `self`
+    /// is a subslice of any `parent`.
     ///
     /// # Example
     ///
     /// ```
-    /// # use bytes::Bytes;
+    /// use bytes::Bytes;
+    ///
+    /// let parent = Bytes::from(b"hello world".to_vec());
+    /// let word = parent.slice(6..);
+    ///
+    /// assert!(word.is_subslice_of(&parent));
+    /// assert!(!parent.is_subslice_of(&word));
+    /// assert!(!Bytes::from(b"world".to_vec()).is_subslice_of(&parent));
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn is_subslice_of(&self, parent: &Bytes) -> bool {
+        if self.is_empty() {
+            return true;
+        }
+
+        let start = self.ptr as usize;
+        let parent_start = parent.ptr as usize;
+
+        start >= parent_start && start + self.len <= parent_start + parent.len
+    }
+
+    /// Return `true` if `self` starts with the bytes of `prefix`.
+    ///
+    /// When `prefix` is a view of the same memory as the start of `self`, e.g. a slice of a
+    /// common parent starting at the same offset, this is answered without comparing the bytes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// let request = Bytes::from(b"GET / HTTP/1.1".to_vec());
+    /// let method = request.slice(..3);
+    ///
+    /// assert!(request.starts_with_bytes(&method));
+    /// assert!(request.starts_with_bytes(&Bytes::from_static(b"GET")));
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn starts_with_bytes(&self, prefix: &Bytes) -> bool {
+        if prefix.len > self.len {
+            return false;
+        }
+
+        prefix.ptr == self.ptr || self.as_slice()[..prefix.len] == *prefix.as_slice()
+    }
+
+    /// Return `true` if `self` ends with the bytes of `suffix`.
+    ///
+    /// When `suffix` is a view of the same memory as the end of `self`, e.g. a slice of a common
+    /// parent ending at the same offset, this is answered without comparing the bytes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// let path = Bytes::from(b"/var/log/syslog".to_vec());
+    /// let file = path.slice(9..);
+    ///
+    /// assert!(path.ends_with_bytes(&file));
+    /// assert!(!path.ends_with_bytes(&Bytes::from_static(b"log/")));
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn ends_with_bytes(&self, suffix: &Bytes) -> bool {
+        if suffix.len > self.len {
+            return false;
+        }
+
+        let start = self.len - suffix.len;
+        suffix.ptr == self.ptr.wrapping_add(start) || self.as_slice()[start..] == *suffix.as_slice()
+    }
+
+    /// Get a subslice of the `Bytes` object
+    /// This create a cloned `Bytes` object with the given subslice
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bytes::Bytes;
+    /// let bytes = Bytes::from_static(b"toto toto");
+    /// let slice = bytes.slice(..4);
+    ///
+    /// assert_eq!(slice.as_slice(), b"toto");
+    /// assert_eq!(bytes.as_slice(), b"toto toto");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This panics if there is an invalid range given e.g if the start is superior to the end
+    /// or if the end is superior to the len of the `Bytes`
+    #[must_use]
+    pub fn slice(&self, range: impl RangeBounds<usize>) -> Bytes {
+        match self.try_slice(range) {
+            Ok(slice) => slice,
+            Err(e) => panic!("invalid bounds: {}", e),
+        }
+    }
+
+    /// Get a subslice of the `Bytes` object, returning an error instead of panicking if the
+    /// range is invalid. This is the variant of [`Bytes::slice`] to use with a range read from
+    /// untrusted data.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::{Bytes, SliceError};
+    ///
+    /// let bytes = Bytes::from_static(b"hello world");
+    ///
+    /// assert_eq!(bytes.try_slice(6..).unwrap(), "world");
+    /// assert_eq!(
+    ///     bytes.try_slice(6..20),
+    ///     Err(SliceError::EndOutOfBounds { end: 20, len: 11 })
+    /// );
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This returns an error describing the violated bound if the start of the range is greater
+    /// than its end, if its end is greater than the len of the `Bytes` or if a bound overflows.
+    pub fn try_slice(&self, range: impl RangeBounds<usize>) -> Result<Bytes, SliceError> {
+        use core::ops::Bound::*;
+
+        let len = self.len;
+
+        let start = match range.start_bound() {
+            Included(&start) => start,
+            Excluded(&start) => start.checked_add(1).ok_or(SliceError::StartOverflow)?,
+            Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Included(&end) => end.checked_add(1).ok_or(SliceError::EndOverflow)?,
+            Excluded(&end) => end,
+            Unbounded => len,
+        };
+
+        if start > end {
+            return Err(SliceError::StartAfterEnd { start, end });
+        }
+        if end > len {
+            return Err(SliceError::EndOutOfBounds { end, len });
+        }
+
+        // If start == end we don't care about slicing the good ptr range
+        // we just return an empty value
+        if start == end {
+            return Ok(Bytes::new());
+        }
+
+        let mut slice = self.clone();
+
+        slice.len = end - start;
+        slice.ptr = unsafe { slice.ptr.add(start) };
+
+        Ok(slice)
+    }
+
+    /// Get a subslice of the `Bytes` object, clamping the bounds instead of panicking.
+    ///
+    /// The end is clamped to the len of `self` and the start to the end, so an out of range
+    /// window results in a shorter or empty `Bytes`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// let bytes = Bytes::from_static(b"hello world");
+    ///
+    /// assert_eq!(bytes.slice_clamped(6..100), "world");
+    /// assert_eq!(bytes.slice_clamped(8..3), "");
+    /// assert_eq!(bytes.slice_clamped(20..), "");
+    /// ```
+    #[must_use]
+    pub fn slice_clamped(&self, range: impl RangeBounds<usize>) -> Bytes {
+        use core::ops::Bound::*;
+
+        let end = match range.end_bound() {
+            Included(&end) => end.saturating_add(1),
+            Excluded(&end) => end,
+            Unbounded => self.len,
+        }
+        .min(self.len);
+        let start = match range.start_bound() {
+            Included(&start) => start,
+            Excluded(&start) => start.saturating_add(1),
+            Unbounded => 0,
+        }
+        .min(end);
+
+        self.slice(start..end)
+    }
+
+    /// Return a `Bytes` holding `subset`, which must be a slice of the bytes of `self`. The
+    /// returned value shares the same underlying buffer as `self`.
+    ///
+    /// This turns a slice borrowed from `self`, e.g. by a parser, back into a `Bytes` without
+    /// copying it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// let bytes = Bytes::from_static(b"GET /index.html HTTP/1.1");
+    /// let line: &[u8] = &bytes;
+    /// let path = line.split(|b| *b == b' ').nth(1).unwrap();
+    ///
+    /// assert_eq!(bytes.slice_ref(path), "/index.html");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This panics if `subset` is not empty and is not contained in the bytes of `self`.
+    #[must_use]
+    pub fn slice_ref(&self, subset: &[u8]) -> Bytes {
+        // An empty slice may point anywhere, e.g. be dangling
+        if subset.is_empty() {
+            return Bytes::new();
+        }
+
+        let start = self.ptr as usize;
+        let sub_start = subset.as_ptr() as usize;
+
+        assert!(
+            sub_start >= start,
+            "subset pointer ({:p}) is smaller than self pointer ({:p})",
+            subset.as_ptr(),
+            self.ptr
+        );
+        assert!(
+            sub_start + subset.len() <= start + self.len,
+            "subset is out of bounds: subset end ({:#x}) > self end ({:#x})",
+            sub_start + subset.len(),
+            start + self.len
+        );
+
+        let offset = sub_start - start;
+        self.slice(offset..offset + subset.len())
+    }
+
+    /// Split the bytes into two at the given position. Afterwards, `self` contains elements from
+    /// `0` to `at` (i.e. `[0..at]`) and the returned value contains the elements from `at` to the
+    /// end (i.e. `[at..]`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// let mut bytes = Bytes::from(b"hello world" as &[u8]);
+    /// let b = bytes.split_off(5);
+    ///
+    /// assert_eq!(&bytes[..], b"hello");
+    /// assert_eq!(&b[..], b" world");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if `at` > `self.len()`
+    #[must_use = "consider Bytes::truncate if you don't need the other half"]
+    pub fn split_off(&mut self, at: usize) -> Bytes {
+        assert!(
+            at <= self.len,
+            "index out of bounds: at ({}) > len ({})",
+            at,
+            self.len
+        );
+
+        let mut ret = self.clone();
+
+        self.len = at;
+
+        unsafe { ret.inc_start(at) };
+
+        self.normalize_empty();
+        ret.normalize_empty();
+        ret
+    }
+
+    /// Split the bytes into two at the given position. Afterwards, `self` contains elements from
+    /// `at` to the end (i.e. `[at..]`) and the returned value contains the elements from `0` to `at`
+    /// (i.e. `[0..at]`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// let mut bytes = Bytes::from(b"hello world" as &[u8]);
+    /// let b = bytes.split_to(5);
+    ///
+    /// assert_eq!(&bytes[..], b" world");
+    /// assert_eq!(&b[..], b"hello");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if `at` > `self.len()`
+    #[must_use = "consider Bytes::advance if you don't need the other half"]
+    pub fn split_to(&mut self, at: usize) -> Bytes {
+        assert!(
+            at <= self.len,
+            "index out of bounds: at ({}) > len ({})",
+            at,
+            self.len
+        );
+
+        let mut ret = self.clone();
+
+        unsafe { self.inc_start(at) };
+
+        ret.len = at;
+
+        self.normalize_empty();
+        ret.normalize_empty();
+        ret
+    }
+
+    /// Split the bytes into two at the given position, consuming `self`. Returns the elements
+    /// from `0` to `mid` (i.e. `[0..mid]`) and the elements from `mid` to the end (i.e.
+    /// `[mid..]`), sharing the same underlying buffer.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// let bytes = Bytes::from(b"hello world" as &[u8]);
+    /// let (hello, world) = bytes.split_at(5);
+    ///
+    /// assert_eq!(&hello[..], b"hello");
+    /// assert_eq!(&world[..], b" world");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if `mid` > `self.len()`
+    #[must_use]
+    pub fn split_at(mut self, mid: usize) -> (Bytes, Bytes) {
+        let rest = self.split_off(mid);
+        (self, rest)
+    }
+
+    /// Join `other` at the end of `self` without copying, if `other` starts right where `self`
+    /// ends in the same buffer, e.g. to undo a [`Bytes::split_off`] or a [`Bytes::split_to`].
+    ///
+    /// Static buffers can't be joined: two adjacent static slices may belong to different
+    /// statics. An empty `self` or `other` is always joined.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// let mut hello = Bytes::from(b"hello world".to_vec());
+    /// let world = hello.split_off(5);
+    ///
+    /// hello.try_unsplit(world).unwrap();
+    /// assert_eq!(hello, "hello world");
+    ///
+    /// let other = Bytes::from(b"!".to_vec());
+    /// assert_eq!(hello.try_unsplit(other).unwrap_err(), "!");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `other` if it's not contiguous with `self` in the same buffer, `self` is left
+    /// unchanged.
+    pub fn try_unsplit(&mut self, other: Bytes) -> Result<(), Bytes> {
+        if other.is_empty() {
+            return Ok(());
+        }
+
+        if self.is_empty() {
+            *self = other;
+            return Ok(());
+        }
+
+        let data = self.data.load(Ordering::Acquire);
+
+        // The data points to the object owning the buffer, except for static buffers
+        if self.is_static()
+            || data != other.data.load(Ordering::Acquire)
+            || self.ptr.wrapping_add(self.len) != other.ptr
+        {
+            return Err(other);
+        }
+
+        self.len += other.len;
+        Ok(())
+    }
+
+    /// Split the bytes into two at the given position like [`Bytes::split_off`], returning an
+    /// error instead of panicking if `at` is out of bounds.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// let mut bytes = Bytes::from_static(b"hello world");
+    ///
+    /// assert_eq!(bytes.split_off_checked(5).unwrap(), " world");
+    /// assert!(bytes.split_off_checked(6).is_err());
+    /// assert_eq!(bytes, "hello");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This returns an error if `at` > `self.len()`, `self` is left untouched.
+    pub fn split_off_checked(&mut self, at: usize) -> Result<Bytes, OutOfBounds> {
+        OutOfBounds::check(at, self.len)?;
+        Ok(self.split_off(at))
+    }
+
+    /// Split the bytes into two at the given position like [`Bytes::split_to`], returning an
+    /// error instead of panicking if `at` is out of bounds.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// let mut bytes = Bytes::from_static(b"hello world");
+    ///
+    /// assert_eq!(bytes.split_to_checked(6).unwrap(), "hello ");
+    /// assert!(bytes.split_to_checked(6).is_err());
+    /// assert_eq!(bytes, "world");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This returns an error if `at` > `self.len()`, `self` is left untouched.
+    pub fn split_to_checked(&mut self, at: usize) -> Result<Bytes, OutOfBounds> {
+        OutOfBounds::check(at, self.len)?;
+        Ok(self.split_to(at))
+    }
+
+    /// Return a `Bytes` containing the last `n` bytes of `self`. The returned value shares the
+    /// same underlying buffer as `self`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// let bytes = Bytes::from_static(b"payload\xde\xad\xbe\xef");
+    /// let trailer = bytes.last_n(4);
+    ///
+    /// assert_eq!(&trailer[..], b"\xde\xad\xbe\xef");
+    /// assert_eq!(bytes.len(), 11);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if `n` > `self.len()`
+    #[must_use]
+    pub fn last_n(&self, n: usize) -> Bytes {
+        assert!(
+            n <= self.len,
+            "index out of bounds: n ({}) > len ({})",
+            n,
+            self.len
+        );
+
+        self.slice(self.len - n..)
+    }
+
+    /// Split the last `n` bytes off the buffer. Afterwards, `self` contains elements from `0` to
+    /// `len - n` and the returned value contains the last `n` elements.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// let mut bytes = Bytes::from(b"hello world" as &[u8]);
+    /// let b = bytes.split_from_end(6);
+    ///
+    /// assert_eq!(&bytes[..], b"hello");
+    /// assert_eq!(&b[..], b" world");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if `n` > `self.len()`
+    #[must_use = "consider Bytes::truncate if you don't need the other half"]
+    pub fn split_from_end(&mut self, n: usize) -> Bytes {
+        assert!(
+            n <= self.len,
+            "index out of bounds: n ({}) > len ({})",
+            n,
+            self.len
+        );
+
+        self.split_off(self.len - n)
+    }
+
+    /// Return a `Bytes` with all the trailing `byte` removed. The returned value shares the same
+    /// underlying buffer as `self`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// let bytes = Bytes::from_static(b"frame\0\0\0");
+    ///
+    /// assert_eq!(&bytes.trim_end_matches(0)[..], b"frame");
+    /// ```
+    #[must_use]
+    pub fn trim_end_matches(&self, byte: u8) -> Bytes {
+        let end = self
+            .as_slice()
+            .iter()
+            .rposition(|b| *b != byte)
+            .map_or(0, |pos| pos + 1);
+
+        self.slice(..end)
+    }
+
+    /// Return the index of the first byte which is one of `set`, or `None` if there is none.
+    ///
+    /// The scan is vectorized when the `simd` feature is enabled, see [`simd::find_any`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// let bytes = Bytes::from_static(b"Host: example.com\r\n");
+    ///
+    /// assert_eq!(bytes.find_any(b"\r\n\0"), Some(17));
+    /// assert_eq!(bytes.find_any(b"?#"), None);
+    /// ```
+    ///
+    /// [`simd::find_any`]: crate::simd::find_any
+    #[must_use]
+    #[inline]
+    pub fn find_any<const N: usize>(&self, set: &[u8; N]) -> Option<usize> {
+        simd::find_any(set, self.as_slice())
+    }
+
+    /// Return the len of the longest prefix made of bytes `b` for which `table[b]` is `true`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// let mut token = [false; 256];
+    /// for b in b'a'..=b'z' {
+    ///     token[b as usize] = true;
+    /// }
+    ///
+    /// let bytes = Bytes::from_static(b"content-length: 42");
+    ///
+    /// assert_eq!(bytes.span_of(&token), 7);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn span_of(&self, table: &[bool; 256]) -> usize {
+        simd::span_of(table, self.as_slice())
+    }
+
+    /// Return the index of the first occurrence of `needle`, or `None` if there is none. An
+    /// empty needle is found at index `0`.
+    ///
+    /// The search is accelerated by [`simd::memchr`], see [`simd::find`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// let bytes = Bytes::from_static(b"Host: example.com\r\n\r\nbody");
+    ///
+    /// assert_eq!(bytes.find(b"\r\n\r\n"), Some(17));
+    /// assert_eq!(bytes.find(b"\n\n"), None);
+    /// ```
+    ///
+    /// [`simd::memchr`]: crate::simd::memchr
+    /// [`simd::find`]: crate::simd::find
+    #[must_use]
+    #[inline]
+    pub fn find(&self, needle: &[u8]) -> Option<usize> {
+        simd::find(needle, self.as_slice())
+    }
+
+    /// Return `true` if `needle` is a subsequence of `self`.
+    ///
+    /// This looks for a sequence of bytes, the `contains` method of `[u8]` looking for a single
+    /// byte is still reachable with `as_slice().contains(&byte)`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// let bytes = Bytes::from_static(b"Transfer-Encoding: chunked");
+    ///
+    /// assert!(bytes.contains(b"chunked"));
+    /// assert!(!bytes.contains(b"gzip"));
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn contains(&self, needle: &[u8]) -> bool {
+        self.find(needle).is_some()
+    }
+
+    /// Return an iterator over the segments of `self` separated by `delimiter`, a byte or a
+    /// sequence of bytes. The segments share the same underlying buffer as `self`.
+    ///
+    /// Like the `split` method of `[u8]`, consecutive delimiters yield empty segments and a
+    /// delimiter at the start or the end of `self` yields an empty first or last segment. An
+    /// empty sequence never matches.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// let bytes = Bytes::from_static(b"Host: example.com\r\nAccept: */*");
+    /// let mut lines = bytes.split(b"\r\n");
+    ///
+    /// assert_eq!(lines.next().unwrap(), "Host: example.com");
+    /// assert_eq!(lines.next().unwrap(), "Accept: */*");
+    /// assert_eq!(lines.next(), None);
+    ///
+    /// let fields: Vec<_> = Bytes::from_static(b"a,b,,c").split(b',').collect();
+    /// assert_eq!(fields, ["a", "b", "", "c"]);
+    /// ```
+    #[inline]
+    pub fn split<D: Delimiter>(&self, delimiter: D) -> Split<D> {
+        Split::new(self.clone(), delimiter)
+    }
+
+    /// Gather the bytes of `ranges` into a [`BytesList`], in the order of the ranges.
+    ///
+    /// With [`Gather::Segmented`] the segments of the list share the buffer of `self`, adjacent
+    /// ranges being merged into a single segment. With [`Gather::Coalesced`] the bytes are
+    /// copied into a single allocation of their total len, [`BytesList::into_bytes`] then
+    /// returns it without copying.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::{Bytes, Gather};
+    ///
+    /// let row = Bytes::from_static(b"1,alice,42,paris");
+    /// let columns = [0..2, 8..10];
+    ///
+    /// let view = row.select(&columns, Gather::Segmented);
+    /// assert_eq!(view.segments(), [&b"1,"[..], &b"42"[..]]);
+    ///
+    /// let copy = row.select(&columns, Gather::Coalesced).into_bytes();
+    /// assert_eq!(copy, "1,42");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This panics if one of the ranges is out of bounds or if its start is greater than its
+    /// end.
+    #[must_use]
+    pub fn select(&self, ranges: &[Range<usize>], gather: Gather) -> BytesList {
+        bytes_list::select(self, ranges, gather)
+    }
+
+    /// Return an iterator over `size` bytes chunks of `self`, the last chunk may be shorter. The
+    /// chunks are `Bytes` sharing the buffer of `self`, so unlike the chunks of `[u8]` they can
+    /// be moved to other threads.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// let bytes = Bytes::from(b"abcdefgh".to_vec());
+    /// let chunks: Vec<Bytes> = bytes.chunks(3).collect();
+    ///
+    /// assert_eq!(chunks, ["abc", "def", "gh"]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This panics if `size` is `0`.
+    #[inline]
+    pub fn chunks(&self, size: usize) -> Chunks {
+        Chunks::new(self.clone(), size)
+    }
+
+    /// Return an iterator over the overlapping windows of `size` bytes of `self`, e.g. to
+    /// compute a rolling hash. The windows are `Bytes` sharing the buffer of `self`, which stays
+    /// alive as long as the iterator or one of the windows.
+    ///
+    /// Each window increments the reference count of the buffer, the windows of `[u8]` available
+    /// through `Deref` are cheaper when they don't need to outlive the borrow of `self`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// let bytes = Bytes::from(b"abcd".to_vec());
+    /// let windows: Vec<Bytes> = bytes.windows(3).collect();
+    ///
+    /// assert_eq!(windows, ["abc", "bcd"]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This panics if `size` is `0`.
+    #[inline]
+    pub fn windows(&self, size: usize) -> Windows {
+        Windows::new(self.clone(), size)
+    }
+
+    /// Return an iterator over the chunks of `self` made of valid utf8 followed by invalid bytes,
+    /// like the `utf8_chunks` method of `[u8]`. The chunks share the buffer of `self`, so a
+    /// payload mixing text and binary data can be inspected without copying it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// let payload = Bytes::from_static(b"id=\xff\xfe;name=caf\xc3\xa9");
+    /// let mut rendered = String::new();
+    ///
+    /// for chunk in payload.utf8_chunks() {
+    ///     rendered.push_str(chunk.valid());
+    ///     for b in chunk.invalid().iter() {
+    ///         rendered.push_str(&format!("\\x{:02x}", b));
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(rendered, "id=\\xff\\xfe;name=café");
+    /// ```
+    #[inline]
+    pub fn utf8_chunks(&self) -> Utf8Chunks {
+        Utf8Chunks::new(self.clone())
+    }
+
+    /// Return an iterator over the lines of `self`. The lines share the same underlying buffer
+    /// as `self`.
+    ///
+    /// Like the `lines` method of `str`, a line ends with either `\n` or `\r\n` and the line
+    /// ending is not part of the yielded line. The last line may have no ending, an empty last
+    /// line is not yielded.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// let log = Bytes::from_static(b"INFO start\r\nWARN retry\nINFO done\n");
+    /// let lines: Vec<_> = log.lines().collect();
+    ///
+    /// assert_eq!(lines, ["INFO start", "WARN retry", "INFO done"]);
+    /// ```
+    #[inline]
+    pub fn lines(&self) -> Lines {
+        Lines::new(self.clone())
+    }
+
+    /// Split off consecutive pieces of the given `sizes` from the start of `self`, like calling
+    /// [`Bytes::split_to`] for each size. `self` keeps the bytes after the last piece.
+    ///
+    /// The bounds are checked once for all the pieces, each piece then only costs a reference
+    /// count increment. This suits the formats listing the lens of all their segments upfront.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// let mut batch = Bytes::from_static(b"idnamepayload|next batch");
+    /// let records = batch.split_many(&[2, 4, 7]);
+    ///
+    /// assert_eq!(records, ["id", "name", "payload"]);
+    /// assert_eq!(batch, "|next batch");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This panics if the sum of `sizes` is greater than `self.len()`, `self` is left unchanged.
+    #[must_use = "consider Bytes::advance if you don't need the pieces"]
+    pub fn split_many(&mut self, sizes: &[usize]) -> Vec<Bytes> {
+        let total = sizes
+            .iter()
+            .try_fold(0, |total: usize, size| total.checked_add(*size))
+            .filter(|total| *total <= self.len);
+
+        let Some(total) = total else {
+            panic!("split sizes out of bounds: total > len ({})", self.len);
+        };
+
+        let mut pieces = Vec::with_capacity(sizes.len());
+        let mut ptr = self.ptr;
+
+        for &size in sizes {
+            if size == 0 {
+                pieces.push(Bytes::new());
+                continue;
+            }
+
+            let mut piece = self.clone();
+            piece.ptr = ptr;
+            piece.len = size;
+            pieces.push(piece);
+
+            // Safety: the sizes add up to at most `self.len` so `ptr` stays in bounds of `self`
+            ptr = unsafe { ptr.add(size) };
+        }
+
+        unsafe { self.inc_start(total) };
+        self.normalize_empty();
+        pieces
+    }
+
+    /// Split `self` into `n` contiguous parts of the same len, the last part also takes the
+    /// bytes left over by the division. The parts share the same underlying buffer as `self`,
+    /// e.g. to hand them over to different threads.
+    ///
+    /// Some parts are empty if `self` holds less than `n` bytes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// let parts = Bytes::from_static(b"abcdefghij").split_evenly(3);
+    /// assert_eq!(parts, ["abc", "def", "ghij"]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This panics if `n` is `0`.
+    #[must_use]
+    pub fn split_evenly(&self, n: usize) -> Vec<Bytes> {
+        assert!(n > 0, "cannot split into 0 parts");
+
+        self.split_at_points(n, |_, point| point)
+    }
+
+    /// Split `self` into `n` contiguous parts like [`split_evenly`], except that each part ends
+    /// right after the first `delimiter` ending at or after its even split point. This keeps the
+    /// records of e.g. a newline delimited file whole while balancing the parts.
+    ///
+    /// A part takes every byte up to the end of `self` if no delimiter follows its split point,
+    /// the following parts are empty.
+    ///
+    /// [`split_evenly`]: Bytes::split_evenly
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// let bytes = Bytes::from_static(b"one\ntwo\nthree\nfour\n");
+    /// let parts = bytes.split_evenly_on(2, b'\n');
+    ///
+    /// assert_eq!(parts, ["one\ntwo\nthree\n", "four\n"]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This panics if `n` is `0`.
+    #[must_use]
+    pub fn split_evenly_on<D: Delimiter>(&self, n: usize, delimiter: D) -> Vec<Bytes> {
+        assert!(n > 0, "cannot split into 0 parts");
+
+        self.split_at_points(n, |start, point| {
+            // Parts grown past their split point push the next ones further
+            let from = cmp::max(start, point.saturating_sub(delimiter.delimiter_len()));
+
+            match delimiter.find_in(&self[from..]) {
+                Some((pos, len)) => from + pos + len,
+                None => self.len,
+            }
+        })
+    }
+
+    /// Split `self` into `n` parts, `end(start, point)` returns the end of the part starting at
+    /// `start` whose even split point is `point`. The last part ends at the end of `self`.
+    fn split_at_points(&self, n: usize, mut end: impl FnMut(usize, usize) -> usize) -> Vec<Bytes> {
+        let size = self.len / n;
+        let mut parts = Vec::with_capacity(n);
+        let mut start = 0;
+
+        for i in 1..n {
+            let stop = cmp::min(end(start, i * size), self.len);
+            parts.push(self.slice(start..stop));
+            start = stop;
+        }
+
+        parts.push(self.slice(start..));
+        parts
+    }
+
+    /// Split `self` into blocks of `N` bytes. Returns an iterator over the blocks and the
+    /// remaining bytes which don't fill a whole block. The remainder shares the same underlying
+    /// buffer as `self`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// let bytes = Bytes::from_static(b"0123456789");
+    /// let (mut blocks, rem) = bytes.as_chunks::<4>();
+    ///
+    /// assert_eq!(blocks.next(), Some(b"0123"));
+    /// assert_eq!(blocks.next(), Some(b"4567"));
+    /// assert_eq!(blocks.next(), None);
+    /// assert_eq!(&rem[..], b"89");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if `N` is `0`
+    pub fn as_chunks<const N: usize>(&self) -> (slice::Iter<'_, [u8; N]>, Bytes) {
+        assert!(N != 0, "chunck size must be non-zero");
+
+        let count = self.len / N;
+
+        // SAFETY:
+        // `[u8; N]` has the same alignment as `u8` and `count * N <= self.len` so the blocks are
+        // all in bounds of `self`
+        let blocks: &[[u8; N]] = unsafe { slice::from_raw_parts(self.ptr.cast(), count) };
+
+        (blocks.iter(), self.slice(count * N..))
+    }
+
+    /// Shorten the buffer to keep the first `len` bytes and dropping the rest. If `len` is greater
+    /// than the current len of the buffer, nothing is done.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// let mut bytes = Bytes::from(b"hello world" as &[u8]);
+    /// bytes.truncate(5);
+    ///
+    /// assert_eq!(bytes, b"hello"[..]);
+    /// ```
+    pub fn truncate(&mut self, len: usize) {
+        if len < self.len {
+            // The promotable vtables compute the capacity of the buffer from `self.len` when it is
+            // freed or promoted so the buffer must be promoted before shrinking it.
+            if ptr::eq(self.vtable, &PROMOTABLE_EVEN_VTABLE)
+                || ptr::eq(self.vtable, &PROMOTABLE_ODD_VTABLE)
+            {
+                drop(self.split_off(len));
+            } else {
+                self.len = len
+            }
+
+            self.normalize_empty();
+        }
+    }
+
+    /// Shorten the buffer to keep the first `len` bytes like [`Bytes::truncate`], returning an
+    /// error if `len` is greater than the current len instead of doing nothing.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// let mut bytes = Bytes::from_static(b"hello world");
+    ///
+    /// assert!(bytes.truncate_checked(20).is_err());
+    /// assert!(bytes.truncate_checked(5).is_ok());
+    /// assert_eq!(bytes, "hello");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This returns an error if `len` > `self.len()`, `self` is left untouched.
+    pub fn truncate_checked(&mut self, len: usize) -> Result<(), OutOfBounds> {
+        OutOfBounds::check(len, self.len)?;
+        self.truncate(len);
+        Ok(())
+    }
+
+    /// Advance the start of the buffer by `cnt` bytes like [`Buf::advance`], returning an error
+    /// instead of panicking if there are less than `cnt` bytes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// let mut bytes = Bytes::from_static(b"hello world");
+    ///
+    /// assert!(bytes.advance_checked(6).is_ok());
+    /// assert!(bytes.advance_checked(6).is_err());
+    /// assert_eq!(bytes, "world");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This returns an error if `cnt` > `self.len()`, `self` is left untouched.
+    pub fn advance_checked(&mut self, cnt: usize) -> Result<(), OutOfBounds> {
+        OutOfBounds::check(cnt, self.len)?;
+        self.advance(cnt);
+        Ok(())
+    }
+
+    /// Clear the buffer by removing all data.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// let mut bytes = Bytes::from(b"hello world" as &[u8]);
+    /// bytes.clear();
+    ///
+    /// assert!(bytes.is_empty());
+    /// ```
+    #[inline]
+    pub fn clear(&mut self) {
+        self.truncate(0)
+    }
+
+    #[inline]
+    unsafe fn inc_start(&mut self, inc: usize) {
+        assert!(inc <= self.len());
+
+        self.len -= inc;
+        self.ptr = self.ptr.add(inc)
+    }
+
+    /// Retrive the inner bytes as a slice
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bytes::Bytes;
     /// let bytes = Bytes::from_static(b"toto");
     ///
-    /// assert_eq!(bytes.as_slice(), b"toto");
+    /// assert_eq!(bytes.as_slice(), b"toto");
+    /// ```
+    #[must_use]
+    #[inline]
+    pub const fn as_slice(&self) -> &[u8] {
+        unsafe { slice::from_raw_parts(self.ptr, self.len) }
+    }
+
+    /// Return the heap memory attributed to `self`.
+    ///
+    /// Static buffers don't use any heap memory. The other buffers report the size of their
+    /// whole allocation even if `self` only views a part of it, plus the size of the reference
+    /// count once the buffer was shared.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// let bytes = Bytes::from(vec![0; 64]);
+    /// assert_eq!(bytes.memory_usage().total(), 64);
+    ///
+    /// let slice = bytes.slice(..8);
+    /// let usage = slice.memory_usage();
+    ///
+    /// assert_eq!(usage.allocation, 64);
+    /// assert!(usage.overhead > 0);
+    /// assert!(usage.shared);
+    /// ```
+    #[must_use]
+    pub fn memory_usage(&self) -> MemoryUsage {
+        if ptr::eq(self.vtable, &STATIC_VTABLE) {
+            return MemoryUsage::default();
+        }
+
+        let data = self.data.load(Ordering::Acquire);
+
+        if ptr::eq(self.vtable, &OWNED_VTABLE) {
+            // Safety: the data of the owned vtable points to a live `OwnedHeader`. The memory
+            // of the owner itself is unknown.
+            let header = unsafe { &*data.cast::<OwnedHeader>() };
+
+            return MemoryUsage {
+                allocation: 0,
+                overhead: 0,
+                shared: header.ref_cnt.load(Ordering::Relaxed) > 1,
+            };
+        }
+
+        let is_promotable = ptr::eq(self.vtable, &PROMOTABLE_EVEN_VTABLE)
+            || ptr::eq(self.vtable, &PROMOTABLE_ODD_VTABLE);
+
+        if is_promotable && data as usize & KIND_MASK == KIND_UNSHARED {
+            // Only the even vtable tags the buffer pointer, the odd one stores it as is
+            let buf = if ptr::eq(self.vtable, &PROMOTABLE_EVEN_VTABLE) {
+                data as usize & !KIND_MASK
+            } else {
+                data as usize
+            };
+
+            return MemoryUsage {
+                allocation: (self.ptr as usize - buf) + self.len,
+                overhead: 0,
+                shared: false,
+            };
+        }
+
+        // Safety: the other representations point to a live `Shared` object
+        let shared = unsafe { &*data.cast::<Shared>() };
+        let hash = if shared.hash.load(Ordering::Relaxed).is_null() {
+            0
+        } else {
+            mem::size_of::<HashCache>()
+        };
+
+        MemoryUsage {
+            allocation: shared.cap,
+            overhead: mem::size_of::<Shared>() + hash,
+            shared: shared.ref_cnt.load(Ordering::Relaxed) > 1,
+        }
+    }
+
+    /// Return a 64 bits hash of the content of `self`, computed with FNV-1a.
+    ///
+    /// The hash only depends on the content so equal buffers have equal hashes whatever their
+    /// representation. Once a buffer is shared, the hash of the first view asking for it is
+    /// cached alongside the reference count so the clones of this view get it without reading
+    /// the bytes again. This is meant for dedup maps and caches, the hash is not cryptographic.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// let blob = Bytes::from(b"large cached blob".to_vec());
+    /// let clone = blob.clone();
+    ///
+    /// assert_eq!(blob.content_hash64(), clone.content_hash64());
+    /// assert_eq!(blob.content_hash64(), Bytes::from_static(b"large cached blob").content_hash64());
+    /// ```
+    #[must_use]
+    pub fn content_hash64(&self) -> u64 {
+        let is_shared = ptr::eq(self.vtable, &SHARED_VTABLE)
+            || ((ptr::eq(self.vtable, &PROMOTABLE_EVEN_VTABLE)
+                || ptr::eq(self.vtable, &PROMOTABLE_ODD_VTABLE))
+                && self.data.load(Ordering::Acquire) as usize & KIND_MASK == KIND_SHARED);
+
+        if !is_shared {
+            return fnv1a64(self.as_slice());
+        }
+
+        // Safety: the shared representations point to a live `Shared` object
+        let shared = unsafe { &*self.data.load(Ordering::Acquire).cast::<Shared>() };
+        let cache = shared.hash.load(Ordering::Acquire);
+
+        if !cache.is_null() {
+            // Safety: the cache is never modified once installed and lives as long as `shared`
+            let cache = unsafe { &*cache };
+
+            if cache.ptr == self.ptr && cache.len == self.len {
+                return cache.hash;
+            }
+
+            return fnv1a64(self.as_slice());
+        }
+
+        let hash = fnv1a64(self.as_slice());
+        let new = Box::into_raw(Box::new(HashCache {
+            ptr: self.ptr,
+            len: self.len,
+            hash,
+        }));
+
+        // Only the first view installs its hash, the others are computed on demand
+        if shared
+            .hash
+            .compare_exchange(ptr::null_mut(), new, Ordering::AcqRel, Ordering::Acquire)
+            .is_err()
+        {
+            drop(unsafe { Box::from_raw(new) });
+        }
+
+        hash
+    }
+
+    /// Consume `self` and return its content as a `Vec`. The allocation is reused if `self` is
+    /// the only owner of its buffer, otherwise the bytes are copied.
+    pub(crate) fn into_vec(self) -> Vec<u8> {
+        let mut this = mem::ManuallyDrop::new(self);
+        let (ptr, len) = (this.ptr, this.len);
+
+        unsafe { (this.vtable.into_vec)(&mut this.data, ptr, len) }
+    }
+
+    /// Return the number of `Bytes` sharing the buffer of `self`, including `self`.
+    ///
+    /// `None` is returned for static buffers, their clones are not counted since the buffer is
+    /// never freed. The count may be outdated as soon as it's returned if other threads hold
+    /// clones of `self`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// let bytes = Bytes::from(b"hello".to_vec());
+    /// assert_eq!(bytes.ref_count(), Some(1));
+    ///
+    /// let clone = bytes.slice(1..);
+    /// assert_eq!(bytes.ref_count(), Some(2));
+    ///
+    /// drop(clone);
+    /// assert!(bytes.is_unique());
+    ///
+    /// assert_eq!(Bytes::from_static(b"static").ref_count(), None);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn ref_count(&self) -> Option<usize> {
+        unsafe { (self.vtable.ref_count)(&self.data) }
+    }
+
+    /// Return `true` if no other `Bytes` shares the buffer of `self`. Static buffers are never
+    /// unique, see [`Bytes::ref_count`].
+    #[must_use]
+    #[inline]
+    pub fn is_unique(&self) -> bool {
+        self.ref_count() == Some(1)
+    }
+
+    /// Return `true` if `self` views static memory, e.g. a `Bytes` created with
+    /// [`Bytes::from_static`] or an empty `Bytes`. Cloning and dropping such a `Bytes` don't
+    /// touch any reference count.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// assert!(Bytes::from_static(b"static").is_static());
+    /// assert!(Bytes::new().is_static());
+    /// assert!(!Bytes::from(b"heap".to_vec()).is_static());
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn is_static(&self) -> bool {
+        ptr::eq(self.vtable, &STATIC_VTABLE)
+    }
+
+    /// Return the static slice viewed by `self`, `None` if `self` doesn't view static memory.
+    /// See [`Bytes::is_static`].
+    #[must_use]
+    #[inline]
+    pub fn as_static(&self) -> Option<&'static [u8]> {
+        if !self.is_static() {
+            return None;
+        }
+
+        // Safety: the static vtable is only used for `'static` slices
+        Some(unsafe { slice::from_raw_parts(self.ptr, self.len) })
+    }
+
+    /// Convert `self` into the static slice it views, so that a hot path can use it without
+    /// reference counting.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// let bytes = Bytes::from_static(b"GET / HTTP/1.1").slice(4..5);
+    /// assert_eq!(bytes.into_static(), Ok(&b"/"[..]));
+    ///
+    /// let bytes = Bytes::from(b"heap".to_vec());
+    /// assert!(bytes.into_static().is_err());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `self` if it doesn't view static memory.
+    pub fn into_static(self) -> Result<&'static [u8], Bytes> {
+        self.as_static().ok_or(self)
+    }
+
+    /// Consume `self` and return the bytes it views as a static slice, keeping its buffer alive
+    /// until the end of the process like `Vec::leak`. This is meant for the data living as long
+    /// as the process, e.g. an interned configuration blob.
+    ///
+    /// The share of `self` in the buffer is never released, so the buffer isn't freed when the
+    /// other `Bytes` sharing it are dropped and the drop hooks attached to it are never called.
+    /// Static memory is returned as is.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// let config = Bytes::from(b"mode=fast\nlevel=3".to_vec());
+    /// let mode: &'static [u8] = config.slice(..9).leak();
+    /// drop(config);
+    ///
+    /// assert_eq!(mode, b"mode=fast");
+    /// ```
+    #[must_use]
+    pub fn leak(self) -> &'static [u8] {
+        let this = mem::ManuallyDrop::new(self);
+
+        // Safety: the buffer is never freed since the reference held by `this` is never released
+        unsafe { slice::from_raw_parts(this.ptr, this.len) }
+    }
+
+    /// Attach `hook` to the buffer of `self`, it's called once the last `Bytes` sharing the
+    /// buffer is dropped and the buffer is freed. This is meant to check that large buffers are
+    /// actually released, e.g. in the tests of a long-lived service.
+    ///
+    /// Several hooks can be attached to the same buffer, they are called in the reverse order of
+    /// their attachment by the thread dropping the last `Bytes`. Converting the last `Bytes` into
+    /// a `Vec` or a `BytesMut` reusing the buffer calls the hooks too.
+    ///
+    /// Only the buffers allocated by the crate can be observed, `hook` is returned for static
+    /// buffers and buffers kept alive by an owner, see [`Bytes::from_owner`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::sync::atomic::{AtomicBool, Ordering};
+    /// use std::sync::Arc;
+    ///
+    /// use bytes::Bytes;
+    ///
+    /// let released = Arc::new(AtomicBool::new(false));
+    /// let bytes = Bytes::from(vec![0; 1024]);
+    ///
+    /// let flag = released.clone();
+    /// bytes.on_final_drop(move || flag.store(true, Ordering::Relaxed)).ok().unwrap();
+    ///
+    /// let header = bytes.slice(..16);
+    /// drop(bytes);
+    /// assert!(!released.load(Ordering::Relaxed));
+    ///
+    /// drop(header);
+    /// assert!(released.load(Ordering::Relaxed));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Return `hook` if the buffer of `self` isn't allocated by the crate.
+    #[cfg(feature = "drop-hook")]
+    pub fn on_final_drop<F>(&self, hook: F) -> Result<(), F>
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        let is_promotable = ptr::eq(self.vtable, &PROMOTABLE_EVEN_VTABLE)
+            || ptr::eq(self.vtable, &PROMOTABLE_ODD_VTABLE);
+
+        if !is_promotable && !ptr::eq(self.vtable, &SHARED_VTABLE) {
+            return Err(hook);
+        }
+
+        if is_promotable && self.data.load(Ordering::Acquire) as usize & KIND_MASK == KIND_UNSHARED
+        {
+            // Cloning promotes the buffer to a shared buffer which can hold the hooks
+            drop(self.clone());
+        }
+
+        // Safety: the buffer is shared now so the data points to a live `Shared` object
+        let shared = unsafe { &*self.data.load(Ordering::Acquire).cast::<Shared>() };
+        shared.push_hook(Box::new(hook));
+        Ok(())
+    }
+
+    /// Convert `self` into a `BytesMut` without copying its bytes if `self` is the only owner of
+    /// its buffer. The bytes are moved to the start of the buffer so that its whole capacity can
+    /// be reused.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// let bytes = Bytes::from(b"hello".to_vec());
+    /// let clone = bytes.clone();
+    ///
+    /// let bytes = bytes.try_into_mut().err().unwrap();
+    /// drop(clone);
+    ///
+    /// let mut bytes_mut = bytes.try_into_mut().unwrap();
+    /// bytes_mut.extend_from_slice(b" world");
+    ///
+    /// assert_eq!(bytes_mut.as_ref(), b"hello world");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `self` if its buffer is shared with other `Bytes`, is static or is kept alive by
+    /// an owner, or if the global memory budget can't account for its capacity.
+    pub fn try_into_mut(self) -> Result<BytesMut, Bytes> {
+        if self.is_empty() {
+            return Ok(BytesMut::new());
+        }
+
+        // The memory kept alive by an owner doesn't belong to the crate and can't be reused
+        if !self.is_unique() || ptr::eq(self.vtable, &OWNED_VTABLE) {
+            return Err(self);
+        }
+
+        BytesMut::try_from_vec(self.into_vec()).map_err(Bytes::from)
+    }
+
+    /// Return a mutable slice of the bytes of `self`, copying them into a new buffer first if
+    /// the buffer of `self` can't be mutated in place, like `Arc::make_mut`.
+    ///
+    /// The bytes are mutated in place if `self` is the only owner of its buffer. They are copied
+    /// if the buffer is shared with other `Bytes`, is static or is kept alive by an owner, `self`
+    /// then views the new buffer and the other `Bytes` are left untouched.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// let mut bytes = Bytes::from(b"hello world".to_vec());
+    /// let ptr = bytes.as_ptr();
+    ///
+    /// bytes.make_mut()[0] = b'H';
+    /// assert_eq!((bytes.as_ref(), bytes.as_ptr()), (&b"Hello world"[..], ptr));
+    ///
+    /// let clone = bytes.clone();
+    /// bytes.make_mut()[6] = b'W';
+    ///
+    /// assert_eq!(bytes, "Hello World");
+    /// assert_eq!(clone, "Hello world");
+    /// ```
+    pub fn make_mut(&mut self) -> &mut [u8] {
+        if self.is_empty() {
+            return &mut [];
+        }
+
+        if !self.is_unique() || ptr::eq(self.vtable, &OWNED_VTABLE) {
+            *self = Bytes::from(self.as_slice().to_vec());
+        } else if *self.data.get_mut() as usize & KIND_MASK == KIND_SHARED {
+            // The shared and the promoted buffers point to a `Shared` object, the content hash
+            // cached for a view of the buffer is stale once the bytes change
+            let shared = unsafe { &mut *self.data.get_mut().cast::<Shared>() };
+            let cache = mem::replace(shared.hash.get_mut(), ptr::null_mut());
+
+            if !cache.is_null() {
+                drop(unsafe { Box::from_raw(cache) });
+            }
+        }
+
+        // Safety: `self` is the only owner of a buffer allocated by the crate, nothing else
+        // reads or writes its bytes while `self` is mutably borrowed
+        unsafe { slice::from_raw_parts_mut(self.ptr.cast_mut(), self.len) }
+    }
+}
+
+impl Clone for Bytes {
+    #[cfg_attr(feature = "tracing", track_caller)]
+    fn clone(&self) -> Self {
+        self.debug_assert_canonical();
+        lifecycle!("clone", len = self.len, callsite = %core::panic::Location::caller());
+        unsafe { (self.vtable.clone)(&self.data, self.ptr, self.len) }
+    }
+}
+
+impl Drop for Bytes {
+    fn drop(&mut self) {
+        unsafe { (self.vtable.drop)(&mut self.data, self.ptr, self.len) }
+    }
+}
+
+impl Default for Bytes {
+    #[inline]
+    fn default() -> Bytes {
+        Bytes::new()
+    }
+}
+
+impl std::hash::Hash for Bytes {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        let b = self.as_slice();
+        b.hash(state);
+    }
+}
+
+impl Buf for Bytes {
+    #[inline]
+    fn remaining(&self) -> usize {
+        self.len()
+    }
+
+    #[inline]
+    fn chuncks(&self) -> &[u8] {
+        self.as_slice()
+    }
+
+    #[inline]
+    fn remaining_slice(&self) -> Option<&[u8]> {
+        Some(self.as_slice())
+    }
+
+    #[inline]
+    fn fork(&self) -> Bytes {
+        self.clone()
+    }
+
+    #[inline]
+    fn copy_to_bytes(&mut self, len: usize) -> Bytes {
+        self.split_to(len)
+    }
+
+    #[inline]
+    fn advance(&mut self, cnt: usize) {
+        assert!(
+            cnt <= self.len,
+            "cannot advance past the end of the buffer: len ({}) < count ({})",
+            self.len,
+            cnt
+        );
+
+        unsafe { self.inc_start(cnt) }
+        self.normalize_empty();
+    }
+}
+
+unsafe impl Send for Bytes {}
+
+unsafe impl Sync for Bytes {}
+
+// The reference counts are only updated by single atomic operations and the vtable functions
+// don't panic once they started updating them, so a panic can't leave a shared buffer with a
+// wrong count.
+const _: () = crate::assert_unwind_safe::<Bytes>();
+
+// === AsRef, Borrow and Deref
+
+impl Deref for Bytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        self.as_slice()
+    }
+}
+
+impl AsRef<[u8]> for Bytes {
+    fn as_ref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl Borrow<[u8]> for Bytes {
+    fn borrow(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl<'a> IntoIterator for &'a Bytes {
+    type Item = &'a u8;
+    type IntoIter = slice::Iter<'a, u8>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.as_slice().iter()
+    }
+}
+
+impl FromIterator<u8> for Bytes {
+    /// Collect the bytes into a new buffer. The lower bound of the `size_hint` of the iterator
+    /// is reserved upfront, an iterator reporting its exact len is collected with a single
+    /// allocation.
+    fn from_iter<I: IntoIterator<Item = u8>>(iter: I) -> Bytes {
+        Bytes::from(Vec::from_iter(iter))
+    }
+}
+
+// === PartialEq, PartialOrd and Eq
+
+// ** Bytes **
+
+impl PartialEq<Bytes> for Bytes {
+    fn eq(&self, other: &Bytes) -> bool {
+        // Two views of the same memory are equal without comparing the bytes
+        (self.ptr == other.ptr && self.len == other.len) || self.as_slice() == other.as_slice()
+    }
+}
+
+impl PartialOrd<Bytes> for Bytes {
+    fn partial_cmp(&self, other: &Bytes) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Bytes {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        self.as_slice().cmp(other.as_slice())
+    }
+}
+
+impl Eq for Bytes {}
+
+// ** [u8] **
+
+impl PartialEq<[u8]> for Bytes {
+    fn eq(&self, other: &[u8]) -> bool {
+        self.as_slice() == other
+    }
+}
+
+impl PartialOrd<[u8]> for Bytes {
+    fn partial_cmp(&self, other: &[u8]) -> Option<cmp::Ordering> {
+        Some(self.as_slice().cmp(other))
+    }
+}
+
+impl<'a> PartialEq<&'a [u8]> for Bytes {
+    fn eq(&self, other: &&'a [u8]) -> bool {
+        self.as_slice() == *other
+    }
+}
+
+impl<'a> PartialOrd<&'a [u8]> for Bytes {
+    fn partial_cmp(&self, other: &&'a [u8]) -> Option<cmp::Ordering> {
+        Some(self.as_slice().cmp(*other))
+    }
+}
+
+impl PartialEq<Bytes> for [u8] {
+    fn eq(&self, other: &Bytes) -> bool {
+        self == other.as_slice()
+    }
+}
+
+impl PartialOrd<Bytes> for [u8] {
+    fn partial_cmp(&self, other: &Bytes) -> Option<cmp::Ordering> {
+        Some(self.cmp(other.as_slice()))
+    }
+}
+
+impl PartialEq<Bytes> for &[u8] {
+    fn eq(&self, other: &Bytes) -> bool {
+        *self == other.as_slice()
+    }
+}
+
+impl PartialOrd<Bytes> for &[u8] {
+    fn partial_cmp(&self, other: &Bytes) -> Option<cmp::Ordering> {
+        Some((*self).cmp(other.as_slice()))
+    }
+}
+
+// ** str **
+
+impl PartialEq<str> for Bytes {
+    fn eq(&self, other: &str) -> bool {
+        self.as_slice() == other.as_bytes()
+    }
+}
+
+impl PartialOrd<str> for Bytes {
+    fn partial_cmp(&self, other: &str) -> Option<cmp::Ordering> {
+        Some(self.as_slice().cmp(other.as_bytes()))
+    }
+}
+
+impl<'a> PartialEq<&'a str> for Bytes {
+    fn eq(&self, other: &&'a str) -> bool {
+        self.as_slice() == other.as_bytes()
+    }
+}
+
+impl<'a> PartialOrd<&'a str> for Bytes {
+    fn partial_cmp(&self, other: &&'a str) -> Option<cmp::Ordering> {
+        Some(self.as_slice().cmp(other.as_bytes()))
+    }
+}
+
+impl PartialEq<Bytes> for str {
+    fn eq(&self, other: &Bytes) -> bool {
+        self.as_bytes() == other.as_slice()
+    }
+}
+
+impl PartialOrd<Bytes> for str {
+    fn partial_cmp(&self, other: &Bytes) -> Option<cmp::Ordering> {
+        Some(self.as_bytes().cmp(other.as_slice()))
+    }
+}
+
+impl PartialEq<Bytes> for &str {
+    fn eq(&self, other: &Bytes) -> bool {
+        self.as_bytes() == other.as_slice()
+    }
+}
+
+impl PartialOrd<Bytes> for &str {
+    fn partial_cmp(&self, other: &Bytes) -> Option<cmp::Ordering> {
+        Some(self.as_bytes().cmp(other.as_slice()))
+    }
+}
+
+// ** Vec<u8> **
+
+impl PartialEq<Vec<u8>> for Bytes {
+    fn eq(&self, other: &Vec<u8>) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl PartialOrd<Vec<u8>> for Bytes {
+    fn partial_cmp(&self, other: &Vec<u8>) -> Option<cmp::Ordering> {
+        Some(self.as_slice().cmp(other.as_slice()))
+    }
+}
+
+impl PartialEq<Bytes> for Vec<u8> {
+    fn eq(&self, other: &Bytes) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl PartialOrd<Bytes> for Vec<u8> {
+    fn partial_cmp(&self, other: &Bytes) -> Option<cmp::Ordering> {
+        Some(self.as_slice().cmp(other.as_slice()))
+    }
+}
+
+// ** String **
+
+impl PartialEq<String> for Bytes {
+    fn eq(&self, other: &String) -> bool {
+        self.as_slice() == other.as_bytes()
+    }
+}
+
+impl PartialOrd<String> for Bytes {
+    fn partial_cmp(&self, other: &String) -> Option<cmp::Ordering> {
+        Some(self.as_slice().cmp(other.as_bytes()))
+    }
+}
+
+impl PartialEq<Bytes> for String {
+    fn eq(&self, other: &Bytes) -> bool {
+        self.as_bytes() == other.as_slice()
+    }
+}
+
+impl PartialOrd<Bytes> for String {
+    fn partial_cmp(&self, other: &Bytes) -> Option<cmp::Ordering> {
+        Some(self.as_bytes().cmp(other.as_slice()))
+    }
+}
+
+// ** BytesMut **
+
+impl PartialEq<BytesMut> for Bytes {
+    fn eq(&self, other: &BytesMut) -> bool {
+        self.as_slice() == other.as_ref()
+    }
+}
+
+impl PartialOrd<BytesMut> for Bytes {
+    fn partial_cmp(&self, other: &BytesMut) -> Option<cmp::Ordering> {
+        Some(self.as_slice().cmp(other.as_ref()))
+    }
+}
+
+impl PartialEq<Bytes> for BytesMut {
+    fn eq(&self, other: &Bytes) -> bool {
+        self.as_ref() == other.as_slice()
+    }
+}
+
+impl PartialOrd<Bytes> for BytesMut {
+    fn partial_cmp(&self, other: &Bytes) -> Option<cmp::Ordering> {
+        Some(self.as_ref().cmp(other.as_slice()))
+    }
+}
+
+// === From ===
+
+impl From<Vec<u8>> for Bytes {
+    #[cfg_attr(feature = "tracing", track_caller)]
+    fn from(mut value: Vec<u8>) -> Self {
+        let len = value.len();
+        let cap = value.capacity();
+        let ptr = value.as_mut_ptr();
+
+        if len == 0 {
+            return Bytes::new();
+        }
+
+        // Avoid allocating new memory if possible
+        if len == cap {
+            return Bytes::from(value.into_boxed_slice());
+        }
+
+        let shared = Box::new(Shared::new(ptr, cap, 1));
+
+        mem::forget(value);
+        let shared = Box::into_raw(shared);
+
+        lifecycle!("alloc", ptr = ?ptr, len, cap, callsite = %core::panic::Location::caller());
+
+        Bytes {
+            ptr,
+            len,
+            data: AtomicPtr::new(shared.cast()),
+            vtable: &SHARED_VTABLE,
+        }
+    }
+}
+
+impl From<Box<[u8]>> for Bytes {
+    #[cfg_attr(feature = "tracing", track_caller)]
+    fn from(value: Box<[u8]>) -> Self {
+        // `Box` doesn't allocate memory for empty slices so we don't care about it
+        if value.is_empty() {
+            return Bytes::new();
+        }
+
+        let len = value.len();
+        let ptr = Box::into_raw(value) as *mut u8;
+
+        lifecycle!("alloc", ptr = ?ptr, len, cap = len, callsite = %core::panic::Location::caller());
+
+        if ptr as usize & KIND_MASK == 0 {
+            // We set the kind of the ptr to `KIND_UNSHARED` so that it can be shared
+            // later on
+            let data = map_ptr(ptr, |p| p | KIND_UNSHARED);
+            Bytes {
+                ptr,
+                len,
+                data: AtomicPtr::new(data.cast()),
+                vtable: &PROMOTABLE_EVEN_VTABLE,
+            }
+        } else {
+            Bytes {
+                ptr,
+                len,
+                data: AtomicPtr::new(ptr.cast()),
+                vtable: &PROMOTABLE_ODD_VTABLE,
+            }
+        }
+    }
+}
+
+impl From<String> for Bytes {
+    /// Convert a `String` into a `Bytes`, reusing its allocation.
+    #[cfg_attr(feature = "tracing", track_caller)]
+    fn from(value: String) -> Self {
+        Bytes::from(value.into_bytes())
+    }
+}
+
+impl From<Cow<'static, [u8]>> for Bytes {
+    /// Convert a `Cow` into a `Bytes`, a borrowed slice is viewed as a static buffer without
+    /// allocating and an owned vector is moved into the `Bytes`.
+    #[cfg_attr(feature = "tracing", track_caller)]
+    fn from(value: Cow<'static, [u8]>) -> Self {
+        match value {
+            Cow::Borrowed(slice) => Bytes::from_static(slice),
+            Cow::Owned(vec) => Bytes::from(vec),
+        }
+    }
+}
+
+impl From<Cow<'static, str>> for Bytes {
+    /// Convert a `Cow` into a `Bytes` like the conversion from `Cow<'static, [u8]>`.
+    #[cfg_attr(feature = "tracing", track_caller)]
+    fn from(value: Cow<'static, str>) -> Self {
+        match value {
+            Cow::Borrowed(str) => Bytes::from_static(str.as_bytes()),
+            Cow::Owned(string) => Bytes::from(string),
+        }
+    }
+}
+
+impl From<Arc<[u8]>> for Bytes {
+    /// Convert an `Arc<[u8]>` into a `Bytes` viewing its bytes, without copying them. The `Arc`
+    /// is kept alive by the returned `Bytes` and its clones, the bytes are freed once they and
+    /// the other clones of the `Arc` are all dropped.
+    fn from(value: Arc<[u8]>) -> Self {
+        Bytes::from_owner(value)
+    }
+}
+
+impl From<Arc<Vec<u8>>> for Bytes {
+    /// Convert an `Arc<Vec<u8>>` into a `Bytes` viewing its bytes, without copying them. See
+    /// the conversion from `Arc<[u8]>`.
+    fn from(value: Arc<Vec<u8>>) -> Self {
+        let (ptr, len) = (value.as_ptr(), value.len());
+
+        // Safety: the vector is behind an `Arc`, it can't be mutated nor moved while the `Arc`
+        // kept by the `Bytes` is alive
+        unsafe { Bytes::with_owner(value, ptr, len) }
+    }
+}
+
+impl From<Bytes> for Vec<u8> {
+    /// Convert a `Bytes` into a `Vec<u8>`, reusing the allocation if `value` is the only owner
+    /// of its buffer. The bytes are moved to the start of the allocation if `value` only views
+    /// a part of it. Otherwise, e.g. for a shared or static buffer, the bytes are copied.
+    fn from(value: Bytes) -> Vec<u8> {
+        value.into_vec()
+    }
+}
+
+impl From<&'static str> for Bytes {
+    #[inline]
+    fn from(value: &'static str) -> Self {
+        Bytes::from_static(value.as_bytes())
+    }
+}
+
+impl From<&'static [u8]> for Bytes {
+    #[inline]
+    fn from(value: &'static [u8]) -> Self {
+        Bytes::from_static(value)
+    }
+}
+
+impl<const N: usize> From<&'static [u8; N]> for Bytes {
+    /// View a static array without allocating, e.g. `Bytes::from(b"PING")`.
+    #[inline]
+    fn from(value: &'static [u8; N]) -> Self {
+        Bytes::from_static(value)
+    }
+}
+
+impl<const N: usize> From<[u8; N]> for Bytes {
+    /// Move the array into a new buffer, e.g. `Bytes::from(*b"PING")` or
+    /// `Bytes::from(n.to_be_bytes())`.
+    #[inline]
+    fn from(value: [u8; N]) -> Self {
+        Bytes::from(Box::<[u8]>::from(value))
+    }
+}
+
+// === Into ===
+
+impl TryFrom<Bytes> for String {
+    type Error = FromUtf8Error;
+
+    /// Convert a `Bytes` into a `String` if it's made of valid utf8. The allocation is reused if
+    /// the `Bytes` is the only owner of its buffer.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// let bytes = Bytes::from(b"hello".to_vec());
+    /// assert_eq!(String::try_from(bytes).unwrap(), "hello");
+    ///
+    /// let bytes = Bytes::from_static(b"\xff");
+    /// let err = String::try_from(bytes).unwrap_err();
+    /// assert_eq!(err.into_bytes(), b"\xff"[..]);
     /// ```
+    fn try_from(value: Bytes) -> Result<String, FromUtf8Error> {
+        match str::from_utf8(&value) {
+            // Safety: the bytes were just checked to be valid utf8
+            Ok(_) => Ok(unsafe { String::from_utf8_unchecked(value.into_vec()) }),
+            Err(error) => Err(FromUtf8Error::new(value, error)),
+        }
+    }
+}
+
+impl<'a> TryFrom<&'a Bytes> for &'a str {
+    type Error = str::Utf8Error;
+
     #[inline]
-    pub fn as_slice(&self) -> &[u8] {
-        unsafe { slice::from_raw_parts(self.ptr, self.len) }
+    fn try_from(value: &'a Bytes) -> Result<&'a str, str::Utf8Error> {
+        str::from_utf8(value)
+    }
+}
+
+/// The error returned when converting a `Bytes` made of invalid utf8 into a string.
+///
+/// The original `Bytes` can be retrieved with [`FromUtf8Error::into_bytes`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FromUtf8Error {
+    bytes: Bytes,
+    error: str::Utf8Error,
+}
+
+impl FromUtf8Error {
+    #[inline]
+    pub(crate) fn new(bytes: Bytes, error: str::Utf8Error) -> FromUtf8Error {
+        FromUtf8Error { bytes, error }
+    }
+
+    /// Return the bytes that failed to be converted.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Consume `self` and return the bytes that failed to be converted.
+    #[inline]
+    pub fn into_bytes(self) -> Bytes {
+        self.bytes
+    }
+
+    /// Return the details of the conversion failure.
+    #[inline]
+    pub fn utf8_error(&self) -> str::Utf8Error {
+        self.error
+    }
+}
+
+impl fmt::Display for FromUtf8Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.error, f)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for FromUtf8Error {}
+
+/// The error returned by [`Bytes::try_slice`] when the range is invalid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SliceError {
+    /// The excluded start of the range is `usize::MAX`.
+    StartOverflow,
+    /// The included end of the range is `usize::MAX`.
+    EndOverflow,
+    /// The start of the range is greater than its end.
+    StartAfterEnd {
+        /// The start of the range.
+        start: usize,
+        /// The end of the range.
+        end: usize,
+    },
+    /// The end of the range is greater than the len of the buffer.
+    EndOutOfBounds {
+        /// The end of the range.
+        end: usize,
+        /// The len of the buffer.
+        len: usize,
+    },
+}
+
+impl fmt::Display for SliceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SliceError::StartOverflow => write!(f, "start overflow"),
+            SliceError::EndOverflow => write!(f, "end overflow"),
+            SliceError::StartAfterEnd { start, end } => {
+                write!(f, "start ({}) > end ({})", start, end)
+            }
+            SliceError::EndOutOfBounds { end, len } => {
+                write!(f, "end out of bounds: end ({}) > len ({})", end, len)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SliceError {}
+
+/// The error returned by the `*_checked` methods when an index is greater than the len of the
+/// buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutOfBounds {
+    index: usize,
+    len: usize,
+}
+
+impl OutOfBounds {
+    /// Return an error if `index` > `len`.
+    #[inline]
+    pub(crate) fn check(index: usize, len: usize) -> Result<(), OutOfBounds> {
+        if index > len {
+            Err(OutOfBounds { index, len })
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Return the index which was out of bounds.
+    #[inline]
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Return the greatest valid index, i.e. the len of the buffer.
+    #[inline]
+    pub fn limit(&self) -> usize {
+        self.len
+    }
+}
+
+impl fmt::Display for OutOfBounds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "index out of bounds: at ({}) > len ({})",
+            self.index, self.len
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for OutOfBounds {}
+
+// === Vtables ===
+// === Static vtable ===
+
+static STATIC_VTABLE: Vtable = Vtable {
+    clone: static_clone,
+    drop: static_drop,
+    into_vec: static_into_vec,
+    ref_count: static_ref_count,
+};
+
+unsafe fn static_clone(_: &AtomicPtr<()>, ptr: *const u8, len: usize) -> Bytes {
+    // Because the underlying value is static we don't care about
+    // the reference counter
+    let slice = slice::from_raw_parts(ptr, len);
+    Bytes::from_static(slice)
+}
+
+unsafe fn static_drop(_: &mut AtomicPtr<()>, _: *const u8, _: usize) {
+    // Nothing to do
+}
+
+unsafe fn static_into_vec(_: &mut AtomicPtr<()>, ptr: *const u8, len: usize) -> Vec<u8> {
+    slice::from_raw_parts(ptr, len).to_vec()
+}
+
+unsafe fn static_ref_count(_: &AtomicPtr<()>) -> Option<usize> {
+    // The static memory is never freed so the clones are not counted
+    None
+}
+
+// === Promotable vtable ===
+// This is used to create `Bytes` from data already on the heap
+// It avoids changing the data location if there is only one object
+// using this data but it changes the location whenever the `Bytes` object is cloned
+
+// Mask used to determine if a values needs to be promoted to a shared `Bytes`
+const KIND_UNSHARED: usize = 0x1;
+const KIND_SHARED: usize = 0x0;
+const KIND_MASK: usize = 0x1;
+
+static PROMOTABLE_ODD_VTABLE: Vtable = Vtable {
+    clone: promotable_odd_clone,
+    drop: promotable_odd_drop,
+    into_vec: promotable_odd_into_vec,
+    ref_count: promotable_ref_count,
+};
+
+unsafe fn promotable_odd_clone(data: &AtomicPtr<()>, ptr: *const u8, len: usize) -> Bytes {
+    let shared = data.load(Ordering::Relaxed);
+    let kind = shared as usize & KIND_MASK;
+
+    if kind == KIND_SHARED {
+        shallow_clone_arc(shared.cast(), ptr, len)
+    } else {
+        debug_assert_eq!(kind, KIND_UNSHARED);
+        shallow_clone_vec(data, shared, shared.cast(), ptr, len)
+    }
+}
+
+unsafe fn promotable_odd_drop(data: &mut AtomicPtr<()>, ptr: *const u8, len: usize) {
+    let data = data.get_mut();
+    let shared = *data;
+    let kind = shared as usize & KIND_MASK;
+
+    if kind == KIND_SHARED {
+        release_shared(shared.cast())
+    } else {
+        debug_assert_eq!(kind, KIND_UNSHARED);
+        free_boxed_slice(shared.cast(), ptr, len)
+    }
+}
+
+static PROMOTABLE_EVEN_VTABLE: Vtable = Vtable {
+    clone: promotable_even_clone,
+    drop: promotable_even_drop,
+    into_vec: promotable_even_into_vec,
+    ref_count: promotable_ref_count,
+};
+
+unsafe fn promotable_even_clone(data: &AtomicPtr<()>, ptr: *const u8, len: usize) -> Bytes {
+    let shared = data.load(Ordering::Relaxed);
+    let kind = shared as usize & KIND_MASK;
+
+    if kind == KIND_SHARED {
+        shallow_clone_arc(shared.cast(), ptr, len)
+    } else {
+        debug_assert_eq!(kind, KIND_UNSHARED);
+        let buf = map_ptr(shared.cast(), |p| p & !KIND_MASK);
+        shallow_clone_vec(data, shared, buf, ptr, len)
     }
 }
 
-impl Clone for Bytes {
-    fn clone(&self) -> Self {
-        unsafe { (self.vtable.clone)(&self.data, self.ptr, self.len) }
+unsafe fn promotable_even_drop(data: &mut AtomicPtr<()>, ptr: *const u8, len: usize) {
+    let data = data.get_mut();
+    let shared = *data;
+    let kind = shared as usize & KIND_MASK;
+
+    if kind == KIND_SHARED {
+        release_shared(shared.cast())
+    } else {
+        debug_assert_eq!(kind, KIND_UNSHARED);
+        let buf = map_ptr(shared.cast(), |p| p & !KIND_MASK);
+        free_boxed_slice(buf, ptr, len)
     }
 }
 
-impl Drop for Bytes {
-    fn drop(&mut self) {
-        unsafe { (self.vtable.drop)(&mut self.data, self.ptr, self.len) }
+unsafe fn promotable_ref_count(data: &AtomicPtr<()>) -> Option<usize> {
+    let shared = data.load(Ordering::Acquire);
+
+    if shared as usize & KIND_MASK == KIND_UNSHARED {
+        // The buffer is promoted on the first clone
+        Some(1)
+    } else {
+        Some(shared_ref_count_impl(shared.cast()))
     }
 }
 
-impl Default for Bytes {
+unsafe fn promotable_odd_into_vec(data: &mut AtomicPtr<()>, ptr: *const u8, len: usize) -> Vec<u8> {
+    promotable_into_vec(data, ptr, len, |shared| shared.cast())
+}
+
+unsafe fn promotable_even_into_vec(
+    data: &mut AtomicPtr<()>,
+    ptr: *const u8,
+    len: usize,
+) -> Vec<u8> {
+    promotable_into_vec(data, ptr, len, |shared| {
+        map_ptr(shared.cast(), |p| p & !KIND_MASK)
+    })
+}
+
+unsafe fn promotable_into_vec<F>(
+    data: &mut AtomicPtr<()>,
+    ptr: *const u8,
+    len: usize,
+    to_buf: F,
+) -> Vec<u8>
+where
+    F: FnOnce(*mut ()) -> *mut u8,
+{
+    let shared = *data.get_mut();
+    let kind = shared as usize & KIND_MASK;
+
+    if kind == KIND_SHARED {
+        shared_into_vec_impl(shared.cast(), ptr, len)
+    } else {
+        debug_assert_eq!(kind, KIND_UNSHARED);
+        let buf = to_buf(shared);
+        let cap = (ptr as usize - buf as usize) + len;
+
+        // The buffer is not shared so the bytes can be moved to the start of the allocation
+        ptr::copy(ptr, buf, len);
+        Vec::from_raw_parts(buf, len, cap)
+    }
+}
+
+// === Shared vtable ===
+
+static SHARED_VTABLE: Vtable = Vtable {
+    clone: shared_clone,
+    drop: shared_drop,
+    into_vec: shared_into_vec,
+    ref_count: shared_ref_count,
+};
+
+unsafe fn shared_clone(data: &AtomicPtr<()>, ptr: *const u8, len: usize) -> Bytes {
+    let shared = data.load(Ordering::Relaxed);
+    shallow_clone_arc(shared.cast(), ptr, len)
+}
+
+unsafe fn shared_drop(data: &mut AtomicPtr<()>, _: *const u8, _: usize) {
+    let shared: *mut Shared = data.get_mut().cast();
+    release_shared(shared)
+}
+
+unsafe fn shared_ref_count(data: &AtomicPtr<()>) -> Option<usize> {
+    Some(shared_ref_count_impl(data.load(Ordering::Relaxed).cast()))
+}
+
+unsafe fn shared_ref_count_impl(shared: *mut Shared) -> usize {
+    // Synchronizes with the `Release` decrements of the dropped owners so that their accesses
+    // happen before the buffer is reused
+    (*shared).ref_cnt.load(Ordering::Acquire)
+}
+
+unsafe fn shared_into_vec(data: &mut AtomicPtr<()>, ptr: *const u8, len: usize) -> Vec<u8> {
+    shared_into_vec_impl(data.get_mut().cast(), ptr, len)
+}
+
+unsafe fn shared_into_vec_impl(shared: *mut Shared, ptr: *const u8, len: usize) -> Vec<u8> {
+    // If we are the only owner nobody else can acquire a reference anymore, the `Acquire`
+    // ordering synchronizes with the `Release` decrements of the previous owners
+    if (*shared)
+        .ref_cnt
+        .compare_exchange(1, 0, Ordering::Acquire, Ordering::Relaxed)
+        .is_err()
+    {
+        let vec = slice::from_raw_parts(ptr, len).to_vec();
+        release_shared(shared);
+        return vec;
+    }
+
+    let buf = (*shared).buf;
+    let cap = (*shared).cap;
+
+    // The buffer is moved into the vector so only the shared object is freed
+    (*shared).cap = 0;
+    drop(Box::from_raw(shared));
+
+    if cap == 0 {
+        return Vec::new();
+    }
+
+    ptr::copy(ptr, buf, len);
+    Vec::from_raw_parts(buf, len, cap)
+}
+
+pub(crate) unsafe fn shallow_clone_arc(shared: *mut Shared, ptr: *const u8, len: usize) -> Bytes {
+    (*shared).ref_cnt.fetch_add(1, Ordering::Release);
+
+    Bytes {
+        ptr,
+        len,
+        data: AtomicPtr::new(shared.cast()),
+        vtable: &SHARED_VTABLE,
+    }
+}
+
+unsafe fn shallow_clone_vec(
+    atom: &AtomicPtr<()>,
+    ptr: *const (),
+    buf: *mut u8,
+    offset: *const u8,
+    len: usize,
+) -> Bytes {
+    let shared = Box::new(Shared::new(buf, (offset as usize - buf as usize) + len, 2));
+
+    let shared = Box::into_raw(shared);
+
+    // Verif that the pointer is aligned
+    // This is ensured by the `Box` API so this assert should not fail
+    debug_assert_eq!(
+        shared as usize & KIND_MASK,
+        KIND_SHARED,
+        "internal Box<Shared> should have an aligned pointer"
+    );
+
+    match atom.compare_exchange(ptr as _, shared.cast(), Ordering::AcqRel, Ordering::Acquire) {
+        Ok(actual) => {
+            debug_assert_eq!(actual as usize, ptr as usize);
+            lifecycle!("promote", ptr = ?buf, cap = (*shared).cap);
+
+            // Exchange was successful so we can return the new `Bytes` value
+            Bytes {
+                ptr: offset,
+                len,
+                data: AtomicPtr::new(shared.cast()),
+                vtable: &SHARED_VTABLE,
+            }
+        }
+        Err(actual) => {
+            // The exchange was made by an other thread so we acquire the value
+            // created by this other thread and we clone it into a new `Bytes` object
+
+            // Free the shared object we just allocated without running its destructor, the
+            // buffer is now owned by the shared object created by the other thread
+            let shared: Box<Shared> = Box::from_raw(shared);
+            mem::forget(*shared);
+
+            // Create an Arc copy of the `Bytes` object using the acquired new shared value
+            shallow_clone_arc(actual.cast(), offset, len)
+        }
+    }
+}
+
+pub(crate) unsafe fn release_shared(shared: *mut Shared) {
+    // If this is diffetent from 1 than we don't need to drop the value
+    if (*shared).ref_cnt.fetch_sub(1, Ordering::Release) != 1 {
+        return;
+    }
+
+    // Else we need to drop the underlying value. The fence synchronizes with the `Release`
+    // decrements of the other owners so that all their accesses happen before the drop.
+    atomic::fence(Ordering::Acquire);
+    drop(Box::from_raw(shared))
+}
+
+unsafe fn free_boxed_slice(buf: *mut u8, offset: *const u8, len: usize) {
+    let cap = (offset as usize - buf as usize) + len;
+    lifecycle!("drop", ptr = ?buf, cap);
+    // TODO:
+    // Safety: ?value
+    dealloc(buf, Layout::from_size_align_unchecked(cap, 1))
+}
+
+pub(crate) struct Shared {
+    pub(crate) buf: *mut u8,
+    pub(crate) cap: usize,
+    pub(crate) ref_cnt: AtomicUsize,
+    /// The content hash of the first view of the buffer which asked for it, null until then
+    hash: AtomicPtr<HashCache>,
+    /// The last attached hook of the list of hooks called once the buffer is freed
+    #[cfg(feature = "drop-hook")]
+    hooks: AtomicPtr<DropHook>,
+}
+
+impl Shared {
     #[inline]
-    fn default() -> Bytes {
-        Bytes::new()
+    pub(crate) fn new(buf: *mut u8, cap: usize, ref_cnt: usize) -> Shared {
+        Shared {
+            buf,
+            cap,
+            ref_cnt: AtomicUsize::new(ref_cnt),
+            hash: AtomicPtr::new(ptr::null_mut()),
+            #[cfg(feature = "drop-hook")]
+            hooks: AtomicPtr::new(ptr::null_mut()),
+        }
+    }
+
+    #[cfg(feature = "drop-hook")]
+    fn push_hook(&self, hook: Box<dyn FnOnce() + Send>) {
+        let node = Box::into_raw(Box::new(DropHook {
+            hook,
+            next: ptr::null_mut(),
+        }));
+        let mut head = self.hooks.load(Ordering::Relaxed);
+
+        loop {
+            // Safety: `node` is not published yet, it's only accessed by this thread
+            unsafe { (*node).next = head };
+
+            match self
+                .hooks
+                .compare_exchange_weak(head, node, Ordering::Release, Ordering::Relaxed)
+            {
+                Ok(_) => return,
+                Err(actual) => head = actual,
+            }
+        }
     }
 }
 
-impl std::hash::Hash for Bytes {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        let b = self.as_slice();
-        b.hash(state);
+/// A hook attached with [`Bytes::on_final_drop`], linked to the hook attached before it.
+#[cfg(feature = "drop-hook")]
+struct DropHook {
+    hook: Box<dyn FnOnce() + Send>,
+    next: *mut DropHook,
+}
+
+/// The content hash of the view `ptr..ptr + len` of a shared buffer.
+struct HashCache {
+    ptr: *const u8,
+    len: usize,
+    hash: u64,
+}
+
+// Verify that the |Shared` struct size is divisible by 2 because we want to use the LSB has a flag.
+const _: [(); 0 - mem::size_of::<Shared>() % 2] = [];
+
+impl Drop for Shared {
+    fn drop(&mut self) {
+        let hash = *self.hash.get_mut();
+
+        if !hash.is_null() {
+            drop(unsafe { Box::from_raw(hash) });
+        }
+
+        lifecycle!("drop", ptr = ?self.buf, cap = self.cap);
+
+        // Empty buffers don't own any allocation
+        if self.cap != 0 {
+            unsafe { dealloc(self.buf, Layout::from_size_align(self.cap, 1).unwrap()) }
+        }
+
+        #[cfg(feature = "drop-hook")]
+        {
+            let mut node = *self.hooks.get_mut();
+
+            while !node.is_null() {
+                // Safety: the hooks were leaked from boxes and `self` is their only owner
+                let hook = unsafe { Box::from_raw(node) };
+                node = hook.next;
+                (hook.hook)();
+            }
+        }
     }
 }
 
-unsafe impl Send for Bytes {}
+// === Owned vtable ===
+// This is used to create `Bytes` from data kept alive by an owner of any type. The owner is
+// boxed behind a type erased header holding the reference count and the function dropping it.
 
-unsafe impl Sync for Bytes {}
+static OWNED_VTABLE: Vtable = Vtable {
+    clone: owned_clone,
+    drop: owned_drop,
+    into_vec: owned_into_vec,
+    ref_count: owned_ref_count,
+};
+
+#[repr(C)]
+struct OwnedHeader {
+    ref_cnt: AtomicUsize,
+    drop: unsafe fn(*mut OwnedHeader),
+}
+
+// The header is the first field so that a pointer to `Owned<T>` is a pointer to its header
+#[repr(C)]
+struct Owned<T> {
+    header: OwnedHeader,
+    owner: T,
+}
+
+/// Move `owner` to the heap behind a header with a reference count of `1`.
+fn box_owner<T: Send + 'static>(owner: T) -> *mut Owned<T> {
+    Box::into_raw(Box::new(Owned {
+        header: OwnedHeader {
+            ref_cnt: AtomicUsize::new(1),
+            drop: drop_owned::<T>,
+        },
+        owner,
+    }))
+}
+
+unsafe fn drop_owned<T>(header: *mut OwnedHeader) {
+    drop(Box::from_raw(header.cast::<Owned<T>>()))
+}
+
+unsafe fn owned_clone(data: &AtomicPtr<()>, ptr: *const u8, len: usize) -> Bytes {
+    let header: *mut OwnedHeader = data.load(Ordering::Relaxed).cast();
+    (*header).ref_cnt.fetch_add(1, Ordering::Relaxed);
+
+    Bytes {
+        ptr,
+        len,
+        data: AtomicPtr::new(header.cast()),
+        vtable: &OWNED_VTABLE,
+    }
+}
+
+unsafe fn owned_drop(data: &mut AtomicPtr<()>, _: *const u8, _: usize) {
+    let header: *mut OwnedHeader = data.get_mut().cast();
+
+    if (*header).ref_cnt.fetch_sub(1, Ordering::Release) != 1 {
+        return;
+    }
+
+    atomic::fence(Ordering::Acquire);
+    ((*header).drop)(header)
+}
+
+unsafe fn owned_ref_count(data: &AtomicPtr<()>) -> Option<usize> {
+    let header: *mut OwnedHeader = data.load(Ordering::Relaxed).cast();
+    Some((*header).ref_cnt.load(Ordering::Acquire))
+}
+
+unsafe fn owned_into_vec(data: &mut AtomicPtr<()>, ptr: *const u8, len: usize) -> Vec<u8> {
+    // The memory belongs to the owner so it's always copied
+    let vec = slice::from_raw_parts(ptr, len).to_vec();
+    owned_drop(data, ptr, len);
+    vec
+}
+
+// === Handfull functions to manipulate pointers ===
+
+fn fnv1a64(bytes: &[u8]) -> u64 {
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    bytes
+        .iter()
+        .fold(OFFSET, |hash, b| (hash ^ *b as u64).wrapping_mul(PRIME))
+}
+
+fn map_ptr<F>(ptr: *mut u8, f: F) -> *mut u8
+where
+    F: FnOnce(usize) -> usize,
+{
+    let old_ptr = ptr as usize;
+    let new_ptr = f(old_ptr);
+    new_ptr as *mut u8
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_alloc;
+
+    macro_rules! assert_iter {
+        ($bytes:literal) => {
+            let bytes = Bytes::from_static($bytes);
+            assert_iter!(bytes => $bytes);
+        };
+        ($bytes:ident => $lit:literal) => {
+            let mut iter = $lit.into_iter().enumerate();
+
+            while let Some((index, byte)) = iter.next() {
+                assert_eq!($bytes.get(index), *byte);
+            }
+        }
+    }
+
+    #[test]
+    fn static_bytes() {
+        assert_iter!(b"this is a static bytes");
+        assert_iter!(b"another static bytes");
+    }
+
+    #[test]
+    fn static_clone() {
+        let bytes = Bytes::from_static(b"a static byte");
+        let clone = bytes.clone();
+
+        assert_eq!(bytes.ptr, clone.ptr);
+        assert_iter!(bytes => b"a static byte");
+        assert_iter!(clone => b"a static byte");
+    }
+
+    #[test]
+    fn shared_vec_clone() {
+        let bytes = Bytes::from(b"toto".to_vec());
+        let clone = bytes.clone();
 
-// === AsRef, Borrow and Deref
+        assert_eq!(bytes.ptr, clone.ptr);
+        assert_iter!(bytes => b"toto");
+        assert_iter!(clone => b"toto");
+    }
 
-impl Deref for Bytes {
-    type Target = [u8];
+    #[test]
+    fn shared_box_clone() {
+        let boxed = b"toto".to_vec().into_boxed_slice();
+        let bytes = Bytes::from(boxed);
+        let clone = bytes.clone();
 
-    fn deref(&self) -> &Self::Target {
-        self.as_slice()
+        assert_eq!(bytes.ptr, clone.ptr);
+        assert_iter!(bytes => b"toto");
+        assert_iter!(clone => b"toto");
     }
-}
 
-impl AsRef<[u8]> for Bytes {
-    fn as_ref(&self) -> &[u8] {
-        self.as_slice()
-    }
-}
+    #[test]
+    fn copy_from_slice() {
+        let bytes = Bytes::copy_from_slice(b"toto");
 
-impl Borrow<[u8]> for Bytes {
-    fn borrow(&self) -> &[u8] {
-        self.as_slice()
+        assert_iter!(bytes => b"toto");
     }
-}
 
-impl<'a> IntoIterator for &'a Bytes {
-    type Item = &'a u8;
-    type IntoIter = slice::Iter<'a, u8>;
+    #[test]
+    fn index() {
+        let bytes = Bytes::from_static(b"this is a very long long bytes slice");
 
-    fn into_iter(self) -> Self::IntoIter {
-        self.as_slice().iter()
+        assert_eq!(b"this", &bytes[..4]);
+        assert_eq!(b"very long long bytes", &bytes[10..30]);
+        assert_eq!(b"this is a very long long bytes slice", &bytes[..]);
     }
-}
 
-// === PartialEq, PartialOrd and Eq
+    #[test]
+    fn slice() {
+        let bytes = Bytes::from_static(b"this is a very long long bytes slice");
 
-// ** Bytes **
+        let slice = bytes.slice(10..30);
 
-impl PartialEq<Bytes> for Bytes {
-    fn eq(&self, other: &Bytes) -> bool {
-        self.as_slice() == other.as_slice()
+        assert_eq!(b"very long long bytes", &slice[..]);
     }
-}
 
-impl PartialOrd<Bytes> for Bytes {
-    fn partial_cmp(&self, other: &Bytes) -> Option<std::cmp::Ordering> {
-        self.as_slice().partial_cmp(other.as_slice())
+    #[test]
+    fn from_string_reuses_allocation() {
+        let mut string = String::with_capacity(32);
+        string.push_str("hello world");
+        let ptr = string.as_ptr();
+
+        // Only the shared header is allocated, the bytes are not copied
+        let (bytes, allocs) = test_alloc::count_allocs(|| Bytes::from(string));
+        assert_eq!(allocs, 1);
+        assert_eq!(bytes.as_ptr(), ptr);
+
+        // A `Vec` without spare capacity is turned into a boxed slice in place
+        let vec = b"hello world".to_vec();
+        let ptr = vec.as_ptr();
+        let (bytes, allocs) = test_alloc::count_allocs(|| Bytes::from(vec));
+        assert_eq!(allocs, 0);
+        assert_eq!(bytes.as_ptr(), ptr);
     }
-}
 
-impl Ord for Bytes {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.as_slice().cmp(other.as_slice())
+    #[test]
+    fn ref_count() {
+        let promotable = Bytes::from(b"hello".to_vec());
+        assert!(promotable.is_unique());
+
+        let clones = [promotable.clone(), promotable.slice(2..)];
+        assert_eq!(promotable.ref_count(), Some(3));
+        drop(clones);
+        assert!(promotable.is_unique());
+
+        let mut vec = b"hello".to_vec();
+        vec.reserve(10);
+        let shared = Bytes::from(vec);
+        let clone = shared.clone();
+        assert_eq!(clone.ref_count(), Some(2));
+        drop(shared);
+        assert!(clone.is_unique());
+
+        let arc = Arc::new(*b"owned");
+        let owned = Bytes::from_slice_ref_counted(&arc, 1..);
+        assert!(owned.is_unique());
+        assert_eq!(owned.clone().ref_count(), Some(2));
+
+        let static_bytes = Bytes::from_static(b"static");
+        assert!(!static_bytes.is_unique());
+        assert_eq!(Bytes::new().ref_count(), None);
     }
-}
 
-impl Eq for Bytes {}
+    #[test]
+    fn try_into_mut() {
+        let mut vec = Vec::with_capacity(32);
+        vec.extend_from_slice(b"hello world");
+        let ptr = vec.as_ptr();
 
-// ** [u8] **
+        let mut bytes = Bytes::from(vec);
+        bytes.advance(6);
+        let clone = bytes.clone();
 
-impl PartialEq<[u8]> for Bytes {
-    fn eq(&self, other: &[u8]) -> bool {
-        self.as_slice() == other
+        let bytes = bytes.try_into_mut().err().unwrap();
+        drop(clone);
+
+        let (bytes_mut, allocs) = test_alloc::count_allocs(|| bytes.try_into_mut().unwrap());
+        assert_eq!(allocs, 0);
+        assert_eq!(bytes_mut.as_ref(), b"world");
+        assert_eq!(bytes_mut.as_ref().as_ptr(), ptr);
+        assert_eq!(bytes_mut.capacity(), 32);
+
+        // Promoted to a shared buffer by the clone
+        let bytes = Bytes::from(b"hello".to_vec());
+        drop(bytes.clone());
+        assert_eq!(bytes.try_into_mut().unwrap().as_ref(), b"hello");
+
+        assert!(Bytes::from_static(b"static").try_into_mut().is_err());
+        assert!(Bytes::from_slice_ref_counted(&Arc::new(*b"owned"), ..)
+            .try_into_mut()
+            .is_err());
+        assert!(Bytes::new().try_into_mut().is_ok());
     }
-}
 
-impl PartialOrd<[u8]> for Bytes {
-    fn partial_cmp(&self, other: &[u8]) -> Option<std::cmp::Ordering> {
-        self.as_slice().partial_cmp(other)
-    }
-}
+    #[test]
+    fn fork_shares_buffer() {
+        let mut bytes = Bytes::from(b"key=value".to_vec());
+        bytes.advance(4);
 
-impl<'a> PartialEq<&'a [u8]> for Bytes {
-    fn eq(&self, other: &&'a [u8]) -> bool {
-        self.as_slice() == *other
-    }
-}
+        let fork = bytes.fork();
+        assert_eq!(fork.as_ptr(), bytes.as_ptr());
 
-impl<'a> PartialOrd<&'a [u8]> for Bytes {
-    fn partial_cmp(&self, other: &&'a [u8]) -> Option<std::cmp::Ordering> {
-        self.as_slice().partial_cmp(*other)
+        let fork = (&mut bytes).take(3).fork();
+        assert_eq!(fork, "val"[..]);
+        assert_eq!(fork.as_ptr(), bytes.as_ptr());
     }
-}
 
-impl PartialEq<Bytes> for [u8] {
-    fn eq(&self, other: &Bytes) -> bool {
-        self == other.as_slice()
+    #[test]
+    fn copy_to_bytes_shares_buffer() {
+        let bytes = Bytes::from(b"hello world".to_vec());
+        let ptr = bytes.as_ptr();
+
+        let mut take = bytes.clone().take(8);
+        let (hello, allocs) = crate::test_alloc::count_allocs(|| take.copy_to_bytes(5));
+        assert_eq!(hello, "hello"[..]);
+        assert_eq!(hello.as_ptr(), ptr);
+        assert_eq!(allocs, 0);
+        assert_eq!(take.remaining(), 3);
+
+        let mut chain = Bytes::new()
+            .chain(bytes.clone())
+            .chain(Bytes::from_static(b"!"));
+        let (hello, allocs) = crate::test_alloc::count_allocs(|| chain.copy_to_bytes(6));
+        assert_eq!(hello.as_ptr(), ptr);
+        assert_eq!(allocs, 0);
+
+        // The bytes straddle the two buffers, they are copied
+        let world = chain.copy_to_bytes(6);
+        assert_eq!(world, "world!"[..]);
+        assert!(!chain.has_remaining());
     }
-}
 
-impl PartialOrd<Bytes> for [u8] {
-    fn partial_cmp(&self, other: &Bytes) -> Option<std::cmp::Ordering> {
-        self.partial_cmp(other.as_slice())
+    #[test]
+    fn try_unsplit() {
+        let mut bytes = Bytes::from(b"hello world".to_vec());
+        let ptr = bytes.as_ptr();
+
+        let world = bytes.split_off(6);
+        let mut hello = bytes.split_to(5);
+        assert_eq!(bytes, " ");
+
+        // Not adjacent
+        let world = hello.try_unsplit(world).unwrap_err();
+
+        hello.try_unsplit(bytes).unwrap();
+        hello.try_unsplit(world).unwrap();
+        assert_eq!(hello, "hello world");
+        assert_eq!((hello.as_ptr(), hello.ref_count()), (ptr, Some(1)));
+
+        // Adjacent in memory but not in the same buffer
+        let statics = Bytes::from_static(b"abcd");
+        let mut ab = statics.slice(..2);
+        assert_eq!(ab.try_unsplit(statics.slice(2..)).unwrap_err(), "cd");
+
+        let mut empty = Bytes::new();
+        empty.try_unsplit(hello.clone()).unwrap();
+        assert_eq!(empty.as_ptr(), ptr);
+        hello.try_unsplit(Bytes::new()).unwrap();
     }
-}
 
-impl<'a> PartialEq<Bytes> for &'a [u8] {
-    fn eq(&self, other: &Bytes) -> bool {
-        *self == other.as_slice()
+    #[test]
+    fn split_many() {
+        let mut bytes = Bytes::from(b"hello world".to_vec());
+        let ptr = bytes.as_ptr();
+
+        let pieces = bytes.split_many(&[2, 0, 3]);
+        assert_eq!(pieces, ["he", "", "llo"]);
+        assert_eq!(pieces[0].as_ptr(), ptr);
+        assert_eq!(pieces[1].as_ptr(), Bytes::new().as_ptr());
+        assert_eq!(pieces[2].as_ptr(), ptr.wrapping_add(2));
+        assert_eq!(bytes, " world");
+        assert_eq!(bytes.ref_count(), Some(3));
+
+        let pieces = bytes.split_many(&[6]);
+        assert_eq!(pieces, [" world"]);
+        assert_eq!(bytes.as_ptr(), Bytes::new().as_ptr());
+        assert!(bytes.split_many(&[]).is_empty());
     }
-}
 
-impl<'a> PartialOrd<Bytes> for &'a [u8] {
-    fn partial_cmp(&self, other: &Bytes) -> Option<std::cmp::Ordering> {
-        (*self).partial_cmp(other.as_slice())
+    #[test]
+    #[should_panic(expected = "split sizes out of bounds")]
+    fn split_many_out_of_bounds() {
+        let _ = Bytes::from_static(b"hello").split_many(&[usize::MAX, 2]);
     }
-}
 
-// ** str **
+    #[test]
+    fn split_overflow() {
+        let mut bytes = Bytes::from(b"hello world".to_vec());
 
-impl PartialEq<str> for Bytes {
-    fn eq(&self, other: &str) -> bool {
-        self.as_slice() == other.as_bytes()
+        assert!(bytes.split_to_checked(usize::MAX).is_err());
+        assert!(bytes.split_off_checked(crate::MAX_LEN + 1).is_err());
+        assert_eq!(bytes, "hello world");
     }
-}
 
-impl PartialOrd<str> for Bytes {
-    fn partial_cmp(&self, other: &str) -> Option<std::cmp::Ordering> {
-        self.as_slice().partial_cmp(other.as_bytes())
+    #[test]
+    #[should_panic(expected = "index out of bounds")]
+    fn split_to_overflow() {
+        let _ = Bytes::from(b"hello".to_vec()).split_to(usize::MAX);
     }
-}
 
-impl<'a> PartialEq<&'a str> for Bytes {
-    fn eq(&self, other: &&'a str) -> bool {
-        self.as_slice() == other.as_bytes()
+    #[test]
+    fn empty_is_canonical() {
+        let empty = Bytes::new().as_ptr();
+        let mut bytes = Bytes::from(b"hello world".to_vec());
+
+        assert_eq!(bytes.slice(3..3).as_ptr(), empty);
+        assert_eq!(bytes.split_to(0).as_ptr(), empty);
+        assert_eq!(bytes.split_off(bytes.len()).as_ptr(), empty);
+        assert_eq!(Bytes::from(Vec::with_capacity(10)).as_ptr(), empty);
+        assert_eq!(Bytes::from_static(&b"hello"[5..]).as_ptr(), empty);
+
+        let mut rest = bytes.split_off(6);
+        rest.advance(5);
+        assert_eq!(rest.as_ptr(), empty);
+
+        let hello = bytes.split_to(6);
+        assert_eq!(bytes.as_ptr(), empty);
+        assert_eq!(hello, "hello "[..]);
     }
-}
 
-impl<'a> PartialOrd<&'a str> for Bytes {
-    fn partial_cmp(&self, other: &&'a str) -> Option<std::cmp::Ordering> {
-        self.as_slice().partial_cmp(other.as_bytes())
-    }
-}
+    #[test]
+    fn clear() {
+        let vec: Vec<u8> = b"this is a boxed slice".to_vec();
+        let mut bytes = Bytes::from(vec.into_boxed_slice());
+        bytes.clear();
 
-impl PartialEq<Bytes> for str {
-    fn eq(&self, other: &Bytes) -> bool {
-        self.as_bytes() == other.as_slice()
+        assert!(bytes.is_empty());
     }
-}
 
-impl PartialOrd<Bytes> for str {
-    fn partial_cmp(&self, other: &Bytes) -> Option<std::cmp::Ordering> {
-        self.as_bytes().partial_cmp(other.as_slice())
-    }
-}
+    fn hash<T: core::hash::Hash + ?Sized>(value: &T) -> u64 {
+        use core::hash::Hasher;
 
-impl<'a> PartialEq<Bytes> for &'a str {
-    fn eq(&self, other: &Bytes) -> bool {
-        self.as_bytes() == other.as_slice()
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
     }
-}
 
-impl<'a> PartialOrd<Bytes> for &'a str {
-    fn partial_cmp(&self, other: &Bytes) -> Option<std::cmp::Ordering> {
-        self.as_bytes().partial_cmp(other.as_slice())
+    /// Return the same content `content` in every `Bytes` representation
+    fn representations(content: &'static [u8]) -> Vec<Bytes> {
+        let mut with_cap = Vec::with_capacity(content.len() + 8);
+        with_cap.extend_from_slice(content);
+
+        let mut larger = b"<<".to_vec();
+        larger.extend_from_slice(content);
+        larger.extend_from_slice(b">>");
+
+        let promoted = Bytes::from(content.to_vec().into_boxed_slice());
+        let _clone = promoted.clone();
+
+        vec![
+            Bytes::from_static(content),
+            Bytes::copy_from_slice(content),
+            Bytes::from(content.to_vec().into_boxed_slice()),
+            Bytes::from(with_cap),
+            Bytes::from(larger.clone()).slice(2..2 + content.len()),
+            Bytes::from(larger.into_boxed_slice()).slice(2..2 + content.len()),
+            promoted,
+        ]
     }
-}
 
-// === From ===
+    #[test]
+    fn hash_eq_across_representations() {
+        for content in [&b""[..], b"a", b"hello world"] {
+            let expected = hash(content);
 
-impl From<Vec<u8>> for Bytes {
-    fn from(value: Vec<u8>) -> Self {
-        let mut value = value;
-        let len = value.len();
-        let cap = value.capacity();
-        let ptr = value.as_mut_ptr();
+            for bytes in representations(content) {
+                assert_eq!(bytes, content);
+                assert_eq!(hash(&bytes), expected);
+            }
+        }
+    }
 
-        // Avoid allocating new memory if possible
-        if len == cap {
-            return Bytes::from(value.into_boxed_slice());
+    #[test]
+    fn ord() {
+        let mut map = std::collections::BTreeMap::new();
+        for key in [&b"b"[..], b"ab", b"", b"a", b"\xff"] {
+            map.insert(Bytes::copy_from_slice(key), key.len());
         }
 
-        let shared = Box::new(Shared {
-            buf: ptr,
-            cap,
-            ref_cnt: AtomicUsize::new(1),
-        });
+        let keys: Vec<_> = map.keys().cloned().collect();
+        assert_eq!(keys, [&b""[..], b"a", b"ab", b"b", b"\xff"]);
+        assert_eq!(map.get(&b"ab"[..]), Some(&2));
 
-        mem::forget(value);
-        let shared = Box::into_raw(shared);
+        let bytes = Bytes::from_static(b"ab");
+        assert!(bytes < Bytes::from_static(b"b"));
+        assert!(bytes > b"a"[..] && bytes < "abc");
+        assert!(b"aa"[..] < bytes && "b" > bytes);
+    }
 
-        Bytes {
-            ptr,
-            len,
-            data: AtomicPtr::new(shared.cast()),
-            vtable: &SHARED_VTABLE,
-        }
+    #[test]
+    fn cross_type_cmp() {
+        let bytes = Bytes::from_static(b"hello");
+        let (vec, string) = (b"hello".to_vec(), String::from("hello"));
+
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"hello");
+
+        assert_eq!(bytes, vec);
+        assert_eq!(vec, bytes);
+        assert_eq!(bytes, string);
+        assert_eq!(string, bytes);
+        assert!(bytes == buf);
+        assert!(buf == bytes);
+
+        let (vec, string) = (b"world".to_vec(), String::from("hell"));
+        buf.extend_from_slice(b"!");
+
+        assert!(bytes < vec);
+        assert!(vec > bytes);
+        assert!(bytes > string);
+        assert!(string < bytes);
+        assert!(bytes < buf);
+        assert!(buf > bytes);
+        assert!(bytes != buf);
     }
-}
 
-impl From<Box<[u8]>> for Bytes {
-    fn from(value: Box<[u8]>) -> Self {
-        // `Box` doesn't allocate memory for empty slices so we don't care about it
-        if value.is_empty() {
-            return Bytes::new();
-        }
+    #[test]
+    fn hash_map_lookup_by_slice() {
+        let mut map = std::collections::HashMap::new();
+        map.insert(Bytes::from(b"key".to_vec()), 1);
 
-        let len = value.len();
-        let ptr = Box::into_raw(value) as *mut u8;
+        assert_eq!(map.get(&b"key"[..]), Some(&1));
+    }
 
-        if ptr as usize & KIND_MASK == 0 {
-            // We set the kind of the ptr to `KIND_UNSHARED` so that it can be shared
-            // later on
-            let data = map_ptr(ptr, |p| p | KIND_UNSHARED);
-            Bytes {
-                ptr,
-                len,
-                data: AtomicPtr::new(data.cast()),
-                vtable: &PROMOTABLE_EVEN_VTABLE,
+    #[test]
+    fn slicing_preserves_content() {
+        let content: &'static [u8] = b"0123456789abcdef";
+
+        for bytes in representations(content) {
+            for start in 0..=content.len() {
+                for end in start..=content.len() {
+                    let slice = bytes.slice(start..end);
+                    assert_eq!(slice, content[start..end]);
+                    assert_eq!(hash(&slice), hash(&content[start..end]));
+                }
             }
-        } else {
-            Bytes {
-                ptr,
-                len,
-                data: AtomicPtr::new(ptr.cast()),
-                vtable: &PROMOTABLE_ODD_VTABLE,
+
+            for at in 0..=content.len() {
+                let mut head = bytes.clone();
+                let tail = head.split_off(at);
+                assert_eq!(head, content[..at]);
+                assert_eq!(tail, content[at..]);
+
+                let mut tail = bytes.clone();
+                let head = tail.split_to(at);
+                assert_eq!(head, content[..at]);
+                assert_eq!(tail, content[at..]);
+
+                let mut truncated = bytes.clone();
+                truncated.truncate(at);
+                assert_eq!(truncated, content[..at]);
             }
+
+            assert_eq!(bytes, content);
         }
     }
-}
 
-impl From<String> for Bytes {
-    fn from(value: String) -> Self {
-        Bytes::from(value.as_bytes().to_vec())
+    #[test]
+    fn truncate_unshared_then_clone() {
+        for _ in 0..8 {
+            let mut bytes = Bytes::from(b"hello world".to_vec().into_boxed_slice());
+            bytes.truncate(5);
+
+            let clone = bytes.clone();
+            drop(bytes);
+
+            assert_eq!(clone, b"hello"[..]);
+        }
     }
-}
 
-impl From<&'static str> for Bytes {
-    #[inline]
-    fn from(value: &'static str) -> Self {
-        Bytes::from_static(value.as_bytes())
+    #[test]
+    fn as_chunks() {
+        let bytes = Bytes::from(b"0123456789abcdef".to_vec());
+
+        let (blocks, rem) = bytes.as_chunks::<8>();
+        assert_eq!(blocks.as_slice(), [*b"01234567", *b"89abcdef"]);
+        assert!(rem.is_empty());
+
+        let short = bytes.slice(..3);
+        let (blocks, rem) = short.as_chunks::<4>();
+        assert_eq!(blocks.len(), 0);
+        assert_eq!(rem, b"012"[..]);
     }
-}
 
-impl From<&'static [u8]> for Bytes {
-    #[inline]
-    fn from(value: &'static [u8]) -> Self {
-        Bytes::from_static(value)
+    #[test]
+    fn slice_unbounded() {
+        let bytes = Bytes::from_static(b"hello world");
+
+        assert_eq!(b"world", &bytes.slice(6..)[..]);
+        assert_eq!(b"hello world", &bytes.slice(..)[..]);
     }
-}
 
-// === Vtables ===
-// === Static vtable ===
+    #[test]
+    fn slice_clamped() {
+        let bytes = Bytes::from(b"hello world".to_vec());
+
+        assert_eq!(bytes.slice_clamped(..), "hello world");
+        assert_eq!(bytes.slice_clamped(..=usize::MAX), "hello world");
+        assert_eq!(bytes.slice_clamped(..=4), "hello");
+        assert_eq!(bytes.slice_clamped(100..200), "");
+        assert_eq!(bytes.slice_clamped(usize::MAX..), "");
+    }
 
-const STATIC_VTABLE: Vtable = Vtable {
-    clone: static_clone,
-    drop: static_drop,
-};
+    #[test]
+    fn try_slice() {
+        let bytes = Bytes::from(b"hello world".to_vec());
+
+        assert_eq!(bytes.try_slice(..5).unwrap(), "hello");
+        assert_eq!(bytes.try_slice(11..).unwrap(), "");
+        let (start, end) = (5, 3);
+        assert_eq!(
+            bytes.try_slice(start..end),
+            Err(SliceError::StartAfterEnd { start: 5, end: 3 })
+        );
+        assert_eq!(
+            bytes.try_slice(..=11),
+            Err(SliceError::EndOutOfBounds { end: 12, len: 11 })
+        );
+        assert_eq!(bytes.try_slice(..=usize::MAX), Err(SliceError::EndOverflow));
+        assert_eq!(
+            bytes.try_slice((
+                core::ops::Bound::Excluded(usize::MAX),
+                core::ops::Bound::Unbounded
+            )),
+            Err(SliceError::StartOverflow)
+        );
+    }
 
-unsafe fn static_clone(_: &AtomicPtr<()>, ptr: *const u8, len: usize) -> Bytes {
-    // Because the underlying value is static we don't care about
-    // the reference counter
-    let slice = slice::from_raw_parts(ptr, len);
-    Bytes::from_static(slice)
-}
+    #[test]
+    #[should_panic(expected = "end overflow")]
+    fn slice_inclusive_end_overflow() {
+        let bytes = Bytes::from(b"hello world".to_vec());
 
-unsafe fn static_drop(_: &mut AtomicPtr<()>, _: *const u8, _: usize) {
-    // Nothing to do
-}
+        // `usize::MAX + 1` must not wrap to an empty range in release builds
+        let _ = bytes.slice(..=usize::MAX);
+    }
 
-// === Promotable vtable ===
-// This is used to create `Bytes` from data already on the heap
-// It avoids changing the data location if there is only one object
-// using this data but it changes the location whenever the `Bytes` object is cloned
+    #[test]
+    fn split_at() {
+        let bytes = Bytes::from(b"header|body".to_vec());
+
+        let (header, body) = bytes.split_at(7);
+        assert_eq!(header, "header|");
+        assert_eq!(body, "body");
+        assert_eq!(header.ref_count(), Some(2));
+
+        let (empty, all) = body.split_at(0);
+        assert!(empty.is_empty());
+        assert_eq!(all, "body");
+        assert_eq!(all.ref_count(), Some(2));
+    }
 
-// Mask used to determine if a values needs to be promoted to a shared `Bytes`
-const KIND_UNSHARED: usize = 0x1;
-const KIND_SHARED: usize = 0x0;
-const KIND_MASK: usize = 0x1;
+    #[test]
+    fn checked() {
+        let mut bytes = Bytes::from(b"key=value;".to_vec());
+
+        let err = bytes.split_off_checked(11).unwrap_err();
+        assert_eq!((err.index(), err.limit()), (11, 10));
+        assert_eq!(err.to_string(), "index out of bounds: at (11) > len (10)");
+
+        assert_eq!(bytes.split_to_checked(4).unwrap(), "key=");
+        assert_eq!(bytes.split_off_checked(5).unwrap(), ";");
+        assert!(bytes.truncate_checked(6).is_err());
+        assert!(bytes.advance_checked(6).is_err());
+        assert_eq!(bytes, "value");
+
+        assert!(bytes.advance_checked(5).is_ok());
+        assert!(bytes.split_to_checked(1).is_err());
+        assert!(bytes.truncate_checked(0).is_ok());
+    }
 
-const PROMOTABLE_ODD_VTABLE: Vtable = Vtable {
-    clone: promotable_odd_clone,
-    drop: promotable_odd_drop,
-};
+    #[test]
+    fn split_first_last() {
+        let bytes = Bytes::from(b"[1]".to_vec());
 
-unsafe fn promotable_odd_clone(data: &AtomicPtr<()>, ptr: *const u8, len: usize) -> Bytes {
-    let shared = data.load(Ordering::Relaxed);
-    let kind = shared as usize & KIND_MASK;
+        let (open, rest) = bytes.split_first().unwrap();
+        assert_eq!(open, b'[');
+        assert_eq!(rest.as_ptr(), bytes[1..].as_ptr());
 
-    if kind == KIND_SHARED {
-        shallow_clone_arc(shared.cast(), ptr, len)
-    } else {
-        debug_assert_eq!(kind, KIND_UNSHARED);
-        shallow_clone_vec(data, shared, shared.cast(), ptr, len)
+        let (close, inner) = rest.split_last().unwrap();
+        assert_eq!(close, b']');
+        assert_eq!(inner, "1");
+
+        let (_, empty) = inner.split_first().unwrap();
+        assert!(empty.is_empty());
+        assert_eq!(empty.split_first(), None);
+        assert_eq!(empty.split_last(), None);
     }
-}
 
-unsafe fn promotable_odd_drop(data: &mut AtomicPtr<()>, ptr: *const u8, len: usize) {
-    let data = data.get_mut();
-    let shared = *data;
-    let kind = shared as usize & KIND_MASK;
+    #[test]
+    fn strip_prefix_suffix() {
+        let bytes = Bytes::from(b"MAGIC:payload:CRC".to_vec());
 
-    if kind == KIND_SHARED {
-        release_shared(shared.cast())
-    } else {
-        debug_assert_eq!(kind, KIND_UNSHARED);
-        free_boxed_slice(shared.cast(), ptr, len)
+        let payload = bytes.strip_prefix(b"MAGIC:").unwrap();
+        assert_eq!(payload.as_ptr(), bytes[6..].as_ptr());
+        assert_eq!(payload.strip_suffix(b":CRC").unwrap(), "payload");
+
+        assert_eq!(bytes.strip_prefix(b"").unwrap(), bytes);
+        assert!(bytes.strip_suffix(&bytes).unwrap().is_empty());
+        assert_eq!(payload.strip_prefix(b"MAGIC:payload:CRC+"), None);
     }
-}
 
-const PROMOTABLE_EVEN_VTABLE: Vtable = Vtable {
-    clone: promotable_even_clone,
-    drop: promotable_even_drop,
-};
+    #[test]
+    fn repeat() {
+        let bytes = Bytes::from_static(b"abc");
 
-unsafe fn promotable_even_clone(data: &AtomicPtr<()>, ptr: *const u8, len: usize) -> Bytes {
-    let shared = data.load(Ordering::Relaxed);
-    let kind = shared as usize & KIND_MASK;
+        for n in 0..10 {
+            let (repeated, allocs) = test_alloc::count_allocs(|| bytes.repeat(n));
+            assert_eq!(repeated, b"abc".repeat(n).as_slice());
+            assert!(allocs <= 1);
+        }
 
-    if kind == KIND_SHARED {
-        shallow_clone_arc(shared.cast(), ptr, len)
-    } else {
-        debug_assert_eq!(kind, KIND_UNSHARED);
-        let buf = map_ptr(shared.cast(), |p| p & !KIND_MASK);
-        shallow_clone_vec(data, shared, buf, ptr, len)
+        assert!(Bytes::new().repeat(usize::MAX).is_empty());
     }
-}
 
-unsafe fn promotable_even_drop(data: &mut AtomicPtr<()>, ptr: *const u8, len: usize) {
-    let data = data.get_mut();
-    let shared = *data;
-    let kind = shared as usize & KIND_MASK;
+    #[test]
+    #[should_panic(expected = "repeat len overflow")]
+    fn repeat_overflow() {
+        let _ = Bytes::from_static(b"ab").repeat(usize::MAX / 2 + 1);
+    }
 
-    if kind == KIND_SHARED {
-        release_shared(shared.cast())
-    } else {
-        debug_assert_eq!(kind, KIND_UNSHARED);
-        let buf = map_ptr(shared.cast(), |p| p & !KIND_MASK);
-        free_boxed_slice(buf, ptr, len)
+    #[test]
+    fn trim_ascii() {
+        let bytes = Bytes::from(b"  Host:\texample.com \r\n".to_vec());
+
+        let trimmed = bytes.trim_ascii();
+        assert_eq!(trimmed, "Host:\texample.com");
+        assert_eq!(trimmed.as_ptr(), bytes[2..].as_ptr());
+        assert_eq!(bytes.trim_ascii_end().as_ptr(), bytes.as_ptr());
+
+        let blank = Bytes::from(b" \r\n\x0c".to_vec());
+        assert!(blank.trim_ascii().is_empty());
+        assert!(blank.trim_ascii_start().is_empty());
+        assert!(blank.trim_ascii_end().is_empty());
+
+        // Only ASCII whitespace is trimmed
+        assert_eq!(
+            Bytes::from_static(b"\x0bab\xa0").trim_ascii(),
+            b"\x0bab\xa0"[..]
+        );
     }
-}
 
-// === Shared vtable ===
+    #[test]
+    fn shared_parent() {
+        let parent = Bytes::from(b"key=value".to_vec());
+        let (key, value) = (parent.slice(..3), parent.slice(4..));
+
+        assert!(key.is_subslice_of(&parent));
+        assert!(value.is_subslice_of(&parent.clone()));
+        assert!(!value.is_subslice_of(&key));
+        assert!(Bytes::new().is_subslice_of(&key));
+
+        assert!(parent.starts_with_bytes(&key));
+        assert!(parent.ends_with_bytes(&value));
+        assert!(!parent.starts_with_bytes(&value));
+        assert!(!key.ends_with_bytes(&parent));
+
+        // Same content in another buffer
+        let copy = Bytes::copy_from_slice(b"value");
+        assert!(!copy.is_subslice_of(&parent));
+        assert!(parent.ends_with_bytes(&copy));
+        assert_eq!(copy, value);
+    }
 
-const SHARED_VTABLE: Vtable = Vtable {
-    clone: shared_clone,
-    drop: shared_drop,
-};
+    #[test]
+    fn split_evenly() {
+        let bytes = Bytes::from(b"abcdefghij".to_vec());
 
-unsafe fn shared_clone(data: &AtomicPtr<()>, ptr: *const u8, len: usize) -> Bytes {
-    let shared = data.load(Ordering::Relaxed);
-    shallow_clone_arc(shared.cast(), ptr, len)
-}
+        let parts = bytes.split_evenly(4);
+        assert_eq!(parts, ["ab", "cd", "ef", "ghij"]);
+        assert_eq!(parts[2].as_ptr(), bytes[4..].as_ptr());
 
-unsafe fn shared_drop(data: &mut AtomicPtr<()>, _: *const u8, _: usize) {
-    let shared: *mut Shared = data.get_mut().cast();
-    release_shared(shared)
-}
+        assert_eq!(bytes.split_evenly(1), ["abcdefghij"]);
+        assert_eq!(Bytes::from_static(b"ab").split_evenly(3), ["", "", "ab"]);
 
-unsafe fn shallow_clone_arc(shared: *mut Shared, ptr: *const u8, len: usize) -> Bytes {
-    (*shared).ref_cnt.fetch_add(1, Ordering::Release);
+        // A delimiter ending right at a split point keeps the point
+        let lines = Bytes::from_static(b"ab\ncd\nef\ngh\n");
+        assert_eq!(
+            lines.split_evenly_on(4, b'\n'),
+            ["ab\n", "cd\n", "ef\n", "gh\n"]
+        );
+        assert_eq!(
+            lines.split_evenly_on(2, b"\r\n"),
+            [lines.clone(), Bytes::new()]
+        );
 
-    Bytes {
-        ptr,
-        len,
-        data: AtomicPtr::new(shared.cast()),
-        vtable: &SHARED_VTABLE,
+        // Long records push the following split points
+        let lines = Bytes::from_static(b"a\nbcdefgh\ni\nj\n");
+        assert_eq!(
+            lines.split_evenly_on(4, b'\n'),
+            ["a\nbcdefgh\n", "i\n", "j\n", ""]
+        );
+        assert_eq!(
+            lines.split_evenly_on(3, b"h\n"),
+            ["a\nbcdefgh\n", "i\nj\n", ""]
+        );
     }
-}
 
-unsafe fn shallow_clone_vec(
-    atom: &AtomicPtr<()>,
-    ptr: *const (),
-    buf: *mut u8,
-    offset: *const u8,
-    len: usize,
-) -> Bytes {
-    let shared = Box::new(Shared {
-        buf,
-        cap: (offset as usize - buf as usize) + len,
-        ref_cnt: AtomicUsize::new(2),
-    });
+    #[test]
+    fn slice_ref() {
+        let bytes = Bytes::from(b"key=value".to_vec());
+
+        let value = bytes.slice_ref(&bytes[4..]);
+        assert_eq!(value, "value");
+        assert_eq!(value.as_ptr(), bytes[4..].as_ptr());
+        assert_eq!(bytes.slice_ref(&bytes[..]), bytes);
+        assert!(bytes.slice_ref(&[]).is_empty());
+    }
 
-    let shared = Box::into_raw(shared);
+    #[test]
+    #[should_panic(expected = "out of bounds")]
+    fn slice_ref_out_of_bounds() {
+        let bytes = Bytes::from(b"key=value".to_vec());
+        let key = bytes.slice(..3);
 
-    // Verif that the pointer is aligned
-    // This is ensured by the `Box` API so this assert should not fail
-    debug_assert_eq!(
-        shared as usize & KIND_MASK,
-        KIND_SHARED,
-        "internal Box<Shared> should have an aligned pointer"
-    );
+        let _ = key.slice_ref(&bytes[2..5]);
+    }
 
-    match atom.compare_exchange(ptr as _, shared.cast(), Ordering::AcqRel, Ordering::Acquire) {
-        Ok(actual) => {
-            debug_assert_eq!(actual as usize, ptr as usize);
+    #[test]
+    fn tail() {
+        let mut bytes = Bytes::from(b"header|payload|crc!".to_vec());
 
-            // Exchange was successful so we can return the new `Bytes` value
-            Bytes {
-                ptr: offset,
-                len,
-                data: AtomicPtr::new(shared.cast()),
-                vtable: &SHARED_VTABLE,
+        let crc = bytes.split_from_end(4);
+        assert_eq!(b"crc!", &crc[..]);
+        assert_eq!(b"header|payload|", &bytes[..]);
+        assert_eq!(b"|", &bytes.last_n(1)[..]);
+        assert!(bytes.last_n(0).is_empty());
+
+        let padded = Bytes::from_static(b"\0\0\0");
+        assert!(padded.trim_end_matches(0).is_empty());
+    }
+
+    #[test]
+    fn panic_keeps_ref_count() {
+        for content in [&b""[..], b"hello world"] {
+            for bytes in representations(content) {
+                let res = std::panic::catch_unwind(|| {
+                    let _clones = [bytes.clone(), bytes.clone(), bytes.slice(..)];
+                    bytes.slice(..content.len() + 1)
+                });
+
+                assert!(res.is_err());
+                assert_eq!(bytes, content);
             }
         }
-        Err(actual) => {
-            // The exchange was made by an other thread so we acquire the value
-            // created by this other thread and we clone it into a new `Bytes` object
 
-            // Forget the shared object we just allocated to create the new `Bytes` object
-            let shared: Box<Shared> = Box::from_raw(actual as _);
-            mem::forget(*shared);
+        // The buffer is promoted by the clones in the closure and unique again after the panic
+        let bytes = Bytes::from(b"hello world".to_vec().into_boxed_slice());
+        let ptr = bytes.as_ptr();
 
-            // Create an Arc copy of the `Bytes` object using the acquired new shared value
-            shallow_clone_arc(actual.cast(), offset, len)
+        let res = std::panic::catch_unwind(|| {
+            let _clone = bytes.clone();
+            panic!("plugin failure");
+        });
+
+        assert!(res.is_err());
+        let vec = bytes.into_vec();
+        assert_eq!(vec.as_ptr(), ptr);
+    }
+
+    /// An owner counting its drops and panicking in the callbacks called by the vtables.
+    struct PanickingOwner {
+        content: &'static [u8],
+        drops: Arc<AtomicUsize>,
+        panic_on_as_ref: bool,
+        panic_on_drop: bool,
+    }
+
+    impl AsRef<[u8]> for PanickingOwner {
+        fn as_ref(&self) -> &[u8] {
+            assert!(!self.panic_on_as_ref, "as_ref failure");
+            self.content
         }
     }
-}
 
-unsafe fn release_shared(shared: *mut Shared) {
-    // If this is diffetent from 1 than we don't need to drop the value
-    if (*shared).ref_cnt.fetch_sub(1, Ordering::Release) != 1 {
-        return;
+    impl Drop for PanickingOwner {
+        fn drop(&mut self) {
+            self.drops.fetch_add(1, Ordering::Relaxed);
+            assert!(!self.panic_on_drop, "drop failure");
+        }
     }
 
-    // Else we need to drop the underlying value
-    drop(Box::from_raw(shared))
-}
+    #[test]
+    fn panic_in_owner_drop() {
+        let drops = Arc::new(AtomicUsize::new(0));
+        let bytes = Bytes::from_owner(PanickingOwner {
+            content: b"hello world",
+            drops: drops.clone(),
+            panic_on_as_ref: false,
+            panic_on_drop: true,
+        });
 
-unsafe fn free_boxed_slice(buf: *mut u8, offset: *const u8, len: usize) {
-    let cap = (offset as usize - buf as usize) + len;
-    // TODO:
-    // Safety: ?value
-    dealloc(buf, Layout::from_size_align_unchecked(cap, 1))
-}
+        let clones = [bytes.clone(), bytes.slice(6..), bytes.clone()];
+        assert_eq!(bytes.ref_count(), Some(4));
 
-struct Shared {
-    buf: *mut u8,
-    cap: usize,
-    ref_cnt: AtomicUsize,
-}
+        // Only the last drop calls the owner
+        drop(clones);
+        assert_eq!(bytes.ref_count(), Some(1));
+        assert_eq!(drops.load(Ordering::Relaxed), 0);
 
-// Verify that the |Shared` struct size is divisible by 2 because we want to use the LSB has a flag.
-const _: [(); 0 - mem::size_of::<Shared>() % 2] = [];
+        let world = bytes.slice(6..);
+        let res = std::panic::catch_unwind(move || drop(bytes));
+        assert!(res.is_ok());
+        assert_eq!(world.ref_count(), Some(1));
 
-impl Drop for Shared {
-    fn drop(&mut self) {
-        unsafe { dealloc(self.buf, Layout::from_size_align(self.cap, 1).unwrap()) }
+        let res = std::panic::catch_unwind(move || drop(world));
+        assert!(res.is_err());
+        assert_eq!(drops.load(Ordering::Relaxed), 1);
     }
-}
 
-// === Handfull functions to manipulate pointers ===
+    #[test]
+    fn panic_in_owner_as_ref() {
+        let drops = Arc::new(AtomicUsize::new(0));
+
+        let res = std::panic::catch_unwind(|| {
+            Bytes::from_owner(PanickingOwner {
+                content: b"hello world",
+                drops: drops.clone(),
+                panic_on_as_ref: true,
+                panic_on_drop: false,
+            })
+        });
 
-fn map_ptr<F>(ptr: *mut u8, f: F) -> *mut u8
-where
-    F: FnOnce(usize) -> usize,
-{
-    let old_ptr = ptr as usize;
-    let new_ptr = f(old_ptr);
-    new_ptr as *mut u8
-}
+        // The boxed owner is dropped while unwinding instead of being leaked
+        assert!(res.is_err());
+        assert_eq!(drops.load(Ordering::Relaxed), 1);
+    }
 
-#[cfg(test)]
-mod test {
-    use super::*;
+    #[test]
+    fn from_iter() {
+        let (bytes, allocs) =
+            test_alloc::count_allocs(|| (0..10u8).map(|b| b + b'0').collect::<Bytes>());
+        assert_eq!(bytes, "0123456789");
+        assert_eq!(allocs, 1);
 
-    macro_rules! assert_iter {
-        ($bytes:literal) => {
-            let bytes = Bytes::from_static($bytes);
-            assert_iter!(bytes => $bytes);
-        };
-        ($bytes:ident => $lit:literal) => {
-            let mut iter = $lit.into_iter().enumerate();
+        let bytes: Bytes = b"a-b-c".iter().copied().filter(|b| *b != b'-').collect();
+        assert_eq!(bytes, "abc");
 
-            while let Some((index, byte)) = iter.next() {
-                assert_eq!($bytes.get(index), *byte);
-            }
-        }
+        let bytes: Bytes = core::iter::empty().collect();
+        assert_eq!(bytes.as_ptr(), Bytes::new().as_ptr());
     }
 
     #[test]
-    fn static_bytes() {
-        assert_iter!(b"this is a static bytes");
-        assert_iter!(b"another static bytes");
+    #[cfg(feature = "drop-hook")]
+    fn on_final_drop() {
+        use std::sync::Mutex;
+
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let hook = |id| {
+            let calls = calls.clone();
+            move || calls.lock().unwrap().push(id)
+        };
+
+        // The buffer is promoted to hold the hooks
+        let bytes = Bytes::from(b"hello world".to_vec());
+        bytes.on_final_drop(hook(1)).ok().unwrap();
+
+        let world = bytes.slice(6..);
+        world.on_final_drop(hook(2)).ok().unwrap();
+        drop(bytes);
+
+        let world = std::thread::spawn(move || world.clone()).join().unwrap();
+        assert!(calls.lock().unwrap().is_empty());
+
+        drop(world);
+        assert_eq!(*calls.lock().unwrap(), [2, 1]);
+
+        // Reclaiming the buffer releases the last `Bytes`
+        let bytes = Bytes::from(String::from("reclaimed"));
+        bytes.slice(1..).on_final_drop(hook(3)).ok().unwrap();
+        assert_eq!(bytes.into_vec(), b"reclaimed");
+        assert_eq!(*calls.lock().unwrap(), [2, 1, 3]);
+
+        assert!(Bytes::from_static(b"static").on_final_drop(|| ()).is_err());
+        assert!(Bytes::from_owner(b"owned".to_vec())
+            .on_final_drop(|| ())
+            .is_err());
     }
 
     #[test]
-    fn static_clone() {
-        let bytes = Bytes::from_static(b"a static byte");
+    fn into_vec_reclaims_unique_buffer() {
+        let bytes = Bytes::from(b"hello world".to_vec());
+        let ptr = bytes.as_ptr();
+
+        let (vec, allocs) = test_alloc::count_allocs(|| Vec::from(bytes));
+        assert_eq!((vec.as_ptr(), allocs), (ptr, 0));
+
+        // A unique partial view is moved to the start of its allocation
+        let full = Bytes::from(b"hello world".to_vec());
+        let ptr = full.as_ptr();
+        let bytes = full.slice(6..);
+        drop(full);
+
+        let (vec, allocs) = test_alloc::count_allocs(|| Vec::from(bytes));
+        assert_eq!(
+            (vec.as_slice(), vec.as_ptr(), allocs),
+            (&b"world"[..], ptr, 0)
+        );
+
+        // Shared and static buffers are copied
+        let bytes = Bytes::from(b"hello world".to_vec());
         let clone = bytes.clone();
+        let vec = Vec::from(bytes);
+        assert_ne!(vec.as_ptr(), clone.as_ptr());
+        assert_eq!(Vec::from(clone), b"hello world");
+        assert_eq!(Vec::from(Bytes::from_static(b"static")), b"static");
+    }
 
-        assert_eq!(bytes.ptr, clone.ptr);
-        assert_iter!(bytes => b"a static byte");
-        assert_iter!(clone => b"a static byte");
+    #[test]
+    fn memory_usage() {
+        assert_eq!(Bytes::from_static(b"static").memory_usage().total(), 0);
+
+        let mut vec = Vec::with_capacity(32);
+        vec.extend_from_slice(b"hello world");
+
+        let bytes = Bytes::from(vec);
+        assert_eq!(bytes.memory_usage().allocation, 32);
+        assert!(!bytes.memory_usage().shared);
+
+        // Promotable buffers report the whole boxed slice, before and after the promotion
+        for offset in [0, 1] {
+            let mut bytes = Bytes::from(b"hello world".to_vec().into_boxed_slice());
+            bytes.advance(offset);
+
+            let usage = bytes.memory_usage();
+            assert_eq!((usage.allocation, usage.overhead), (11, 0));
+
+            let clone = bytes.clone();
+            assert_eq!(clone.memory_usage().allocation, 11);
+            assert!(clone.memory_usage().shared);
+
+            drop(clone);
+            assert!(!bytes.memory_usage().shared);
+            assert_eq!(bytes.memory_usage().overhead, mem::size_of::<Shared>());
+        }
+
+        // The odd vtable doesn't tag the buffer pointer
+        let vec = test_alloc::odd_allocs(|| b"hello world".to_vec());
+        assert_eq!(vec.as_ptr() as usize & 1, 1);
+
+        let mut bytes = Bytes::from(vec);
+        assert!(ptr::eq(bytes.vtable, &PROMOTABLE_ODD_VTABLE));
+        assert_eq!(
+            bytes.memory_usage(),
+            MemoryUsage {
+                allocation: 11,
+                overhead: 0,
+                shared: false
+            }
+        );
+
+        bytes.advance(6);
+        assert_eq!(bytes.memory_usage().allocation, 11);
     }
 
     #[test]
-    fn shared_vec_clone() {
-        let bytes = Bytes::from(b"toto".to_vec());
+    fn from_slice_ref_counted() {
+        let arc: Arc<Vec<u8>> = Arc::new(b"cached blob".to_vec());
+
+        let bytes = Bytes::from_slice_ref_counted(&arc, 7..);
         let clone = bytes.clone();
+        assert_eq!(Arc::strong_count(&arc), 2);
 
-        assert_eq!(bytes.ptr, clone.ptr);
-        assert_iter!(bytes => b"toto");
-        assert_iter!(clone => b"toto");
+        drop(bytes);
+        assert_eq!(clone, "blob");
+        assert_eq!(clone.memory_usage(), MemoryUsage::default());
+        assert_eq!(String::try_from(clone).unwrap(), "blob");
+        assert_eq!(Arc::strong_count(&arc), 1);
+
+        let empty = Bytes::from_slice_ref_counted(&arc, 3..3);
+        assert!(empty.is_empty());
+        assert_eq!(Arc::strong_count(&arc), 1);
     }
 
     #[test]
-    fn shared_box_clone() {
-        let boxed = b"toto".to_vec().into_boxed_slice();
-        let bytes = Bytes::from(boxed);
-        let clone = bytes.clone();
+    fn from_cow() {
+        static CONFIG: &[u8] = b"key=value";
 
-        assert_eq!(bytes.ptr, clone.ptr);
-        assert_iter!(bytes => b"toto");
-        assert_iter!(clone => b"toto");
+        let (bytes, allocs) = test_alloc::count_allocs(|| Bytes::from(Cow::Borrowed(CONFIG)));
+        assert_eq!((bytes.as_ptr(), allocs), (CONFIG.as_ptr(), 0));
+
+        let vec = b"owned".to_vec();
+        let ptr = vec.as_ptr();
+        let (bytes, allocs) = test_alloc::count_allocs(|| Bytes::from(Cow::<[u8]>::Owned(vec)));
+        assert_eq!((bytes.as_ptr(), allocs), (ptr, 0));
+
+        let bytes = Bytes::from(Cow::Borrowed("static str"));
+        assert_eq!(bytes, "static str");
+
+        let cow: Cow<'static, str> = Cow::Owned(String::from("owned str"));
+        assert_eq!(Bytes::from(cow), "owned str");
     }
 
     #[test]
-    fn copy_from_slice() {
-        let bytes = Bytes::copy_from_slice(b"toto");
+    fn make_mut() {
+        // A promoted buffer is mutated in place and forgets its cached hash
+        let mut bytes = Bytes::from(b"hello world".to_vec());
+        drop(bytes.clone());
+        let hash = bytes.content_hash64();
+        let ptr = bytes.as_ptr();
+
+        let (_, allocs) = test_alloc::count_allocs(|| bytes.make_mut()[0] = b'H');
+        assert_eq!((bytes.as_ptr(), allocs), (ptr, 0));
+        assert_ne!(bytes.content_hash64(), hash);
+        assert_eq!(bytes.content_hash64(), fnv1a64(b"Hello world"));
+
+        // A shared buffer is copied, the other views are left untouched
+        let mut world = bytes.slice(6..);
+        world.make_mut().make_ascii_uppercase();
+        assert_eq!(
+            (bytes.as_ref(), world.as_ref()),
+            (&b"Hello world"[..], &b"WORLD"[..])
+        );
+        assert_ne!(world.as_ptr(), ptr.wrapping_add(6));
+        assert!(world.is_unique());
 
-        assert_iter!(bytes => b"toto");
+        let mut bytes = Bytes::from_static(b"static");
+        bytes.make_mut()[0] = b'S';
+        assert_eq!(bytes, "Static");
+        assert!(!bytes.is_static());
+
+        let mut bytes = Bytes::from_owner(b"owned".to_vec());
+        let ptr = bytes.as_ptr();
+        bytes.make_mut()[0] = b'O';
+        assert_eq!(bytes, "Owned");
+        assert_ne!(bytes.as_ptr(), ptr);
+
+        assert!(Bytes::new().make_mut().is_empty());
     }
 
     #[test]
-    fn index() {
-        let bytes = Bytes::from_static(b"this is a very long long bytes slice");
+    fn from_array() {
+        static PING: &[u8; 4] = b"PING";
 
-        assert_eq!(b"this", &bytes[..4]);
-        assert_eq!(b"very long long bytes", &bytes[10..30]);
-        assert_eq!(b"this is a very long long bytes slice", &bytes[..]);
+        let (bytes, allocs) = test_alloc::count_allocs(|| Bytes::from(PING));
+        assert_eq!((bytes.as_ptr(), allocs), (PING.as_ptr(), 0));
+        assert!(bytes.is_static());
+
+        let (bytes, allocs) = test_alloc::count_allocs(|| Bytes::from(*b"PONG"));
+        assert_eq!((bytes.as_ref(), allocs), (&b"PONG"[..], 1));
+        assert!(bytes.is_unique());
+
+        assert_eq!(Bytes::from(0x0102u16.to_be_bytes()), b"\x01\x02"[..]);
+        assert!(Bytes::from([]).is_empty());
     }
 
     #[test]
-    fn slice() {
-        let bytes = Bytes::from_static(b"this is a very long long bytes slice");
+    fn from_arc() {
+        let arc: Arc<[u8]> = Arc::from(&b"shared slice"[..]);
+        let bytes = Bytes::from(arc.clone());
+        assert_eq!(bytes.as_ptr(), arc.as_ptr());
+        assert_eq!(Arc::strong_count(&arc), 2);
+
+        let clone = bytes.slice(7..);
+        drop(bytes);
+        assert_eq!(clone, "slice");
+        assert_eq!(Arc::strong_count(&arc), 2);
+        drop(clone);
+        assert_eq!(Arc::strong_count(&arc), 1);
+
+        let arc = Arc::new(b"shared vec".to_vec());
+        let (bytes, allocs) = test_alloc::count_allocs(|| Bytes::from(arc.clone()));
+        assert_eq!(bytes, "shared vec");
+        assert_eq!((bytes.as_ptr(), allocs), (arc.as_ptr(), 1));
+
+        drop(bytes);
+        assert_eq!(Arc::strong_count(&arc), 1);
+
+        assert!(Bytes::from(Arc::new(Vec::new())).is_empty());
+    }
 
-        let slice = bytes.slice(10..30);
+    #[test]
+    fn from_owner() {
+        struct Guard {
+            _alive: Arc<()>,
+            bytes: [u8; 8],
+        }
 
-        assert_eq!(b"very long long bytes", &slice[..]);
+        impl AsRef<[u8]> for Guard {
+            fn as_ref(&self) -> &[u8] {
+                &self.bytes
+            }
+        }
+
+        let alive = Arc::new(());
+
+        // The bytes are stored inline in the owner, they must be viewed once it's boxed
+        let bytes = Bytes::from_owner(Guard {
+            _alive: alive.clone(),
+            bytes: *b"guarded!",
+        });
+        let slice = bytes.slice(2..7);
+        assert_eq!(Arc::strong_count(&alive), 2);
+        assert_eq!(bytes.ref_count(), Some(2));
+
+        drop(bytes);
+        assert_eq!(slice, "arded");
+        assert_eq!(Arc::strong_count(&alive), 2);
+
+        drop(slice);
+        assert_eq!(Arc::strong_count(&alive), 1);
+
+        let empty = Bytes::from_owner(Vec::new());
+        assert!(empty.is_empty());
+        assert_eq!(empty.ref_count(), None);
     }
 
     #[test]
-    fn clear() {
-        let vec: Vec<u8> = b"this is a boxed slice".to_vec();
-        let mut bytes = Bytes::from(vec.into_boxed_slice());
-        bytes.clear();
+    fn content_hash64() {
+        // Reference values of FNV-1a
+        assert_eq!(fnv1a64(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a64(b"a"), 0xaf63_dc4c_8601_ec8c);
 
-        assert!(bytes.is_empty());
+        let expected = fnv1a64(b"hello world");
+
+        for bytes in representations(b"hello world") {
+            let clone = bytes.clone();
+
+            assert_eq!(bytes.content_hash64(), expected);
+            assert_eq!(clone.content_hash64(), expected);
+            assert_eq!(bytes.slice(6..).content_hash64(), fnv1a64(b"world"));
+        }
+
+        let bytes = Bytes::from(b"hello world".to_vec());
+        let world = bytes.slice(6..);
+
+        assert_eq!(world.content_hash64(), fnv1a64(b"world"));
+        assert_eq!(bytes.content_hash64(), expected);
+        assert_eq!(world.clone().content_hash64(), fnv1a64(b"world"));
+    }
+
+    #[test]
+    fn into_string() {
+        for content in [&b""[..], b"a", b"hello world"] {
+            for bytes in representations(content) {
+                assert_eq!(String::try_from(bytes).unwrap().as_bytes(), content);
+            }
+        }
+
+        // A unique buffer is reused even if the view doesn't start at the allocation
+        let mut bytes = Bytes::from(b"GET /index.html".to_vec());
+        let ptr = bytes.as_ptr();
+        drop(bytes.split_to(4));
+        let string = String::try_from(bytes).unwrap();
+        assert_eq!(string, "/index.html");
+        assert_eq!(string.as_ptr(), ptr);
+
+        // A shared buffer is copied and left untouched
+        let bytes = Bytes::from(b"GET /index.html".to_vec());
+        let path = bytes.slice(4..);
+        assert_eq!(String::try_from(path).unwrap(), "/index.html");
+        assert_eq!(bytes, "GET /index.html");
+
+        let err = String::try_from(Bytes::from(b"ok\xff".to_vec())).unwrap_err();
+        assert_eq!(err.utf8_error().valid_up_to(), 2);
+        assert_eq!(err.into_bytes(), b"ok\xff"[..]);
     }
 }
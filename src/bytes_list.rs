@@ -0,0 +1,464 @@
+use alloc::vec::Vec;
+use core::ops::{Range, RangeBounds};
+
+use crate::{Buf, Bytes};
+
+/// A list of `Bytes` segments read as a single logical buffer.
+///
+/// The start offset of every segment is stored in a prefix-sum table so that locating the
+/// segment holding a given position is a binary search. This allows random access over a body
+/// assembled from several buffers without flattening it.
+///
+/// # Example
+///
+/// ```
+/// use bytes::{Buf, Bytes, BytesList};
+///
+/// let mut list = BytesList::new();
+/// list.push(Bytes::from_static(b"hello "));
+/// list.push(Bytes::from_static(b"world"));
+///
+/// let mut cursor = list.cursor();
+/// cursor.seek(4);
+///
+/// assert_eq!(cursor.chuncks(), b"o ");
+/// assert_eq!(list.slice(4..8).segments(), [&b"o "[..], &b"wo"[..]]);
+/// ```
+#[derive(Clone, Default)]
+pub struct BytesList {
+    segments: Vec<Bytes>,
+    /// `offsets[i]` is the position of the first byte of `segments[i]`.
+    offsets: Vec<usize>,
+    len: usize,
+}
+
+impl BytesList {
+    /// Create a new empty `BytesList`.
+    #[inline]
+    pub const fn new() -> BytesList {
+        BytesList {
+            segments: Vec::new(),
+            offsets: Vec::new(),
+            len: 0,
+        }
+    }
+
+    /// Append a segment at the end of the list. Empty segments are ignored.
+    ///
+    /// # Panics
+    ///
+    /// This panics if the len of the list would exceed `isize::MAX`.
+    pub fn push(&mut self, segment: Bytes) {
+        if segment.is_empty() {
+            return;
+        }
+
+        let len = self
+            .len
+            .checked_add(segment.len())
+            .filter(|len| *len <= crate::MAX_LEN)
+            .expect("list len overflow");
+
+        self.offsets.push(self.len);
+        self.len = len;
+        self.segments.push(segment);
+    }
+
+    /// Return the total number of bytes of the list.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Return `true` if the list holds no bytes.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Return the segments of the list, none of them is empty.
+    #[inline]
+    pub fn segments(&self) -> &[Bytes] {
+        &self.segments
+    }
+
+    /// Create a [`Buf`] reading the list from its first byte.
+    #[inline]
+    pub fn cursor(&self) -> BytesListCursor<'_> {
+        BytesListCursor {
+            list: self,
+            pos: 0,
+            segment: 0,
+        }
+    }
+
+    /// Return a `BytesList` holding the bytes of `range`. The segments are shared with `self`,
+    /// no bytes are copied.
+    ///
+    /// # Panics
+    ///
+    /// This panics if the range is out of bounds or if its start is greater than its end.
+    pub fn slice(&self, range: impl RangeBounds<usize>) -> BytesList {
+        use core::ops::Bound::*;
+
+        let start = match range.start_bound() {
+            Included(&start) => start,
+            Excluded(&start) => start
+                .checked_add(1)
+                .expect("invalid bounds: start overflow"),
+            Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Included(&end) => end.checked_add(1).expect("invalid bounds: end overflow"),
+            Excluded(&end) => end,
+            Unbounded => self.len,
+        };
+
+        assert!(
+            start <= end,
+            "invalid bounds: start ({}) > end ({})",
+            start,
+            end
+        );
+        assert!(
+            end <= self.len,
+            "invalid bounds: end out of bounds ({})",
+            end
+        );
+
+        let mut list = BytesList::new();
+
+        if start == end {
+            return list;
+        }
+
+        let first = self.segment_of(start);
+        let last = self.segment_of(end - 1);
+
+        for i in first..=last {
+            let offset = self.offsets[i];
+            let segment = &self.segments[i];
+            let from = start.saturating_sub(offset);
+            let to = core::cmp::min(end - offset, segment.len());
+
+            list.push(segment.slice(from..to));
+        }
+
+        list
+    }
+
+    /// Convert `self` into a single `Bytes`. The segment is returned without copying if there is
+    /// only one, otherwise the bytes of every segment are copied into a new buffer.
+    pub fn into_bytes(mut self) -> Bytes {
+        match self.segments.len() {
+            0 => Bytes::new(),
+            1 => self.segments.pop().unwrap(),
+            _ => {
+                let mut vec = Vec::with_capacity(self.len);
+                for segment in &self.segments {
+                    vec.extend_from_slice(segment);
+                }
+                Bytes::from(vec)
+            }
+        }
+    }
+
+    /// Return the index of the segment holding the byte at `pos`.
+    ///
+    /// `pos` must be lower than `self.len`.
+    #[inline]
+    fn segment_of(&self, pos: usize) -> usize {
+        debug_assert!(pos < self.len);
+        self.offsets.partition_point(|&offset| offset <= pos) - 1
+    }
+}
+
+impl FromIterator<Bytes> for BytesList {
+    fn from_iter<I: IntoIterator<Item = Bytes>>(iter: I) -> BytesList {
+        let mut list = BytesList::new();
+
+        for segment in iter {
+            list.push(segment);
+        }
+
+        list
+    }
+}
+
+impl Extend<Bytes> for BytesList {
+    fn extend<I: IntoIterator<Item = Bytes>>(&mut self, iter: I) {
+        for segment in iter {
+            self.push(segment);
+        }
+    }
+}
+
+/// How [`Bytes::select`] gathers the selected ranges.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Gather {
+    /// Every range is a segment sharing the buffer, adjacent ranges are merged into a single
+    /// segment. No bytes are copied.
+    Segmented,
+    /// The ranges are copied into a single new buffer allocated with their total len, the list
+    /// has at most one segment. This releases the source buffer once it's no longer used
+    /// elsewhere and gives contiguous bytes to the reader.
+    Coalesced,
+}
+
+/// Gather the `ranges` of `bytes` into a list following `gather`.
+pub(crate) fn select(bytes: &Bytes, ranges: &[Range<usize>], gather: Gather) -> BytesList {
+    for range in ranges {
+        assert!(
+            range.start <= range.end,
+            "invalid bounds: start ({}) > end ({})",
+            range.start,
+            range.end
+        );
+        assert!(
+            range.end <= bytes.len(),
+            "invalid bounds: end out of bounds ({})",
+            range.end
+        );
+    }
+
+    match gather {
+        Gather::Segmented => {
+            let mut list = BytesList::new();
+            let mut pending: Option<Range<usize>> = None;
+
+            for range in ranges {
+                pending = match pending {
+                    Some(prev) if prev.end == range.start => Some(prev.start..range.end),
+                    Some(prev) => {
+                        list.push(bytes.slice(prev));
+                        Some(range.clone())
+                    }
+                    None => Some(range.clone()),
+                };
+            }
+
+            if let Some(range) = pending {
+                list.push(bytes.slice(range));
+            }
+            list
+        }
+        Gather::Coalesced => {
+            let len = ranges
+                .iter()
+                .try_fold(0usize, |len, range| len.checked_add(range.len()))
+                .filter(|len| *len <= crate::MAX_LEN)
+                .expect("selection len overflow");
+
+            let mut vec = Vec::with_capacity(len);
+            for range in ranges {
+                vec.extend_from_slice(&bytes[range.clone()]);
+            }
+
+            let mut list = BytesList::new();
+            list.push(Bytes::from(vec));
+            list
+        }
+    }
+}
+
+/// A seekable [`Buf`] over a [`BytesList`], created by [`BytesList::cursor`].
+#[derive(Clone)]
+pub struct BytesListCursor<'a> {
+    list: &'a BytesList,
+    pos: usize,
+    /// Index of the segment holding `pos`, equal to the number of segments once `pos` reached
+    /// the end of the list.
+    segment: usize,
+}
+
+impl BytesListCursor<'_> {
+    /// Return the position of the cursor from the start of the list.
+    #[inline]
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Move the cursor to `pos`. This runs in O(log n) with `n` the number of segments.
+    ///
+    /// # Panics
+    ///
+    /// This panics if `pos` is greater than the len of the list.
+    pub fn seek(&mut self, pos: usize) {
+        assert!(
+            pos <= self.list.len,
+            "position out of bounds: pos ({}) > len ({})",
+            pos,
+            self.list.len
+        );
+
+        self.pos = pos;
+        self.segment = if pos == self.list.len {
+            self.list.segments.len()
+        } else {
+            self.list.segment_of(pos)
+        };
+    }
+}
+
+impl Buf for BytesListCursor<'_> {
+    #[inline]
+    fn remaining(&self) -> usize {
+        self.list.len - self.pos
+    }
+
+    fn chuncks(&self) -> &[u8] {
+        match self.list.segments.get(self.segment) {
+            Some(segment) => &segment[self.pos - self.list.offsets[self.segment]..],
+            None => &[],
+        }
+    }
+
+    fn peek_to_slice(&self, dst: &mut [u8]) {
+        // A cursor is only a position in the list, reading from a copy leaves `self` untouched
+        self.clone().copy_to_slice(dst)
+    }
+
+    fn fork(&self) -> Bytes {
+        let rest = self.list.slice(self.pos..);
+
+        match rest.segments() {
+            [] => Bytes::new(),
+            [segment] => segment.clone(),
+            segments => {
+                let mut vec = Vec::with_capacity(rest.len());
+                for segment in segments {
+                    vec.extend_from_slice(segment);
+                }
+                Bytes::from(vec)
+            }
+        }
+    }
+
+    fn advance(&mut self, cnt: usize) {
+        assert!(
+            cnt <= self.remaining(),
+            "cannot advance past remaining: cnt ({}) > remaining ({})",
+            cnt,
+            self.remaining()
+        );
+
+        let pos = self.pos + cnt;
+
+        // Advancing within the current segment is the common case, avoid the binary search
+        match self.list.segments.get(self.segment) {
+            Some(segment) if pos < self.list.offsets[self.segment] + segment.len() => {
+                self.pos = pos
+            }
+            _ => self.seek(pos),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn list() -> BytesList {
+        [&b"hello"[..], b" ", b"", b"wonderful", b" world"]
+            .into_iter()
+            .map(Bytes::from_static)
+            .collect()
+    }
+
+    #[test]
+    fn select() {
+        let row = Bytes::from(b"id=7;name=bob;age=42".to_vec());
+        let ranges = [0..4, 4..5, 10..13, 18..20];
+
+        let list = row.select(&ranges, Gather::Segmented);
+        assert_eq!(list.segments(), [&b"id=7;"[..], b"bob", b"42"]);
+        assert_eq!(list.segments()[1].as_ptr(), row[10..].as_ptr());
+
+        let list = row.select(&ranges, Gather::Coalesced);
+        assert_eq!(list.segments(), [&b"id=7;bob42"[..]]);
+        assert!(!list.segments()[0].is_subslice_of(&row));
+
+        // Ranges may overlap or be out of order
+        let list = row.select(&[10..13, 11..12], Gather::Segmented);
+        assert_eq!(list.into_bytes(), "bobo");
+
+        assert!(row.select(&[], Gather::Coalesced).is_empty());
+        assert!(row.select(&[3..3, 5..5], Gather::Segmented).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid bounds")]
+    fn select_out_of_bounds() {
+        let row = Bytes::from_static(b"abc");
+        let _ = row.select(&[0..1, 2..4], Gather::Segmented);
+    }
+
+    #[test]
+    fn into_bytes() {
+        let hello = Bytes::from_static(b"hello");
+        let single: BytesList = [hello.clone()].into_iter().collect();
+        assert_eq!(single.into_bytes().as_ptr(), hello.as_ptr());
+
+        assert_eq!(list().into_bytes(), "hello wonderful world");
+        assert!(BytesList::new().into_bytes().is_empty());
+    }
+
+    #[test]
+    fn cursor_seek() {
+        let list = list();
+        let mut cursor = list.cursor();
+
+        assert_eq!(list.len(), 21);
+        assert_eq!(list.segments().len(), 4);
+
+        cursor.seek(6);
+        assert_eq!(cursor.chuncks(), b"wonderful");
+
+        cursor.seek(8);
+        assert_eq!(cursor.chuncks(), b"nderful");
+        assert_eq!(cursor.remaining(), 13);
+
+        cursor.advance(7);
+        assert_eq!(cursor.chuncks(), b" world");
+
+        cursor.seek(21);
+        assert!(!cursor.has_remaining());
+        assert_eq!(cursor.chuncks(), b"");
+
+        cursor.seek(0);
+        let mut dst = [0; 21];
+        cursor.copy_to_slice(&mut dst);
+        assert_eq!(&dst, b"hello wonderful world");
+    }
+
+    #[test]
+    fn slice() {
+        let list = list();
+
+        let slice = list.slice(3..17);
+        assert_eq!(slice.len(), 14);
+        assert_eq!(slice.segments(), [&b"lo"[..], b" ", b"wonderful", b" w"]);
+        assert_eq!(slice.segments()[2].as_ptr(), list.segments()[2].as_ptr());
+
+        assert_eq!(list.slice(7..9).segments(), [&b"on"[..]]);
+        assert!(list.slice(5..5).is_empty());
+        assert_eq!(list.slice(..).len(), list.len());
+    }
+
+    #[test]
+    fn laws() {
+        let list = list();
+
+        crate::testsuite::assert_buf_laws(|| list.cursor(), b"hello wonderful world");
+    }
+
+    #[test]
+    #[should_panic(expected = "list len overflow")]
+    fn push_overflow() {
+        let mut list = list();
+
+        // The segments can't fill the address space, pretend they do
+        list.len = crate::MAX_LEN - 2;
+        list.push(Bytes::from_static(b"abc"));
+    }
+}
@@ -1,24 +1,57 @@
-use core::fmt;
+use core::mem::ManuallyDrop;
 use core::ptr::{self, NonNull};
 use core::slice;
+use core::sync::atomic::{AtomicPtr, Ordering};
+use core::{cmp, fmt};
 
 use alloc::alloc::{alloc, dealloc, handle_alloc_error, realloc, Layout};
+use alloc::sync::Arc;
 
+use crate::budget::{BudgetExceeded, MemoryUsage, Tracker};
 use crate::buf::{Buf, BufMut, UninitSlice};
+use crate::bytes::OutOfBounds;
 
 pub struct BytesMut {
     ptr: NonNull<u8>,
     len: usize,
     cap: usize,
+
+    /// The tracker accounting the capacity of `self`. The global tracker is used if `None`.
+    budget: Option<Arc<Tracker>>,
+
+    /// `true` if the capacity of `self` is accounted by its tracker. This is always the case
+    /// with an own tracker, the global tracker only accounts the buffers which allocated while
+    /// it was enabled.
+    accounted: bool,
+
+    /// The growth policy of `self`. The default growth policy is used if `None`.
+    growth: Option<GrowthPolicy>,
 }
 
+/// A policy computing the new capacity of a [`BytesMut`] which needs to grow.
+///
+/// It's called with the current capacity and the capacity needed for the pending write, which is
+/// always greater than the current one. A result smaller than the needed capacity is ignored and
+/// if the returned capacity can't be allocated the buffer falls back to the needed capacity.
+///
+/// The policy only applies to the writes appending to the buffer (`push`, `extend_from_slice`
+/// and the `BufMut` methods), explicit reservations like [`BytesMut::reserve`] are exact.
+pub type GrowthPolicy = fn(current: usize, needed: usize) -> usize;
+
+/// The default growth policy of the buffers, null for [`BytesMut::builtin_growth`].
+static DEFAULT_GROWTH: AtomicPtr<()> = AtomicPtr::new(ptr::null_mut());
+
 impl BytesMut {
+    #[must_use]
     #[inline]
-    pub fn new() -> BytesMut {
+    pub const fn new() -> BytesMut {
         BytesMut {
             ptr: NonNull::dangling(),
             len: 0,
             cap: 0,
+            budget: None,
+            accounted: false,
+            growth: None,
         }
     }
 
@@ -27,7 +60,7 @@ impl BytesMut {
     ///
     /// # Panics
     ///
-    /// If the `cap` exceed `isize::MAX` the function will panic.
+    /// If the `cap` exceed `isize::MAX` or the memory budget the function will panic.
     ///
     /// ```should_panic
     /// use bytes::BytesMut;
@@ -35,35 +68,179 @@ impl BytesMut {
     /// // usize::MAX > isize::MAX so this should panic
     /// let _ = BytesMut::with_capacity(usize::MAX);
     /// ```
+    #[must_use]
     pub fn with_capacity(cap: usize) -> BytesMut {
-        assert!(
-            cap <= isize::MAX as usize,
-            "capacity too large, capacity must be inferior to `isize::MAX`"
-        );
+        let mut bytes_mut = BytesMut::new();
+        bytes_mut.realloc_or_panic(cap);
+        bytes_mut
+    }
 
-        let layout = Layout::array::<u8>(cap).unwrap();
-        let ptr = unsafe { alloc(layout) };
+    /// Create an empty `bytes::BytesMut` with a given capacity, returning an error instead of
+    /// panicking if the allocation fails.
+    pub fn try_with_capacity(cap: usize) -> Result<BytesMut, TryReserveError> {
+        let mut bytes_mut = BytesMut::new();
+        bytes_mut.try_realloc(cap)?;
+        Ok(bytes_mut)
+    }
 
-        let ptr = match NonNull::new(ptr) {
-            Some(ptr) => ptr,
-            None => handle_alloc_error(layout),
-        };
+    /// Create an empty `bytes::BytesMut` whose allocations are accounted by `tracker` instead of
+    /// the global tracker.
+    ///
+    /// See the [`budget`](crate::budget) module for more details.
+    #[must_use]
+    #[inline]
+    pub fn with_budget(tracker: Arc<Tracker>) -> BytesMut {
+        BytesMut {
+            budget: Some(tracker),
+            accounted: true,
+            ..BytesMut::new()
+        }
+    }
+
+    /// Return the tracker accounting the allocations of `self`.
+    #[inline]
+    pub fn budget(&self) -> &Tracker {
+        self.budget.as_deref().unwrap_or(Tracker::global())
+    }
+
+    /// Set the growth policy of `self`, overriding the default growth policy.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::BytesMut;
+    ///
+    /// const MIB: usize = 1 << 20;
+    ///
+    /// // Double the capacity up to 1 MiB then grow by 1 MiB increments
+    /// fn capped(current: usize, needed: usize) -> usize {
+    ///     if current < MIB {
+    ///         BytesMut::builtin_growth(current, needed)
+    ///     } else {
+    ///         needed.next_multiple_of(MIB)
+    ///     }
+    /// }
+    ///
+    /// let mut buf = BytesMut::with_capacity(2 * MIB);
+    /// buf.set_growth_policy(capped);
+    /// buf.extend_from_slice(&vec![0; 2 * MIB + 1]);
+    ///
+    /// assert_eq!(buf.capacity(), 3 * MIB);
+    /// ```
+    #[inline]
+    pub fn set_growth_policy(&mut self, policy: GrowthPolicy) {
+        self.growth = Some(policy);
+    }
 
-        BytesMut { ptr, cap, len: 0 }
+    /// Set the growth policy used by the buffers without their own policy, `None` restores the
+    /// [built-in policy](BytesMut::builtin_growth).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::BytesMut;
+    ///
+    /// fn exact(_: usize, needed: usize) -> usize {
+    ///     needed
+    /// }
+    ///
+    /// BytesMut::set_default_growth_policy(Some(exact));
+    ///
+    /// let mut buf = BytesMut::with_capacity(8);
+    /// buf.extend_from_slice(b"more than 8 bytes");
+    /// assert_eq!(buf.capacity(), 17);
+    ///
+    /// BytesMut::set_default_growth_policy(None);
+    /// ```
+    pub fn set_default_growth_policy(policy: Option<GrowthPolicy>) {
+        let ptr = policy.map_or(ptr::null_mut(), |policy| policy as *mut ());
+        DEFAULT_GROWTH.store(ptr, Ordering::Relaxed);
     }
 
-    pub fn len(&self) -> usize {
+    /// The built-in growth policy, doubling the capacity.
+    #[inline]
+    pub fn builtin_growth(current: usize, needed: usize) -> usize {
+        cmp::max(needed, current.saturating_mul(2))
+    }
+
+    /// Return the growth policy of `self`.
+    fn growth_policy(&self) -> GrowthPolicy {
+        if let Some(policy) = self.growth {
+            return policy;
+        }
+
+        let ptr = DEFAULT_GROWTH.load(Ordering::Relaxed);
+
+        if ptr.is_null() {
+            BytesMut::builtin_growth
+        } else {
+            // Safety: the pointer was created from a `GrowthPolicy` by `set_default_growth_policy`
+            unsafe { core::mem::transmute::<*mut (), GrowthPolicy>(ptr) }
+        }
+    }
+
+    /// Return the number of heap bytes allocated by `self`. This is the number of bytes
+    /// accounted by its [tracker](BytesMut::budget).
+    #[must_use]
+    #[inline]
+    pub const fn allocated_bytes(&self) -> usize {
+        self.cap
+    }
+
+    /// Return the heap memory attributed to `self`.
+    ///
+    /// A `BytesMut` exclusively owns its allocation and has no bookkeeping allocation, see
+    /// [`Bytes::memory_usage`](crate::Bytes::memory_usage) once it's frozen.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::BytesMut;
+    ///
+    /// let buf = BytesMut::with_capacity(64);
+    /// let usage = buf.memory_usage();
+    ///
+    /// assert_eq!(usage.allocation, 64);
+    /// assert_eq!(usage.overhead, 0);
+    /// assert!(!usage.shared);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub const fn memory_usage(&self) -> MemoryUsage {
+        MemoryUsage {
+            allocation: self.cap,
+            overhead: 0,
+            shared: false,
+        }
+    }
+
+    #[must_use]
+    #[inline]
+    pub const fn len(&self) -> usize {
         self.len
     }
 
-    pub fn is_empty(&self) -> bool {
+    #[must_use]
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
         self.len == 0
     }
 
-    pub fn capacity(&self) -> usize {
+    #[must_use]
+    #[inline]
+    pub const fn capacity(&self) -> usize {
         self.cap
     }
 
+    /// Return the number of bytes which can be written without reallocating, i.e.
+    /// `self.capacity() - self.len()`.
+    #[must_use]
+    #[inline]
+    pub const fn remaining_capacity(&self) -> usize {
+        self.cap - self.len
+    }
+
+    #[inline]
     pub fn push(&mut self, b: u8) {
         if self.len == self.cap {
             self.grow();
@@ -84,19 +261,144 @@ impl BytesMut {
         }
     }
 
+    /// Return an iterator over the chunks of the buffer made of valid utf8 followed by invalid
+    /// bytes, see [`Bytes::utf8_chunks`] for chunks which can outlive the borrow of `self`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::{BufMut, BytesMut};
+    ///
+    /// let mut buf = BytesMut::new();
+    /// buf.put_slice(b"ok\xff");
+    ///
+    /// let chunk = buf.utf8_chunks().next().unwrap();
+    /// assert_eq!(chunk.valid(), "ok");
+    /// assert_eq!(chunk.invalid(), b"\xff");
+    /// ```
+    #[inline]
+    pub fn utf8_chunks(&self) -> core::str::Utf8Chunks<'_> {
+        self.as_ref().utf8_chunks()
+    }
+
+    /// Shorten the buffer to keep the first `len` bytes, returning an error if `len` is greater
+    /// than the current len. The capacity is not changed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::{BufMut, BytesMut};
+    ///
+    /// let mut buf = BytesMut::new();
+    /// buf.put_slice(b"hello world");
+    ///
+    /// assert!(buf.truncate_checked(20).is_err());
+    /// assert!(buf.truncate_checked(5).is_ok());
+    /// assert_eq!(buf.as_ref(), b"hello");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This returns an error if `len` > `self.len()`, `self` is left untouched.
+    pub fn truncate_checked(&mut self, len: usize) -> Result<(), OutOfBounds> {
+        OutOfBounds::check(len, self.len)?;
+        self.len = len;
+        Ok(())
+    }
+
     /// Consume `self` and turns it into a `Vec<u8>`
+    #[deprecated(note = "use `BytesMut::into_vec` to reuse the buffer or \
+                         `BytesMut::as_vec_copy` to copy it")]
+    #[must_use]
+    #[inline]
     pub fn to_vec(self) -> alloc::vec::Vec<u8> {
+        self.into_vec()
+    }
+
+    /// Return a `Vec<u8>` holding a copy of the bytes of `self`, like the `to_vec` method of
+    /// `[u8]`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::{BufMut, BytesMut};
+    ///
+    /// let mut buf = BytesMut::new();
+    /// buf.put_slice(b"hello");
+    ///
+    /// assert_eq!(buf.as_vec_copy(), b"hello");
+    /// assert_eq!(buf.as_ref(), b"hello");
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn as_vec_copy(&self) -> alloc::vec::Vec<u8> {
+        self.as_ref().to_vec()
+    }
+
+    /// Consume `self` and turns it into a `Vec<u8>`, reusing its buffer without copying the
+    /// bytes.
+    ///
+    /// The capacity of the returned vector is no longer accounted by the memory budget.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::{BufMut, BytesMut};
+    ///
+    /// let mut buf = BytesMut::with_capacity(64);
+    /// buf.put_slice(b"hello");
+    ///
+    /// let vec = buf.into_vec();
+    /// assert_eq!(vec, b"hello");
+    /// assert_eq!(vec.capacity(), 64);
+    /// ```
+    #[must_use]
+    pub fn into_vec(self) -> alloc::vec::Vec<u8> {
+        // Avoid running the destructor of `self`, the buffer is now owned by the vec
+        let mut this = ManuallyDrop::new(self);
+
+        this.release(this.cap);
+        let budget = this.budget.take();
+        drop(budget);
+
+        if this.cap == 0 {
+            return alloc::vec::Vec::new();
+        }
+
         // Create the vec from ptr
-        let v = unsafe { alloc::vec::Vec::from_raw_parts(self.ptr.as_ptr(), self.len, self.cap) };
+        unsafe { alloc::vec::Vec::from_raw_parts(this.ptr.as_ptr(), this.len, this.cap) }
+    }
+
+    /// Create a `BytesMut` reusing the allocation of `vec`, accounted by the global memory
+    /// budget if it is enabled. The `Vec` is returned if the budget is exceeded.
+    pub(crate) fn try_from_vec(vec: alloc::vec::Vec<u8>) -> Result<BytesMut, alloc::vec::Vec<u8>> {
+        if vec.capacity() == 0 {
+            return Ok(BytesMut::new());
+        }
+
+        let accounted = Tracker::global().is_enabled();
+
+        if accounted && Tracker::global().try_acquire(vec.capacity()).is_err() {
+            return Err(vec);
+        }
 
-        // Forget `self` to avoid running it's destructor
-        core::mem::forget(self);
-        v
+        let mut vec = ManuallyDrop::new(vec);
+
+        Ok(BytesMut {
+            // Safety: a `Vec` with a non-zero capacity has a non-null pointer
+            ptr: unsafe { NonNull::new_unchecked(vec.as_mut_ptr()) },
+            len: vec.len(),
+            cap: vec.capacity(),
+            budget: None,
+            accounted,
+            growth: None,
+        })
     }
 
+    #[must_use]
     #[inline]
     pub fn freeze(self) -> crate::bytes::Bytes {
-        self.to_vec().into()
+        self.into_vec().into()
     }
 
     /// Set the len of `self` to `len`
@@ -105,7 +407,7 @@ impl BytesMut {
     ///
     /// * `len` must be inferior or equal to `self.cap`
     /// * all bytes in range `0..len` must be initialized or this will lead to **undefined
-    /// behaviours**.
+    ///   behaviours**.
     ///
     /// # Panics
     ///
@@ -133,22 +435,47 @@ impl BytesMut {
     /// assert_eq!(bytes_mut.capacity(), 10);
     /// assert_eq!(bytes_mut.as_ref(), b"10 bytes !");
     /// ```
+    #[inline]
     pub fn extend_from_slice(&mut self, slice: &[u8]) {
-        self.reserve(slice.len());
+        if self.cap - self.len < slice.len() {
+            self.reserve_for_extend(slice.len());
+        }
 
         // SAFETY:
-        // The reserve calls ensures that we have enough space in `self.ptr` to copy every bytes of
-        // `slice.as_ptr()`
-
-        unsafe {
-            self.ptr
-                .as_ptr()
-                .add(self.len)
-                .copy_from(slice.as_ptr(), slice.len());
-        }
+        // The capacity was checked above so there is enough space in `self.ptr` to copy every
+        // bytes of `slice.as_ptr()`
+        unsafe { copy_small(slice.as_ptr(), self.ptr.as_ptr().add(self.len), slice.len()) }
         self.len += slice.len();
     }
 
+    /// The slow path of `extend_from_slice`, kept out of line so that the common case of a
+    /// write fitting in the capacity stays small enough to be inlined.
+    #[cold]
+    #[inline(never)]
+    fn reserve_for_extend(&mut self, additional: usize) {
+        self.reserve_amortized(additional);
+    }
+
+    /// Reserve capacity for at least `res` more bytes.
+    ///
+    /// The capacity is grown to exactly `self.len() + res` bytes, the growth policy is not
+    /// applied.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::BytesMut;
+    ///
+    /// let mut bytes_mut = BytesMut::with_capacity(8);
+    /// bytes_mut.extend_from_slice(b"8 bytes!");
+    ///
+    /// bytes_mut.reserve(1);
+    /// assert_eq!(bytes_mut.capacity(), 9);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This panics if the new capacity exceeds `isize::MAX` or the memory budget.
     #[inline]
     pub fn reserve(&mut self, res: usize) {
         let rem = self.cap - self.len;
@@ -157,7 +484,75 @@ impl BytesMut {
             return;
         }
 
-        self.inner_reserve(self.cap + (res - rem));
+        let cap = self.cap.checked_add(res - rem).expect("capacity overflow");
+        self.realloc_or_panic(cap);
+    }
+
+    /// Try to reserve capacity for at least `res` more bytes. On error `self` is left unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::{BytesMut, TryReserveError};
+    ///
+    /// let mut bytes_mut = BytesMut::new();
+    ///
+    /// assert!(bytes_mut.try_reserve(16).is_ok());
+    /// assert_eq!(bytes_mut.try_reserve(usize::MAX), Err(TryReserveError::CapacityOverflow));
+    /// ```
+    pub fn try_reserve(&mut self, res: usize) -> Result<(), TryReserveError> {
+        let rem = self.cap - self.len;
+
+        if rem >= res {
+            return Ok(());
+        }
+
+        let cap = self
+            .cap
+            .checked_add(res - rem)
+            .ok_or(TryReserveError::CapacityOverflow)?;
+        self.try_realloc(cap)
+    }
+
+    /// Reserve capacity for a total of at least `total` bytes, counting the bytes already in
+    /// `self`. This does nothing if the capacity is already large enough.
+    ///
+    /// Unlike [`BytesMut::reserve`], which is relative to the len, this takes the size of the
+    /// whole buffer, e.g. the size of a frame read from its header.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::BytesMut;
+    ///
+    /// let mut frame = BytesMut::new();
+    /// frame.extend_from_slice(b"\x00\x0a");
+    ///
+    /// // The header gives the size of the whole frame
+    /// frame.reserve_total(2 + 10);
+    ///
+    /// assert!(frame.capacity() >= 12);
+    /// assert!(frame.remaining_capacity() >= 10);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This panics if `total` exceeds `isize::MAX` or the memory budget.
+    #[inline]
+    pub fn reserve_total(&mut self, total: usize) {
+        if self.cap < total {
+            self.realloc_or_panic(total);
+        }
+    }
+
+    /// Try to reserve capacity for a total of at least `total` bytes like
+    /// [`BytesMut::reserve_total`]. On error `self` is left unchanged.
+    pub fn try_reserve_total(&mut self, total: usize) -> Result<(), TryReserveError> {
+        if self.cap < total {
+            self.try_realloc(total)?;
+        }
+
+        Ok(())
     }
 
     #[inline]
@@ -169,56 +564,168 @@ impl BytesMut {
         }
     }
 
-    fn inner_reserve(&mut self, cap: usize) {
-        assert!(cap <= isize::MAX as usize, "capacity too large");
+    #[inline]
+    pub(crate) fn as_mut_slice(&mut self) -> &mut [u8] {
+        if self.cap == 0 {
+            &mut []
+        } else {
+            unsafe { slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+        }
+    }
 
-        let layout = Layout::array::<u8>(cap).unwrap();
+    fn grow(&mut self) {
+        self.reserve_amortized(1);
+    }
 
-        let ptr = if self.cap == 0 {
-            unsafe { alloc(layout) }
-        } else {
-            let old_layout = Layout::array::<u8>(self.cap).unwrap();
-            let old_ptr = self.ptr.as_ptr();
+    /// Reserve capacity for at least `additional` more bytes following the growth policy, for
+    /// the writes appending to `self`.
+    fn reserve_amortized(&mut self, additional: usize) {
+        let rem = self.cap - self.len;
 
-            unsafe { realloc(old_ptr, old_layout, layout.size()) }
-        };
+        if rem >= additional {
+            return;
+        }
 
-        self.ptr = match NonNull::new(ptr) {
-            Some(ptr) => ptr,
-            None => handle_alloc_error(layout),
-        };
-        self.cap = cap;
+        let cap = self
+            .cap
+            .checked_add(additional - rem)
+            .expect("capacity overflow");
+        self.grow_or_panic(cap);
     }
 
-    fn grow(&mut self) {
-        let (cap, layout) = if self.cap == 0 {
-            (1, Layout::array::<u8>(1).unwrap())
-        } else {
-            let new_cap = 2 * self.cap;
+    fn grow_or_panic(&mut self, needed: usize) {
+        let res = self.try_grow(needed);
+        BytesMut::unwrap_or_panic(res)
+    }
 
-            (new_cap, Layout::array::<u8>(new_cap).unwrap())
-        };
+    /// Grow the buffer to hold at least `needed` bytes following its growth policy.
+    fn try_grow(&mut self, needed: usize) -> Result<(), TryReserveError> {
+        let cap = self.growth_policy()(self.cap, needed);
+
+        if cap > needed && self.try_realloc(cap).is_ok() {
+            return Ok(());
+        }
 
-        assert!(cap <= isize::MAX as usize, "allocation too large");
+        self.try_realloc(needed)
+    }
+
+    fn realloc_or_panic(&mut self, cap: usize) {
+        let res = self.try_realloc(cap);
+        BytesMut::unwrap_or_panic(res)
+    }
+
+    fn unwrap_or_panic(res: Result<(), TryReserveError>) {
+        match res {
+            Ok(()) => (),
+            Err(TryReserveError::AllocError { layout }) => handle_alloc_error(layout),
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    /// Grow the buffer to `cap` bytes. This is the only function allocating memory for `self`.
+    fn try_realloc(&mut self, cap: usize) -> Result<(), TryReserveError> {
+        debug_assert!(cap >= self.cap);
+
+        if cap == self.cap {
+            return Ok(());
+        }
+
+        if cap > isize::MAX as usize {
+            return Err(TryReserveError::CapacityOverflow);
+        }
+
+        let layout = Layout::array::<u8>(cap).map_err(|_| TryReserveError::CapacityOverflow)?;
+
+        self.acquire(cap - self.cap)?;
 
         let ptr = if self.cap == 0 {
             unsafe { alloc(layout) }
         } else {
             let old_layout = Layout::array::<u8>(self.cap).unwrap();
             let old_ptr = self.ptr.as_ptr();
+
             unsafe { realloc(old_ptr, old_layout, layout.size()) }
         };
 
-        self.ptr = match NonNull::new(ptr) {
-            Some(ptr) => ptr,
-            None => handle_alloc_error(layout),
-        };
-        self.cap = cap;
+        match NonNull::new(ptr) {
+            Some(ptr) => {
+                self.ptr = ptr;
+                self.cap = cap;
+                Ok(())
+            }
+            None => {
+                self.release(cap - self.cap);
+                Err(TryReserveError::AllocError { layout })
+            }
+        }
+    }
+
+    /// Account `n` more bytes of capacity with the tracker of `self`.
+    ///
+    /// The buffers of the disabled global tracker are not accounted, so that programs which
+    /// don't use a budget don't update its shared counter on every allocation. Once the tracker
+    /// is enabled, a buffer growing starts being accounted with its whole capacity.
+    fn acquire(&mut self, n: usize) -> Result<(), BudgetExceeded> {
+        if self.accounted {
+            return self.budget().try_acquire(n);
+        }
+
+        let global = Tracker::global();
+
+        if !global.is_enabled() {
+            return Ok(());
+        }
+
+        global.try_acquire(self.cap + n)?;
+        self.accounted = true;
+        Ok(())
+    }
+
+    /// Stop accounting `n` bytes of capacity if `self` is accounted.
+    #[inline]
+    fn release(&self, n: usize) {
+        if self.accounted {
+            self.budget().release(n);
+        }
+    }
+}
+
+/// Copy `len` bytes from `src` to `dst`.
+///
+/// The short copies of encoders writing the fields of a message are done with at most two
+/// overlapping unaligned loads and stores instead of a call to `memcpy`.
+///
+/// # Safety
+///
+/// `src` must be valid for reads of `len` bytes, `dst` must be valid for writes of `len` bytes
+/// and the two ranges must not overlap.
+#[inline(always)]
+unsafe fn copy_small(src: *const u8, dst: *mut u8, len: usize) {
+    #[inline(always)]
+    unsafe fn copy_pair<T>(src: *const u8, dst: *mut u8, len: usize) {
+        // The two copies overlap when `len` is not twice the size of `T`, writing the same
+        // bytes twice
+        let tail = len - core::mem::size_of::<T>();
+        let head = src.cast::<T>().read_unaligned();
+        let end = src.add(tail).cast::<T>().read_unaligned();
+        dst.cast::<T>().write_unaligned(head);
+        dst.add(tail).cast::<T>().write_unaligned(end);
+    }
+
+    match len {
+        0 => {}
+        1 => *dst = *src,
+        2..=3 => copy_pair::<u16>(src, dst, len),
+        4..=7 => copy_pair::<u32>(src, dst, len),
+        8..=16 => copy_pair::<u64>(src, dst, len),
+        _ => ptr::copy_nonoverlapping(src, dst, len),
     }
 }
 
 impl Drop for BytesMut {
     fn drop(&mut self) {
+        self.release(self.cap);
+
         if self.cap != 0 {
             let layout = Layout::array::<u8>(self.cap).unwrap();
             unsafe { dealloc(self.ptr.as_ptr(), layout) };
@@ -233,16 +740,61 @@ impl Default for BytesMut {
     }
 }
 
+/// The error returned by the fallible allocation methods of [`BytesMut`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TryReserveError {
+    /// The requested capacity exceeds `isize::MAX`.
+    CapacityOverflow,
+    /// The allocator failed to allocate the requested memory.
+    AllocError {
+        /// The layout of the failed allocation.
+        layout: Layout,
+    },
+    /// The allocation would exceed the memory budget.
+    BudgetExceeded(BudgetExceeded),
+}
+
+impl From<BudgetExceeded> for TryReserveError {
+    #[inline]
+    fn from(value: BudgetExceeded) -> Self {
+        TryReserveError::BudgetExceeded(value)
+    }
+}
+
+impl fmt::Display for TryReserveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TryReserveError::CapacityOverflow => {
+                write!(
+                    f,
+                    "capacity too large, capacity must be inferior to `isize::MAX`"
+                )
+            }
+            TryReserveError::AllocError { layout } => {
+                write!(f, "memory allocation of {} bytes failed", layout.size())
+            }
+            TryReserveError::BudgetExceeded(e) => fmt::Display::fmt(e, f),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for TryReserveError {}
+
 unsafe impl Sync for BytesMut {}
 
 unsafe impl Send for BytesMut {}
 
+// The len is only increased once the bytes are written and the budget is acquired before the
+// allocation and released if it fails, so a panic leaves `self` consistent.
+const _: () = crate::assert_unwind_safe::<BytesMut>();
+
 // === impl `bytes::BufMut` ===
 
 impl BufMut for BytesMut {
     fn remaining_mut(&self) -> usize {
         // `alloc::vec` ensures that vectors don't allocate more than `isize::MAX` bytes
-        core::isize::MAX as usize - self.len()
+        crate::MAX_LEN - self.len()
     }
 
     unsafe fn advance(&mut self, count: usize) {
@@ -260,6 +812,11 @@ impl BufMut for BytesMut {
     }
 
     fn chuncks_mut(&mut self) -> &mut UninitSlice {
+        // The buffer grows on demand so we make sure that the returned chunck is never empty
+        if self.capacity() == self.len() {
+            self.reserve_amortized(64);
+        }
+
         let cap = self.capacity();
         let len = self.len();
         let ptr = self.ptr.as_ptr();
@@ -272,7 +829,7 @@ impl BufMut for BytesMut {
         T: Buf,
         Self: Sized,
     {
-        self.reserve(src.remaining());
+        self.reserve_amortized(src.remaining());
 
         while src.has_remaining() {
             let chunck = src.chuncks();
@@ -286,6 +843,16 @@ impl BufMut for BytesMut {
     fn put_slice(&mut self, src: &[u8]) {
         self.extend_from_slice(src);
     }
+
+    #[inline]
+    fn put_u8(&mut self, byte: u8) {
+        self.push(byte);
+    }
+
+    #[inline]
+    fn reserve_hint(&mut self, additional: usize) {
+        self.reserve_amortized(additional);
+    }
 }
 
 // === AsRef / Deref / Borrow ===
@@ -318,6 +885,7 @@ impl fmt::Write for BytesMut {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::test_alloc;
 
     #[test]
     fn new() {
@@ -335,6 +903,282 @@ mod test {
         assert_eq!(bytes_mut.cap, 10);
     }
 
+    #[test]
+    fn reserve_total() {
+        let mut bytes_mut = BytesMut::with_capacity(8);
+        bytes_mut.extend_from_slice(b"abcd");
+        assert_eq!(bytes_mut.remaining_capacity(), 4);
+
+        // Already large enough
+        bytes_mut.reserve_total(6);
+        assert_eq!(bytes_mut.capacity(), 8);
+
+        bytes_mut.reserve_total(20);
+        assert!(bytes_mut.capacity() >= 20);
+        assert_eq!(bytes_mut.remaining_capacity(), bytes_mut.capacity() - 4);
+
+        assert_eq!(
+            bytes_mut.try_reserve_total(usize::MAX),
+            Err(TryReserveError::CapacityOverflow)
+        );
+        assert_eq!(bytes_mut.as_ref(), b"abcd");
+    }
+
+    #[test]
+    fn reserve_overflow() {
+        let mut bytes_mut = BytesMut::with_capacity(8);
+        bytes_mut.extend_from_slice(b"abcd");
+
+        // The capacity would exceed `isize::MAX` without overflowing a `usize`
+        assert_eq!(
+            bytes_mut.try_reserve(crate::MAX_LEN),
+            Err(TryReserveError::CapacityOverflow)
+        );
+        assert_eq!(
+            bytes_mut.try_reserve(usize::MAX),
+            Err(TryReserveError::CapacityOverflow)
+        );
+        assert_eq!(bytes_mut.as_ref(), b"abcd");
+        assert_eq!(bytes_mut.capacity(), 8);
+
+        let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            bytes_mut.reserve(usize::MAX)
+        }));
+        assert!(res.is_err());
+        assert_eq!(bytes_mut.as_ref(), b"abcd");
+    }
+
+    #[test]
+    #[should_panic(expected = "put_many len overflow")]
+    fn put_many_overflow() {
+        /// A `Buf` claiming `self.0` bytes, it is never read
+        #[derive(Clone)]
+        struct Claim(usize);
+
+        impl Buf for Claim {
+            fn remaining(&self) -> usize {
+                self.0
+            }
+
+            fn chuncks(&self) -> &[u8] {
+                unreachable!()
+            }
+
+            fn advance(&mut self, _: usize) {
+                unreachable!()
+            }
+
+            fn fork(&self) -> crate::Bytes {
+                unreachable!()
+            }
+        }
+
+        BytesMut::new().put_many([Claim(crate::MAX_LEN), Claim(1)]);
+    }
+
+    #[test]
+    fn budget() {
+        let tracker = Arc::new(Tracker::new(16));
+        let mut bytes_mut = BytesMut::with_budget(tracker.clone());
+
+        bytes_mut.extend_from_slice(b"0123456789");
+        assert_eq!(tracker.used(), bytes_mut.capacity());
+
+        assert!(matches!(
+            bytes_mut.try_reserve(100),
+            Err(TryReserveError::BudgetExceeded(_))
+        ));
+        assert_eq!(bytes_mut.as_ref(), b"0123456789");
+
+        let vec = bytes_mut.into_vec();
+        assert_eq!(tracker.used(), 0);
+        assert_eq!(vec, b"0123456789");
+    }
+
+    #[test]
+    fn global_budget_disabled() {
+        // No test enables the global tracker
+        let bytes_mut = BytesMut::with_capacity(64);
+        assert!(!bytes_mut.accounted);
+
+        let bytes_mut = BytesMut::try_from_vec(bytes_mut.into_vec()).unwrap();
+        assert!(!bytes_mut.accounted);
+        assert_eq!(Tracker::global().used(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "memory budget exceeded")]
+    fn budget_panics() {
+        let mut bytes_mut = BytesMut::with_budget(Arc::new(Tracker::new(4)));
+
+        bytes_mut.extend_from_slice(b"0123456789");
+    }
+
+    #[test]
+    fn put_slice_repeated() {
+        for count in [0, 1, 2, 7, 100] {
+            let mut bytes_mut = BytesMut::new();
+            bytes_mut.put_slice_repeated(b"abc", count);
+            assert_eq!(bytes_mut.as_ref(), b"abc".repeat(count));
+        }
+
+        // The pattern continues across the chunks
+        let mut buf = Vec::with_capacity(64).limit(5).chain_mut(Vec::new());
+        buf.put_slice_repeated(b"abc", 5);
+
+        let (first, second) = buf.into_inner();
+        assert_eq!(first.into_inner(), b"abcab");
+        assert_eq!(second, b"cabcabcabc");
+    }
+
+    #[test]
+    fn panic_during_put() {
+        /// A `Buf` made of chuncks of one byte panicking after `self.0` bytes
+        struct Faulty(usize, &'static [u8]);
+
+        impl Buf for Faulty {
+            fn remaining(&self) -> usize {
+                self.1.len()
+            }
+
+            fn chuncks(&self) -> &[u8] {
+                assert!(self.0 > 0, "faulty buffer");
+                &self.1[..1]
+            }
+
+            fn advance(&mut self, cnt: usize) {
+                self.0 -= cnt;
+                self.1 = &self.1[cnt..];
+            }
+
+            fn fork(&self) -> crate::Bytes {
+                crate::Bytes::copy_from_slice(self.1)
+            }
+        }
+
+        let tracker = Arc::new(Tracker::new(1024));
+        let mut bytes_mut = BytesMut::with_budget(tracker.clone());
+
+        let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            bytes_mut.put(Faulty(5, b"hello world"));
+        }));
+
+        assert!(res.is_err());
+        assert_eq!(bytes_mut.as_ref(), b"hello");
+        assert_eq!(tracker.used(), bytes_mut.capacity());
+
+        drop(bytes_mut);
+        assert_eq!(tracker.used(), 0);
+    }
+
+    #[test]
+    fn growth_policy() {
+        fn by_16(_: usize, needed: usize) -> usize {
+            needed.next_multiple_of(16)
+        }
+
+        let mut bytes_mut = BytesMut::new();
+        bytes_mut.set_growth_policy(by_16);
+
+        bytes_mut.push(0);
+        assert_eq!(bytes_mut.capacity(), 16);
+        bytes_mut.extend_from_slice(&[0; 16]);
+        assert_eq!(bytes_mut.capacity(), 32);
+
+        // The needed capacity is used if the policy one exceeds the budget
+        let mut bytes_mut = BytesMut::with_budget(Arc::new(Tracker::new(20)));
+        bytes_mut.set_growth_policy(by_16);
+
+        bytes_mut.extend_from_slice(&[0; 17]);
+        assert_eq!(bytes_mut.capacity(), 17);
+    }
+
+    #[test]
+    fn reserve_exact() {
+        let mut bytes_mut = BytesMut::with_capacity(8);
+        bytes_mut.extend_from_slice(&[0; 8]);
+
+        bytes_mut.reserve(1);
+        assert_eq!(bytes_mut.capacity(), 9);
+        bytes_mut.try_reserve(2).unwrap();
+        assert_eq!(bytes_mut.capacity(), 10);
+        bytes_mut.reserve_total(11);
+        assert_eq!(bytes_mut.capacity(), 11);
+
+        // The appending writes still follow the growth policy
+        bytes_mut.extend_from_slice(&[0; 4]);
+        assert_eq!(bytes_mut.capacity(), 22);
+        bytes_mut.put(&[0; 11][..]);
+        assert_eq!(bytes_mut.capacity(), 44);
+    }
+
+    #[test]
+    fn alloc_failure() {
+        let tracker = Arc::new(Tracker::new(1024));
+        let mut bytes_mut = BytesMut::with_budget(tracker.clone());
+        bytes_mut.extend_from_slice(b"hello");
+
+        let res = test_alloc::fail_after(0, || bytes_mut.try_reserve(100));
+
+        assert!(matches!(res, Err(TryReserveError::AllocError { .. })));
+        assert_eq!(bytes_mut.as_ref(), b"hello");
+        assert_eq!(tracker.used(), bytes_mut.capacity());
+    }
+
+    #[test]
+    fn grow_moving() {
+        let bytes_mut = test_alloc::move_on_realloc(|| {
+            let mut bytes_mut = BytesMut::new();
+
+            for b in 0..=255 {
+                bytes_mut.push(b);
+            }
+            bytes_mut.extend_from_slice(&[0; 1000]);
+            bytes_mut
+        });
+
+        assert_eq!(bytes_mut.len(), 1256);
+        assert!(bytes_mut.as_ref()[..256].iter().copied().eq(0..=255));
+        assert_eq!(bytes_mut.freeze().slice(256..), [0; 1000][..]);
+    }
+
+    #[test]
+    fn put_many() {
+        let segments = [&b"hello"[..], b" ", b"wonderful", b" ", b"world"];
+        let mut bytes_mut = BytesMut::new();
+
+        // A single allocation for the buffer
+        let ((), allocs) = test_alloc::count_allocs(|| bytes_mut.put_many(segments));
+
+        assert_eq!(allocs, 1);
+        assert_eq!(bytes_mut.as_ref(), b"hello wonderful world");
+
+        // The reservation goes through the adapters
+        let segments = [&[0xff; 1000][..]; 5];
+        let mut wrapped = crate::CountingBufMut::new(BytesMut::new().limit(8192));
+        let ((), allocs) = test_alloc::count_allocs(|| wrapped.put_many(segments));
+
+        assert_eq!(allocs, 1);
+        assert_eq!(wrapped.count(), 5000);
+    }
+
+    #[test]
+    fn small_copies() {
+        let src: Vec<u8> = (0..40).collect();
+
+        for len in 0..src.len() {
+            let mut bytes_mut = BytesMut::with_capacity(64);
+            bytes_mut.push(0xff);
+            bytes_mut.extend_from_slice(&src[..len]);
+            bytes_mut.put_u8(0xfe);
+
+            assert_eq!(bytes_mut.len(), len + 2);
+            assert_eq!(bytes_mut.as_ref()[0], 0xff);
+            assert_eq!(&bytes_mut.as_ref()[1..=len], &src[..len]);
+            assert_eq!(bytes_mut.as_ref()[len + 1], 0xfe);
+        }
+    }
+
     #[test]
     fn to_vec() {
         let mut bytes_mut = BytesMut::with_capacity(10);
@@ -344,7 +1188,7 @@ mod test {
         bytes_mut.push(0);
         bytes_mut.push(0);
 
-        let vec = bytes_mut.to_vec();
+        let vec = bytes_mut.into_vec();
 
         assert_eq!(vec.capacity(), 10);
         assert_eq!(vec.len(), 4);
@@ -0,0 +1,170 @@
+use core::borrow::Borrow;
+use core::ops::Deref;
+
+use crate::Bytes;
+
+/// The borrowed counterpart of [`Bytes`], like `str` is the borrowed counterpart of `String`.
+///
+/// A `&BytesRef` can be obtained from a `&Bytes`, a `&[u8]` or anything implementing
+/// `AsRef<[u8]>` without copying, and [`ToOwned`] turns it into a `Bytes`. This makes
+/// `Cow<'_, BytesRef>` usable to hold either borrowed or shared bytes.
+///
+/// # Example
+///
+/// ```
+/// use std::borrow::Cow;
+///
+/// use bytes::{Bytes, BytesRef};
+///
+/// fn header_name(raw: &BytesRef) -> Cow<'_, BytesRef> {
+///     if raw.iter().any(u8::is_ascii_uppercase) {
+///         Cow::Owned(Bytes::from(raw.to_ascii_lowercase()))
+///     } else {
+///         Cow::Borrowed(raw)
+///     }
+/// }
+///
+/// let bytes = Bytes::from_static(b"content-type");
+///
+/// assert!(matches!(header_name(bytes.as_bytes_ref()), Cow::Borrowed(_)));
+/// assert_eq!(header_name(BytesRef::new(b"Host")).into_owned(), "host");
+/// ```
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct BytesRef([u8]);
+
+impl BytesRef {
+    /// Create a `&BytesRef` borrowing the bytes of `src`.
+    #[inline]
+    pub fn new<S: AsRef<[u8]> + ?Sized>(src: &S) -> &BytesRef {
+        BytesRef::from_slice(src.as_ref())
+    }
+
+    /// Create a `&BytesRef` borrowing `src`.
+    #[inline]
+    pub const fn from_slice(src: &[u8]) -> &BytesRef {
+        // Safety: `BytesRef` is a transparent wrapper around `[u8]`
+        unsafe { &*(src as *const [u8] as *const BytesRef) }
+    }
+
+    /// Return the borrowed bytes as a slice.
+    #[inline]
+    pub const fn as_slice(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl Bytes {
+    /// Return the bytes of `self` as a `&BytesRef`, e.g. to build a `Cow<'_, BytesRef>`.
+    #[must_use]
+    #[inline]
+    pub fn as_bytes_ref(&self) -> &BytesRef {
+        BytesRef::from_slice(self.as_slice())
+    }
+}
+
+impl Deref for BytesRef {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl AsRef<[u8]> for BytesRef {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl AsRef<BytesRef> for BytesRef {
+    #[inline]
+    fn as_ref(&self) -> &BytesRef {
+        self
+    }
+}
+
+impl Borrow<BytesRef> for Bytes {
+    #[inline]
+    fn borrow(&self) -> &BytesRef {
+        BytesRef::from_slice(self.as_slice())
+    }
+}
+
+impl ToOwned for BytesRef {
+    type Owned = Bytes;
+
+    #[inline]
+    fn to_owned(&self) -> Bytes {
+        Bytes::copy_from_slice(&self.0)
+    }
+}
+
+impl<'a> From<&'a [u8]> for &'a BytesRef {
+    #[inline]
+    fn from(value: &'a [u8]) -> &'a BytesRef {
+        BytesRef::from_slice(value)
+    }
+}
+
+impl<'a> From<&'a Bytes> for &'a BytesRef {
+    #[inline]
+    fn from(value: &'a Bytes) -> &'a BytesRef {
+        BytesRef::from_slice(value.as_slice())
+    }
+}
+
+impl From<&BytesRef> for Bytes {
+    #[inline]
+    fn from(value: &BytesRef) -> Bytes {
+        value.to_owned()
+    }
+}
+
+impl PartialEq<[u8]> for BytesRef {
+    fn eq(&self, other: &[u8]) -> bool {
+        self.0 == *other
+    }
+}
+
+impl PartialEq<Bytes> for BytesRef {
+    fn eq(&self, other: &Bytes) -> bool {
+        self.0 == *other.as_slice()
+    }
+}
+
+impl PartialEq<BytesRef> for Bytes {
+    fn eq(&self, other: &BytesRef) -> bool {
+        *self.as_slice() == other.0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::borrow::Cow;
+    use std::collections::HashMap;
+
+    use super::*;
+
+    #[test]
+    fn lookup_by_ref() {
+        let mut map = HashMap::new();
+        map.insert(Bytes::from(b"key".to_vec()), 1);
+
+        assert_eq!(map.get(BytesRef::new(b"key")), Some(&1));
+        assert_eq!(map.get(BytesRef::new("missing")), None);
+    }
+
+    #[test]
+    fn cow() {
+        let bytes = Bytes::from_static(b"borrowed");
+        let cow: Cow<'_, BytesRef> = Cow::Borrowed(bytes.as_bytes_ref());
+
+        assert_eq!(*cow, bytes);
+
+        let owned = cow.into_owned();
+        assert_eq!(owned, "borrowed");
+    }
+}
@@ -0,0 +1,207 @@
+//! Consistent overhead byte stuffing (COBS).
+//!
+//! A frame is encoded without any `0` byte, with an overhead of at most one byte every 254 bytes,
+//! and is followed by a `0` delimiter. This allows a receiver to find the frame boundaries in a
+//! stream of bytes.
+//!
+//! # Example
+//!
+//! ```
+//! use bytes::codec::cobs;
+//!
+//! let mut encoded = Vec::new();
+//! cobs::encode(&mut &b"\x11\x00\x22\x33"[..], &mut encoded);
+//!
+//! assert_eq!(encoded, [2, 0x11, 3, 0x22, 0x33, 0]);
+//!
+//! let mut decoded = Vec::new();
+//! cobs::decode(&mut &encoded[..], &mut decoded).unwrap();
+//!
+//! assert_eq!(decoded, b"\x11\x00\x22\x33");
+//! ```
+
+use super::DecodeError;
+use crate::{Buf, BufMut};
+
+/// The greatest number of non zero bytes in a block.
+const BLOCK: usize = 254;
+
+/// Encode all the remaining bytes of `src` as a single frame into `dst`, followed by the `0`
+/// delimiter.
+///
+/// # Panics
+///
+/// This panics if there is not enough space remaining in `dst`.
+pub fn encode<B: Buf, M: BufMut>(src: &mut B, dst: &mut M) {
+    let mut block = [0u8; BLOCK];
+    let mut len = 0;
+    // A full block isn't followed by an implicit `0`, so no empty block is needed after it at the
+    // end of the frame
+    let mut full = false;
+
+    while src.has_remaining() {
+        let chunck = src.chuncks();
+
+        for &b in chunck {
+            if b == 0 {
+                dst.put_u8(len as u8 + 1);
+                dst.put_slice(&block[..len]);
+                len = 0;
+                full = false;
+                continue;
+            }
+
+            block[len] = b;
+            len += 1;
+            full = false;
+
+            if len == BLOCK {
+                dst.put_u8(u8::MAX);
+                dst.put_slice(&block);
+                len = 0;
+                full = true;
+            }
+        }
+
+        let count = chunck.len();
+        src.advance(count);
+    }
+
+    if !full {
+        dst.put_u8(len as u8 + 1);
+        dst.put_slice(&block[..len]);
+    }
+
+    dst.put_u8(0);
+}
+
+/// Decode a frame from `src` into `dst`. The bytes of `src` are consumed up to and including the
+/// `0` delimiter of the frame.
+///
+/// # Errors
+///
+/// Returns [`DecodeError::Invalid`] if a block holds a `0` byte and
+/// [`DecodeError::UnexpectedEof`] if `src` ends before the delimiter. The bytes decoded before the
+/// error are written in `dst`.
+///
+/// # Panics
+///
+/// This panics if there is not enough space remaining in `dst`.
+pub fn decode<B: Buf, M: BufMut>(src: &mut B, dst: &mut M) -> Result<(), DecodeError> {
+    loop {
+        if !src.has_remaining() {
+            return Err(DecodeError::UnexpectedEof);
+        }
+
+        let code = src.get_u8();
+
+        if code == 0 {
+            return Ok(());
+        }
+
+        let mut count = code as usize - 1;
+        while count > 0 {
+            if !src.has_remaining() {
+                return Err(DecodeError::UnexpectedEof);
+            }
+
+            let chunck = src.chuncks();
+            let n = count.min(chunck.len());
+
+            if chunck[..n].contains(&0) {
+                return Err(DecodeError::Invalid);
+            }
+
+            dst.put_slice(&chunck[..n]);
+            src.advance(n);
+            count -= n;
+        }
+
+        if !src.has_remaining() {
+            return Err(DecodeError::UnexpectedEof);
+        }
+
+        // The last block of the frame isn't followed by an implicit `0`
+        if code != u8::MAX && src.peek_u8() != 0 {
+            dst.put_u8(0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Bytes;
+
+    fn round_trip(src: &[u8]) -> Vec<u8> {
+        let mut encoded = Vec::new();
+        encode(&mut &src[..], &mut encoded);
+
+        assert_eq!(
+            encoded.iter().position(|b| *b == 0),
+            Some(encoded.len() - 1)
+        );
+
+        let mut decoded = Vec::new();
+        let mut rest = &encoded[..];
+        decode(&mut rest, &mut decoded).unwrap();
+
+        assert!(rest.is_empty());
+        assert_eq!(decoded, src);
+        encoded
+    }
+
+    #[test]
+    fn encode_vectors() {
+        assert_eq!(round_trip(b""), [1, 0]);
+        assert_eq!(round_trip(b"\x00"), [1, 1, 0]);
+        assert_eq!(round_trip(b"\x00\x00"), [1, 1, 1, 0]);
+        assert_eq!(round_trip(b"\x11\x22\x00\x33"), [3, 0x11, 0x22, 2, 0x33, 0]);
+
+        let block: Vec<u8> = (1..=254).collect();
+        let encoded = round_trip(&block);
+        assert_eq!(encoded.len(), 256);
+        assert_eq!((encoded[0], encoded[255]), (0xff, 0));
+
+        let mut long = block.clone();
+        long.push(0xff);
+        let encoded = round_trip(&long);
+        assert_eq!(encoded[255..], [2, 0xff, 0]);
+
+        let mut zero = block;
+        zero.push(0);
+        let encoded = round_trip(&zero);
+        assert_eq!(encoded[255..], [1, 1, 0]);
+    }
+
+    #[test]
+    fn across_chuncks() {
+        let mut src = Bytes::from_static(b"ab\x00c").chain(Bytes::from(vec![0x42; 300]));
+        let mut encoded = Vec::new();
+        encode(&mut src, &mut encoded);
+
+        let mut decoded = Vec::new();
+        decode(&mut Bytes::from(encoded).chain(&b"next"[..]), &mut decoded).unwrap();
+
+        assert_eq!(decoded[..4], *b"ab\x00c");
+        assert_eq!(decoded[4..], [0x42; 300]);
+    }
+
+    #[test]
+    fn decode_errors() {
+        let mut out = Vec::new();
+
+        assert_eq!(
+            decode(&mut &[3, 1][..], &mut out),
+            Err(DecodeError::UnexpectedEof)
+        );
+        assert_eq!(
+            decode(&mut &[2, 1][..], &mut out),
+            Err(DecodeError::UnexpectedEof)
+        );
+        assert_eq!(
+            decode(&mut &[3, 1, 0, 0][..], &mut out),
+            Err(DecodeError::Invalid)
+        );
+    }
+}
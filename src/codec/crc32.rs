@@ -0,0 +1,193 @@
+//! Reflected CRC-32 checksums.
+//!
+//! The polynomials are given in their reflected form, as [`IEEE`] and [`CASTAGNOLI`]. The tables
+//! of these two polynomials are computed at compile time, the table of any other polynomial is
+//! computed on each call.
+//!
+//! # Example
+//!
+//! ```
+//! use bytes::codec::crc32;
+//!
+//! assert_eq!(crc32::checksum(crc32::IEEE, b"123456789"), 0xcbf4_3926);
+//! assert_eq!(crc32::checksum(crc32::CASTAGNOLI, b"123456789"), 0xe306_9283);
+//! ```
+
+use core::fmt;
+
+use crate::Bytes;
+
+/// The polynomial of the CRC-32 used by Ethernet, zlib and PNG.
+pub const IEEE: u32 = 0xedb8_8320;
+
+/// The polynomial of the CRC-32C used by iSCSI, SCTP and ext4.
+pub const CASTAGNOLI: u32 = 0x82f6_3b78;
+
+static IEEE_TABLE: [u32; 256] = table(IEEE);
+static CASTAGNOLI_TABLE: [u32; 256] = table(CASTAGNOLI);
+
+const fn table(polynomial: u32) -> [u32; 256] {
+    let mut table = [0; 256];
+    let mut i = 0;
+
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ polynomial
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+
+        table[i] = crc;
+        i += 1;
+    }
+
+    table
+}
+
+/// Compute the CRC-32 of `data` with the reflected `polynomial`.
+pub fn checksum(polynomial: u32, data: &[u8]) -> u32 {
+    let crc = match polynomial {
+        IEEE => update(&IEEE_TABLE, !0, data),
+        CASTAGNOLI => update(&CASTAGNOLI_TABLE, !0, data),
+        _ => update(&table(polynomial), !0, data),
+    };
+
+    !crc
+}
+
+#[inline]
+fn update(table: &[u32; 256], mut crc: u32, data: &[u8]) -> u32 {
+    for &b in data {
+        crc = table[((crc ^ b as u32) & 0xff) as usize] ^ (crc >> 8);
+    }
+
+    crc
+}
+
+impl Bytes {
+    /// Check the CRC-32 trailing `self` and return the payload preceding it.
+    ///
+    /// The trailer is the 4 bytes little endian checksum of the payload computed with the
+    /// reflected `polynomial`, as in Ethernet or gzip. The payload shares the buffer of `self`,
+    /// nothing is copied.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::codec::crc32;
+    /// use bytes::Bytes;
+    ///
+    /// let frame = Bytes::from_static(b"123456789\x26\x39\xf4\xcb");
+    /// let payload = frame.verify_trailer_crc32(crc32::IEEE).unwrap();
+    ///
+    /// assert_eq!(payload, "123456789");
+    ///
+    /// let err = frame.slice(1..).verify_trailer_crc32(crc32::IEEE).unwrap_err();
+    /// assert_eq!(err.expected(), Some(0xcbf4_3926));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`CrcMismatch`] if the trailer doesn't match the payload or if `self` is shorter
+    /// than the trailer.
+    pub fn verify_trailer_crc32(&self, polynomial: u32) -> Result<Bytes, CrcMismatch> {
+        let Some(at) = self.len().checked_sub(4) else {
+            return Err(CrcMismatch {
+                expected: None,
+                computed: checksum(polynomial, self),
+            });
+        };
+
+        let (payload, trailer) = self.as_slice().split_at(at);
+        let expected = u32::from_le_bytes(trailer.try_into().unwrap());
+        let computed = checksum(polynomial, payload);
+
+        if expected != computed {
+            return Err(CrcMismatch {
+                expected: Some(expected),
+                computed,
+            });
+        }
+
+        Ok(self.slice(..at))
+    }
+}
+
+/// The error returned by [`Bytes::verify_trailer_crc32`] when the checksum doesn't match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CrcMismatch {
+    expected: Option<u32>,
+    computed: u32,
+}
+
+impl CrcMismatch {
+    /// Return the checksum read in the trailer, `None` if the frame is too short to hold one.
+    #[inline]
+    pub fn expected(&self) -> Option<u32> {
+        self.expected
+    }
+
+    /// Return the checksum computed over the payload.
+    #[inline]
+    pub fn computed(&self) -> u32 {
+        self.computed
+    }
+}
+
+impl fmt::Display for CrcMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.expected {
+            Some(expected) => write!(
+                f,
+                "crc mismatch: expected ({:#010x}) != computed ({:#010x})",
+                expected, self.computed
+            ),
+            None => write!(f, "frame too short to hold a crc trailer"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CrcMismatch {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn checksum_polynomials() {
+        assert_eq!(checksum(IEEE, b""), 0);
+        assert_eq!(
+            checksum(IEEE, b"The quick brown fox jumps over the lazy dog"),
+            0x414f_a339
+        );
+        // CRC-32K (Koopman), its table is computed on the fly
+        assert_eq!(checksum(0xeb31_d82e, b"123456789"), 0x2d3d_d0ae);
+    }
+
+    #[test]
+    fn verify_trailer() {
+        let mut frame = b"hello world".to_vec();
+        frame.extend_from_slice(&checksum(CASTAGNOLI, b"hello world").to_le_bytes());
+        let frame = Bytes::from(frame);
+
+        let payload = frame.verify_trailer_crc32(CASTAGNOLI).unwrap();
+        assert_eq!(payload, "hello world");
+        assert_eq!(payload.as_ptr(), frame.as_ptr());
+
+        let err = frame.verify_trailer_crc32(IEEE).unwrap_err();
+        assert_eq!(err.computed(), checksum(IEEE, b"hello world"));
+
+        let err = Bytes::from_static(b"abc")
+            .verify_trailer_crc32(IEEE)
+            .unwrap_err();
+        assert_eq!(err.expected(), None);
+        assert_eq!(err.to_string(), "frame too short to hold a crc trailer");
+    }
+}
@@ -0,0 +1,183 @@
+//! Zig-zag delta encoding of integer sequences.
+//!
+//! Each value is encoded as the difference with the previous one (the first value is encoded as
+//! the difference with `0`). The difference is zig-zag mapped so that small negative deltas stay
+//! small, then written as a LEB128 varint.
+//!
+//! # Example
+//!
+//! ```
+//! use bytes::codec::delta;
+//!
+//! let mut encoded = Vec::new();
+//! delta::encode([1000, 1001, 999, 999], &mut encoded);
+//!
+//! assert_eq!(encoded, [0xd0, 0x0f, 2, 3, 0]);
+//!
+//! let decoded: Result<Vec<i64>, _> = delta::decode(&encoded[..]).collect();
+//! assert_eq!(decoded.unwrap(), [1000, 1001, 999, 999]);
+//! ```
+
+use super::DecodeError;
+use crate::{Buf, BufMut};
+
+/// The maximum len of a LEB128 encoded `u64`.
+const MAX_VARINT_LEN: usize = 10;
+
+/// Encode `values` into `dst`.
+///
+/// # Panics
+///
+/// This panics if there is not enough space remaining in `dst`.
+pub fn encode<I, M>(values: I, dst: &mut M)
+where
+    I: IntoIterator<Item = i64>,
+    M: BufMut,
+{
+    let mut prev = 0i64;
+    let mut varint = [0u8; MAX_VARINT_LEN];
+
+    for value in values {
+        let mut v = zigzag_encode(value.wrapping_sub(prev));
+        let mut len = 0;
+
+        loop {
+            let byte = (v & 0x7f) as u8;
+            v >>= 7;
+
+            if v == 0 {
+                varint[len] = byte;
+                len += 1;
+                break;
+            }
+
+            varint[len] = byte | 0x80;
+            len += 1;
+        }
+
+        dst.put_slice(&varint[..len]);
+        prev = value;
+    }
+}
+
+/// Create an iterator decoding the values encoded in `src`.
+///
+/// The iterator ends when `src` has no remaining bytes. If an invalid or truncated varint is
+/// found, an error is yielded and the iterator ends.
+pub fn decode<B: Buf>(src: B) -> Decoder<B> {
+    Decoder {
+        src,
+        prev: 0,
+        done: false,
+    }
+}
+
+/// An iterator over the values of a zig-zag delta encoded buffer.
+///
+/// This struct is created by [`decode`].
+#[derive(Debug)]
+pub struct Decoder<B> {
+    src: B,
+    prev: i64,
+    done: bool,
+}
+
+impl<B> Decoder<B> {
+    /// Consume `self` and return the inner buffer.
+    #[inline]
+    pub fn into_inner(self) -> B {
+        self.src
+    }
+}
+
+impl<B: Buf> Decoder<B> {
+    fn get_varint(&mut self) -> Result<u64, DecodeError> {
+        let mut value = 0u64;
+
+        for i in 0..MAX_VARINT_LEN {
+            if !self.src.has_remaining() {
+                return Err(DecodeError::UnexpectedEof);
+            }
+
+            let byte = self.src.get_u8();
+
+            // The 10th byte can only hold the last bit of a `u64`
+            if i == MAX_VARINT_LEN - 1 && byte > 1 {
+                return Err(DecodeError::Invalid);
+            }
+
+            value |= ((byte & 0x7f) as u64) << (7 * i);
+
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+
+        Err(DecodeError::Invalid)
+    }
+}
+
+impl<B: Buf> Iterator for Decoder<B> {
+    type Item = Result<i64, DecodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done || !self.src.has_remaining() {
+            return None;
+        }
+
+        match self.get_varint() {
+            Ok(v) => {
+                self.prev = self.prev.wrapping_add(zigzag_decode(v));
+                Some(Ok(self.prev))
+            }
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+#[inline]
+fn zigzag_encode(v: i64) -> u64 {
+    ((v << 1) ^ (v >> 63)) as u64
+}
+
+#[inline]
+fn zigzag_decode(v: u64) -> i64 {
+    ((v >> 1) as i64) ^ -((v & 1) as i64)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn zigzag() {
+        for (v, z) in [(0, 0), (-1, 1), (1, 2), (-2, 3), (i64::MAX, u64::MAX - 1)] {
+            assert_eq!(zigzag_encode(v), z);
+            assert_eq!(zigzag_decode(z), v);
+        }
+        assert_eq!(zigzag_encode(i64::MIN), u64::MAX);
+    }
+
+    #[test]
+    fn extremes() {
+        let values = [i64::MIN, i64::MAX, 0, -1, i64::MIN];
+        let mut encoded = Vec::new();
+
+        encode(values, &mut encoded);
+
+        let decoded: Result<Vec<i64>, _> = decode(&encoded[..]).collect();
+        assert_eq!(decoded.unwrap(), values);
+    }
+
+    #[test]
+    fn truncated() {
+        let mut decoder = decode(&[2, 0x80][..]);
+
+        assert_eq!(decoder.next(), Some(Ok(1)));
+        assert_eq!(decoder.next(), Some(Err(DecodeError::UnexpectedEof)));
+        assert_eq!(decoder.next(), None);
+    }
+}
@@ -0,0 +1,468 @@
+//! Frames prefixed with the length of their body.
+//!
+//! The length of a frame is usually only known once its body is written. A
+//! [`LengthPrefixedWriter`] reserves the length slot, lets the body be written after it and
+//! patches the slot on [`LengthPrefixedWriter::finish`]. The writers can be nested to build TLV
+//! trees.
+//!
+//! On the read side, [`MaxFrameLen`] validates the length read from a prefix before anything is
+//! allocated for the body, so that a peer claiming a 4 GiB frame is rejected upfront.
+//!
+//! # Example
+//!
+//! ```
+//! use bytes::codec::frame::LengthPrefix;
+//! use bytes::{BufMut, BytesMut};
+//!
+//! let mut buf = BytesMut::new();
+//! let mut outer = buf.length_prefixed(LengthPrefix::U16);
+//! outer.put_u8(0x01);
+//!
+//! let mut inner = outer.nested(LengthPrefix::U8);
+//! inner.put_slice(b"hi");
+//! inner.finish();
+//!
+//! outer.finish();
+//!
+//! assert_eq!(buf.as_ref(), b"\x00\x04\x01\x02hi");
+//! ```
+
+use core::fmt;
+
+use crate::{Buf, BufMut, Bytes, BytesMut, Finish, UninitSlice};
+
+/// The width of the big endian length written before a frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LengthPrefix {
+    /// A single byte, for bodies of at most 255 bytes.
+    U8,
+    /// Two bytes, for bodies of at most 65535 bytes.
+    U16,
+    /// Four bytes.
+    U32,
+}
+
+impl LengthPrefix {
+    /// Return the number of bytes of the length slot.
+    #[inline]
+    pub const fn width(self) -> usize {
+        match self {
+            LengthPrefix::U8 => 1,
+            LengthPrefix::U16 => 2,
+            LengthPrefix::U32 => 4,
+        }
+    }
+
+    /// Return the greatest body length which can be written in the slot.
+    #[inline]
+    pub const fn max_len(self) -> usize {
+        match self {
+            LengthPrefix::U8 => u8::MAX as usize,
+            LengthPrefix::U16 => u16::MAX as usize,
+            LengthPrefix::U32 => u32::MAX as usize,
+        }
+    }
+}
+
+/// The greatest body length accepted when decoding a frame.
+///
+/// A decoded length is checked against the cap first and then against the bytes available, with
+/// a single call to [`MaxFrameLen::check`] or [`MaxFrameLen::decode`]. A length above the cap is
+/// an error even if the bytes are available, and an acceptable length missing bytes tells how
+/// many more are needed, so a caller can safely reserve them.
+///
+/// # Example
+///
+/// ```
+/// use bytes::codec::frame::{FrameLenError, LengthPrefix, MaxFrameLen};
+/// use bytes::Bytes;
+///
+/// let max = MaxFrameLen::new(1024);
+///
+/// let mut buf = Bytes::from_static(b"\x00\x00\x00\x05hello\x00\x00");
+/// assert_eq!(max.decode(&mut buf, LengthPrefix::U32), Ok(Bytes::from_static(b"hello")));
+///
+/// // The header is not complete, nothing is consumed
+/// assert_eq!(
+///     max.decode(&mut buf, LengthPrefix::U32),
+///     Err(FrameLenError::Incomplete { needed: 2 })
+/// );
+/// assert_eq!(buf.len(), 2);
+///
+/// let mut buf = Bytes::from_static(b"\xff\xff\xff\xff");
+/// assert_eq!(
+///     max.decode(&mut buf, LengthPrefix::U32),
+///     Err(FrameLenError::TooLarge { len: 0xffff_ffff, max: 1024 })
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MaxFrameLen(usize);
+
+impl MaxFrameLen {
+    /// Accept bodies of at most `max` bytes.
+    #[inline]
+    pub const fn new(max: usize) -> MaxFrameLen {
+        MaxFrameLen(max)
+    }
+
+    /// Return the greatest accepted body length.
+    #[inline]
+    pub const fn get(self) -> usize {
+        self.0
+    }
+
+    /// Check a body length decoded from a prefix, `remaining` being the number of bytes
+    /// available for the body. Return the length as a `usize` if the whole body is available.
+    ///
+    /// The length is taken as a `u64` so that it is never truncated on 32-bit targets, a length
+    /// which doesn't fit in a `usize` is too large whatever the cap.
+    ///
+    /// # Errors
+    ///
+    /// Return [`FrameLenError::TooLarge`] if `len` exceeds the cap and
+    /// [`FrameLenError::Incomplete`] if fewer than `len` bytes are remaining.
+    pub fn check(self, len: u64, remaining: usize) -> Result<usize, FrameLenError> {
+        let body = usize::try_from(len)
+            .ok()
+            .filter(|body| *body <= self.0)
+            .ok_or(FrameLenError::TooLarge { len, max: self.0 })?;
+
+        if body > remaining {
+            return Err(FrameLenError::Incomplete {
+                needed: body - remaining,
+            });
+        }
+
+        Ok(body)
+    }
+
+    /// Decode a frame from `buf`: read its big endian length prefix, check it and return the
+    /// body. `buf` is only advanced when a frame is returned, after an error it still starts at
+    /// the prefix so decoding can be retried once more bytes are received.
+    ///
+    /// The body is extracted with [`Buf::copy_to_bytes`], it is not copied if `buf` is backed by
+    /// a `Bytes`.
+    ///
+    /// # Errors
+    ///
+    /// Return [`FrameLenError::TooLarge`] if the length exceeds the cap and
+    /// [`FrameLenError::Incomplete`] if the prefix or the body are not complete, `needed`
+    /// counting the bytes of both.
+    pub fn decode<B: Buf>(self, buf: &mut B, prefix: LengthPrefix) -> Result<Bytes, FrameLenError> {
+        let width = prefix.width();
+        let remaining = buf.remaining();
+
+        if remaining < width {
+            return Err(FrameLenError::Incomplete {
+                needed: width - remaining,
+            });
+        }
+
+        let mut be = [0; 8];
+        buf.peek_to_slice(&mut be[8 - width..]);
+
+        let body = self.check(u64::from_be_bytes(be), remaining - width)?;
+
+        buf.advance(width);
+        Ok(buf.copy_to_bytes(body))
+    }
+}
+
+/// The error returned when the length of a frame is rejected by [`MaxFrameLen`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameLenError {
+    /// The length exceeds the cap.
+    TooLarge {
+        /// The decoded length.
+        len: u64,
+        /// The cap.
+        max: usize,
+    },
+    /// The frame is not complete.
+    Incomplete {
+        /// The number of bytes missing.
+        needed: usize,
+    },
+}
+
+impl fmt::Display for FrameLenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FrameLenError::TooLarge { len, max } => {
+                write!(f, "frame too large: len ({}) > max len ({})", len, max)
+            }
+            FrameLenError::Incomplete { needed } => {
+                write!(f, "incomplete frame: {} more bytes needed", needed)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for FrameLenError {}
+
+impl BytesMut {
+    /// Start a frame prefixed with the length of its body, see [`LengthPrefixedWriter`].
+    #[inline]
+    pub fn length_prefixed(&mut self, prefix: LengthPrefix) -> LengthPrefixedWriter<'_> {
+        LengthPrefixedWriter::new(self, prefix)
+    }
+}
+
+/// A frame being written at the end of a `BytesMut`, created by [`BytesMut::length_prefixed`].
+///
+/// The body is written through the `BufMut` implementation of the writer. Dropping the writer
+/// without calling [`LengthPrefixedWriter::finish`] discards the frame: the buffer is truncated
+/// back to its len before the frame was started, so an error returned while writing the body
+/// doesn't leave a partial frame behind.
+#[must_use = "the frame is discarded unless `finish` is called"]
+pub struct LengthPrefixedWriter<'a> {
+    buf: &'a mut BytesMut,
+    /// The offset of the length slot in `buf`.
+    start: usize,
+    prefix: LengthPrefix,
+    finished: bool,
+}
+
+impl<'a> LengthPrefixedWriter<'a> {
+    /// Reserve the length slot at the end of `buf` and start writing the body after it.
+    pub fn new(buf: &'a mut BytesMut, prefix: LengthPrefix) -> LengthPrefixedWriter<'a> {
+        let start = buf.len();
+        buf.put_slice(&[0; 4][..prefix.width()]);
+
+        LengthPrefixedWriter {
+            buf,
+            start,
+            prefix,
+            finished: false,
+        }
+    }
+
+    /// Return the number of body bytes written so far.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.buf.len() - self.start - self.prefix.width()
+    }
+
+    /// Return `true` if no body bytes were written yet.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Start a frame nested in the body of `self`. It must be finished before writing to `self`
+    /// again.
+    #[inline]
+    pub fn nested(&mut self, prefix: LengthPrefix) -> LengthPrefixedWriter<'_> {
+        LengthPrefixedWriter::new(self.buf, prefix)
+    }
+
+    /// Write the length of the body in the slot and return it.
+    ///
+    /// # Panics
+    ///
+    /// This panics if the length of the body is greater than [`LengthPrefix::max_len`], the
+    /// frame is discarded in this case.
+    pub fn finish(mut self) -> usize {
+        let len = self.len();
+
+        assert!(
+            len <= self.prefix.max_len(),
+            "frame too long: len ({}) > max len ({})",
+            len,
+            self.prefix.max_len()
+        );
+
+        let width = self.prefix.width();
+        let be = (len as u64).to_be_bytes();
+
+        self.buf.as_mut_slice()[self.start..self.start + width].copy_from_slice(&be[8 - width..]);
+        self.finished = true;
+        len
+    }
+}
+
+impl Finish for LengthPrefixedWriter<'_> {
+    type Output = usize;
+
+    #[inline]
+    fn finish(self) -> usize {
+        LengthPrefixedWriter::finish(self)
+    }
+}
+
+impl Drop for LengthPrefixedWriter<'_> {
+    fn drop(&mut self) {
+        if !self.finished {
+            // Safety: `start` is lower than the len of `buf` so its bytes are initialized
+            unsafe { self.buf.set_len(self.start) }
+        }
+    }
+}
+
+impl BufMut for LengthPrefixedWriter<'_> {
+    #[inline]
+    fn remaining_mut(&self) -> usize {
+        self.buf.remaining_mut()
+    }
+
+    #[inline]
+    fn chuncks_mut(&mut self) -> &mut UninitSlice {
+        self.buf.chuncks_mut()
+    }
+
+    #[inline]
+    unsafe fn advance(&mut self, count: usize) {
+        self.buf.advance(count)
+    }
+
+    #[inline]
+    fn put_slice(&mut self, src: &[u8]) {
+        self.buf.put_slice(src)
+    }
+
+    #[inline]
+    fn reserve_hint(&mut self, additional: usize) {
+        self.buf.reserve_hint(additional)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn nested() {
+        let mut buf = BytesMut::new();
+        buf.put_u8(0xff);
+
+        let mut outer = buf.length_prefixed(LengthPrefix::U32);
+        for tag in [1, 2] {
+            let mut tlv = outer.nested(LengthPrefix::U8);
+            tlv.put_u8(tag);
+            tlv.put_slice(&[tag; 3]);
+            assert_eq!(tlv.finish(), 4);
+        }
+
+        // Dropped without being finished, the frame is discarded
+        let mut tlv = outer.nested(LengthPrefix::U16);
+        tlv.put_slice(b"partial");
+        drop(tlv);
+
+        assert_eq!(outer.len(), 10);
+        assert_eq!(outer.finish(), 10);
+        assert_eq!(
+            buf.as_ref(),
+            b"\xff\x00\x00\x00\x0a\x04\x01\x01\x01\x01\x04\x02\x02\x02\x02"
+        );
+    }
+
+    #[test]
+    fn max_frame_len_saturates() {
+        let max = MaxFrameLen::new(u32::MAX as usize);
+
+        assert_eq!(
+            max.check(u32::MAX as u64 + 1, usize::MAX),
+            Err(FrameLenError::TooLarge {
+                len: 1 << 32,
+                max: u32::MAX as usize
+            })
+        );
+        assert_eq!(
+            max.check(u32::MAX as u64, 10),
+            Err(FrameLenError::Incomplete {
+                needed: u32::MAX as usize - 10
+            })
+        );
+
+        // Lengths which don't fit in a `usize` are never truncated
+        #[cfg(target_pointer_width = "32")]
+        assert_eq!(
+            MaxFrameLen::new(usize::MAX).check(1 << 32, usize::MAX),
+            Err(FrameLenError::TooLarge {
+                len: 1 << 32,
+                max: usize::MAX
+            })
+        );
+
+        let max = MaxFrameLen::new(16);
+        assert_eq!(max.check(16, 16), Ok(16));
+        assert_eq!(
+            max.check(17, usize::MAX),
+            Err(FrameLenError::TooLarge { len: 17, max: 16 })
+        );
+    }
+
+    #[test]
+    fn max_frame_len_decode() {
+        let max = MaxFrameLen::new(8);
+        let bytes = Bytes::from(b"\x00\x03abc\x00\x02d".to_vec());
+
+        // The prefix and the body span several chunks, peeking at the prefix doesn't copy the
+        // buffer
+        let mut buf = bytes.slice(..1).chain(bytes.slice(1..));
+        let (frame, count) =
+            crate::test_alloc::count_allocs(|| max.decode(&mut buf, LengthPrefix::U16));
+        assert_eq!(frame, Ok(Bytes::from_static(b"abc")));
+        assert_eq!(count, 0);
+        assert_eq!(
+            max.decode(&mut buf, LengthPrefix::U16),
+            Err(FrameLenError::Incomplete { needed: 1 })
+        );
+        assert_eq!(buf.remaining(), 3);
+
+        let mut buf = Bytes::from_static(b"\x00\x00\x00");
+        assert_eq!(
+            max.decode(&mut buf, LengthPrefix::U32),
+            Err(FrameLenError::Incomplete { needed: 1 })
+        );
+    }
+
+    #[test]
+    fn max_frame_len_decode_split_prefix() {
+        /// A third-party `Buf` yielding one byte per chunck
+        struct Bytewise(&'static [u8]);
+
+        impl Buf for Bytewise {
+            fn remaining(&self) -> usize {
+                self.0.len()
+            }
+
+            fn chuncks(&self) -> &[u8] {
+                &self.0[..self.0.len().min(1)]
+            }
+
+            fn advance(&mut self, cnt: usize) {
+                self.0 = &self.0[cnt..];
+            }
+
+            fn fork(&self) -> Bytes {
+                Bytes::from_static(self.0)
+            }
+        }
+
+        let max = MaxFrameLen::new(8);
+        let mut buf = Bytewise(b"\x00\x00\x00\x02hi\x00");
+
+        assert_eq!(
+            max.decode(&mut buf, LengthPrefix::U32),
+            Ok(Bytes::from_static(b"hi"))
+        );
+        assert_eq!(buf.remaining(), 1);
+        assert_eq!(
+            max.decode(&mut buf, LengthPrefix::U16),
+            Err(FrameLenError::Incomplete { needed: 1 })
+        );
+        assert_eq!(buf.remaining(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "frame too long")]
+    fn too_long() {
+        let mut buf = BytesMut::new();
+        let mut frame = buf.length_prefixed(LengthPrefix::U8);
+
+        frame.put_slice(&[0; 256]);
+        frame.finish();
+    }
+}
@@ -0,0 +1,189 @@
+//! JSON string escaping.
+//!
+//! The strings are escaped as described by RFC 8259: quotation marks, reverse solidi and control
+//! characters are escaped, everything else is written as is.
+//!
+//! # Example
+//!
+//! ```
+//! use std::fmt::Write;
+//!
+//! use bytes::codec::json::{self, JsonStringWriter};
+//! use bytes::BytesMut;
+//!
+//! let mut body = BytesMut::new();
+//! body.extend_from_slice(b"{\"msg\":\"");
+//! json::put_json_escaped_str(&mut body, "say \"hi\"\n");
+//! body.extend_from_slice(b"\",\"path\":");
+//!
+//! let mut writer = JsonStringWriter::new(body);
+//! write!(writer, "C:\\{}", "tmp").unwrap();
+//! let mut body = writer.finish();
+//! body.extend_from_slice(b"}");
+//!
+//! assert_eq!(body.as_ref(), br#"{"msg":"say \"hi\"\n","path":"C:\\tmp"}"#);
+//! ```
+
+use core::fmt;
+
+use crate::{BufMut, Finish};
+
+const HEX: &[u8; 16] = b"0123456789abcdef";
+
+/// Write `src` escaped as the content of a JSON string into `dst`. The surrounding quotation
+/// marks are not written.
+///
+/// # Panics
+///
+/// This panics if there is not enough space remaining in `dst`.
+pub fn put_json_escaped_str<M: BufMut>(dst: &mut M, src: &str) {
+    let bytes = src.as_bytes();
+    let mut start = 0;
+
+    for (i, &b) in bytes.iter().enumerate() {
+        let escaped: &[u8] = match b {
+            b'"' => b"\\\"",
+            b'\\' => b"\\\\",
+            b'\n' => b"\\n",
+            b'\r' => b"\\r",
+            b'\t' => b"\\t",
+            0x08 => b"\\b",
+            0x0c => b"\\f",
+            0x00..=0x1f => &[
+                b'\\',
+                b'u',
+                b'0',
+                b'0',
+                HEX[(b >> 4) as usize],
+                HEX[(b & 0xf) as usize],
+            ],
+            _ => continue,
+        };
+
+        // Write the run of bytes that don't need to be escaped at once
+        dst.put_slice(&bytes[start..i]);
+        dst.put_slice(escaped);
+        start = i + 1;
+    }
+
+    dst.put_slice(&bytes[start..]);
+}
+
+/// An adapter writing a JSON string into a `BufMut`, escaping its content on the fly.
+///
+/// The opening quotation mark is written on creation and the closing one by
+/// [`JsonStringWriter::finish`]. The content is written with [`JsonStringWriter::push_str`] or
+/// through `fmt::Write`, so formatted values are escaped without an intermediate `String`.
+///
+/// # Example
+///
+/// ```
+/// use std::fmt::Write;
+///
+/// use bytes::codec::json::JsonStringWriter;
+///
+/// let mut writer = JsonStringWriter::new(Vec::new());
+/// writer.push_str("tab\t");
+/// write!(writer, "{:?}", "quoted").unwrap();
+///
+/// assert_eq!(writer.finish(), br#""tab\t\"quoted\"""#);
+/// ```
+#[derive(Debug)]
+#[must_use = "the closing quotation mark is only written by `finish`"]
+pub struct JsonStringWriter<M: BufMut> {
+    inner: M,
+}
+
+impl<M: BufMut> JsonStringWriter<M> {
+    /// Create a new `JsonStringWriter` writing into `inner`.
+    ///
+    /// # Panics
+    ///
+    /// This panics if `inner` is full.
+    pub fn new(mut inner: M) -> JsonStringWriter<M> {
+        inner.put_u8(b'"');
+        JsonStringWriter { inner }
+    }
+
+    /// Write `src` escaped.
+    ///
+    /// # Panics
+    ///
+    /// This panics if there is not enough space remaining in the inner buffer.
+    #[inline]
+    pub fn push_str(&mut self, src: &str) {
+        put_json_escaped_str(&mut self.inner, src)
+    }
+
+    /// Return a reference to the inner buffer.
+    #[inline]
+    pub fn get_ref(&self) -> &M {
+        &self.inner
+    }
+
+    /// Write the closing quotation mark and return the inner buffer.
+    ///
+    /// # Panics
+    ///
+    /// This panics if the inner buffer is full.
+    pub fn finish(mut self) -> M {
+        self.inner.put_u8(b'"');
+        self.inner
+    }
+}
+
+impl<M: BufMut> Finish for JsonStringWriter<M> {
+    type Output = M;
+
+    #[inline]
+    fn finish(self) -> M {
+        JsonStringWriter::finish(self)
+    }
+}
+
+impl<M: BufMut> fmt::Write for JsonStringWriter<M> {
+    fn write_str(&mut self, src: &str) -> fmt::Result {
+        // Escaping a byte takes at most 6 bytes
+        if self.inner.remaining_mut() / 6 < src.len() {
+            return Err(fmt::Error);
+        }
+
+        self.push_str(src);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn escape(src: &str) -> Vec<u8> {
+        let mut dst = Vec::new();
+        put_json_escaped_str(&mut dst, src);
+        dst
+    }
+
+    #[test]
+    fn escapes() {
+        assert_eq!(escape(""), b"");
+        assert_eq!(escape("plain"), b"plain");
+        assert_eq!(escape("\"\\/"), br#"\"\\/"#);
+        assert_eq!(escape("\x08\x0c\n\r\t"), br#"\b\f\n\r\t"#);
+        assert_eq!(escape("\0\x1f\x7f"), b"\\u0000\\u001f\x7f");
+        assert_eq!(
+            escape("h\u{e9}llo \u{1f600}"),
+            "h\u{e9}llo \u{1f600}".as_bytes()
+        );
+    }
+
+    #[test]
+    fn writer_limit() {
+        use core::fmt::Write;
+
+        let mut writer = JsonStringWriter::new(Vec::new().limit(8));
+
+        assert!(writer.write_str("a").is_ok());
+        assert!(writer.write_str("bc").is_err());
+        assert_eq!(writer.get_ref().get_ref(), b"\"a");
+    }
+}
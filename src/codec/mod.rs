@@ -0,0 +1,33 @@
+//! Encoding and framing primitives working generically over [`Buf`](crate::Buf) and
+//! [`BufMut`](crate::BufMut).
+
+use core::fmt;
+
+pub mod cobs;
+pub mod crc32;
+pub mod delta;
+pub mod frame;
+pub mod json;
+pub mod rle;
+pub mod slip;
+
+/// The error returned when a decoder fails to decode its input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The input ended in the middle of an encoded value.
+    UnexpectedEof,
+    /// The input is not valid for the encoding.
+    Invalid,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::UnexpectedEof => write!(f, "unexpected end of input"),
+            DecodeError::Invalid => write!(f, "invalid input"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DecodeError {}
@@ -0,0 +1,124 @@
+//! Run-length encoding.
+//!
+//! The encoded form is a sequence of `(count, byte)` pairs where `count` is in `1..=255`.
+//!
+//! # Example
+//!
+//! ```
+//! use bytes::codec::rle;
+//!
+//! let mut encoded = Vec::new();
+//! rle::encode(&mut &b"aaaabccc"[..], &mut encoded);
+//!
+//! assert_eq!(encoded, [4, b'a', 1, b'b', 3, b'c']);
+//!
+//! let mut decoded = Vec::new();
+//! rle::decode(&mut &encoded[..], &mut decoded).unwrap();
+//!
+//! assert_eq!(decoded, b"aaaabccc");
+//! ```
+
+use super::DecodeError;
+use crate::{Buf, BufMut};
+
+/// Encode all the remaining bytes of `src` into `dst`.
+///
+/// # Panics
+///
+/// This panics if there is not enough space remaining in `dst`.
+pub fn encode<B: Buf, M: BufMut>(src: &mut B, dst: &mut M) {
+    let mut run: Option<(u8, u8)> = None;
+
+    while src.has_remaining() {
+        let chunck = src.chuncks();
+
+        for &b in chunck {
+            run = match run {
+                Some((byte, count)) if byte == b && count < u8::MAX => Some((byte, count + 1)),
+                Some((byte, count)) => {
+                    dst.put_slice(&[count, byte]);
+                    Some((b, 1))
+                }
+                None => Some((b, 1)),
+            };
+        }
+
+        let len = chunck.len();
+        src.advance(len);
+    }
+
+    if let Some((byte, count)) = run {
+        dst.put_slice(&[count, byte]);
+    }
+}
+
+/// Decode all the remaining bytes of `src` into `dst`.
+///
+/// # Errors
+///
+/// Returns [`DecodeError::Invalid`] if a run has a count of `0` and [`DecodeError::UnexpectedEof`]
+/// if `src` ends in the middle of a pair. The runs decoded before the error are written in `dst`.
+///
+/// # Panics
+///
+/// This panics if there is not enough space remaining in `dst`.
+pub fn decode<B: Buf, M: BufMut>(src: &mut B, dst: &mut M) -> Result<(), DecodeError> {
+    let mut chunck = [0u8; 64];
+
+    while src.has_remaining() {
+        if src.remaining() < 2 {
+            return Err(DecodeError::UnexpectedEof);
+        }
+
+        let count = src.get_u8();
+        let byte = src.get_u8();
+
+        if count == 0 {
+            return Err(DecodeError::Invalid);
+        }
+
+        let mut count = count as usize;
+        while count > 0 {
+            let n = count.min(chunck.len());
+            chunck[..n].fill(byte);
+            dst.put_slice(&chunck[..n]);
+            count -= n;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Bytes;
+
+    #[test]
+    fn long_runs_across_chuncks() {
+        let mut src = Bytes::from(vec![7u8; 300]).chain(Bytes::from(vec![7u8; 10]));
+        let mut encoded = Vec::new();
+
+        encode(&mut src, &mut encoded);
+        assert_eq!(encoded, [255, 7, 55, 7]);
+
+        let mut decoded = Vec::new();
+        decode(&mut &encoded[..], &mut decoded).unwrap();
+        assert_eq!(decoded, vec![7u8; 310]);
+    }
+
+    #[test]
+    fn decode_errors() {
+        let mut out = Vec::new();
+
+        assert_eq!(
+            decode(&mut &[2, 1, 3][..], &mut out),
+            Err(DecodeError::UnexpectedEof)
+        );
+        assert_eq!(out, [1, 1]);
+        assert_eq!(
+            decode(&mut &[0, 1][..], &mut out),
+            Err(DecodeError::Invalid)
+        );
+    }
+}
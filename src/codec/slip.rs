@@ -0,0 +1,144 @@
+//! Serial line internet protocol (SLIP) framing, as described by RFC 1055.
+//!
+//! A frame is followed by an `END` byte, the `END` and `ESC` bytes of its content are escaped
+//! with the two bytes sequences `ESC ESC_END` and `ESC ESC_ESC`.
+//!
+//! # Example
+//!
+//! ```
+//! use bytes::codec::slip;
+//!
+//! let mut encoded = Vec::new();
+//! slip::encode(&mut &b"a\xc0b"[..], &mut encoded);
+//!
+//! assert_eq!(encoded, b"a\xdb\xdcb\xc0");
+//!
+//! let mut decoded = Vec::new();
+//! slip::decode(&mut &encoded[..], &mut decoded).unwrap();
+//!
+//! assert_eq!(decoded, b"a\xc0b");
+//! ```
+
+use super::DecodeError;
+use crate::{Buf, BufMut};
+
+/// The frame delimiter.
+pub const END: u8 = 0xc0;
+/// The escape byte.
+pub const ESC: u8 = 0xdb;
+/// Follows `ESC` to encode an `END` byte of the content.
+pub const ESC_END: u8 = 0xdc;
+/// Follows `ESC` to encode an `ESC` byte of the content.
+pub const ESC_ESC: u8 = 0xdd;
+
+/// Encode all the remaining bytes of `src` as a single frame into `dst`, followed by `END`.
+///
+/// # Panics
+///
+/// This panics if there is not enough space remaining in `dst`.
+pub fn encode<B: Buf, M: BufMut>(src: &mut B, dst: &mut M) {
+    while src.has_remaining() {
+        let chunck = src.chuncks();
+        let mut start = 0;
+
+        for (i, &b) in chunck.iter().enumerate() {
+            let escaped = match b {
+                END => [ESC, ESC_END],
+                ESC => [ESC, ESC_ESC],
+                _ => continue,
+            };
+
+            // Write the run of bytes that don't need to be escaped at once
+            dst.put_slice(&chunck[start..i]);
+            dst.put_slice(&escaped);
+            start = i + 1;
+        }
+
+        dst.put_slice(&chunck[start..]);
+
+        let len = chunck.len();
+        src.advance(len);
+    }
+
+    dst.put_u8(END);
+}
+
+/// Decode a frame from `src` into `dst`. The bytes of `src` are consumed up to and including the
+/// `END` byte of the frame.
+///
+/// # Errors
+///
+/// Returns [`DecodeError::Invalid`] if `ESC` is followed by another byte than `ESC_END` or
+/// `ESC_ESC` and [`DecodeError::UnexpectedEof`] if `src` ends before `END`. The bytes decoded
+/// before the error are written in `dst`.
+///
+/// # Panics
+///
+/// This panics if there is not enough space remaining in `dst`.
+pub fn decode<B: Buf, M: BufMut>(src: &mut B, dst: &mut M) -> Result<(), DecodeError> {
+    while src.has_remaining() {
+        let chunck = src.chuncks();
+        let run = chunck.iter().position(|b| *b == END || *b == ESC);
+        let len = run.unwrap_or(chunck.len());
+
+        dst.put_slice(&chunck[..len]);
+        src.advance(len);
+
+        if run.is_none() {
+            continue;
+        }
+
+        match src.get_u8() {
+            END => return Ok(()),
+            _ if !src.has_remaining() => break,
+            _ => match src.get_u8() {
+                ESC_END => dst.put_u8(END),
+                ESC_ESC => dst.put_u8(ESC),
+                _ => return Err(DecodeError::Invalid),
+            },
+        }
+    }
+
+    Err(DecodeError::UnexpectedEof)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Bytes;
+
+    #[test]
+    fn round_trip() {
+        let mut src = Bytes::from_static(b"\xc0\xdbab\xdb").chain(&b"\xdc\xdd\xc0"[..]);
+        let mut encoded = Vec::new();
+        encode(&mut src, &mut encoded);
+
+        assert_eq!(encoded, b"\xdb\xdc\xdb\xddab\xdb\xdd\xdc\xdd\xdb\xdc\xc0");
+
+        // Split the escape sequences between the chuncks of the source
+        let mut src = Bytes::from(encoded[..5].to_vec()).chain(&encoded[5..]);
+        let mut decoded = Vec::new();
+        decode(&mut src, &mut decoded).unwrap();
+
+        assert_eq!(decoded, b"\xc0\xdbab\xdb\xdc\xdd\xc0");
+        assert!(!src.has_remaining());
+    }
+
+    #[test]
+    fn decode_errors() {
+        let mut out = Vec::new();
+
+        assert_eq!(
+            decode(&mut &b"ab"[..], &mut out),
+            Err(DecodeError::UnexpectedEof)
+        );
+        assert_eq!(
+            decode(&mut &b"a\xdb"[..], &mut out),
+            Err(DecodeError::UnexpectedEof)
+        );
+        assert_eq!(
+            decode(&mut &b"\xdbx\xc0"[..], &mut out),
+            Err(DecodeError::Invalid)
+        );
+    }
+}
@@ -0,0 +1,270 @@
+//! Method names compatible with the `bytes` crate of the tokio project.
+//!
+//! The [`Buf`] and [`BufMut`] traits of this module are implemented for every
+//! [`crate::Buf`] and [`crate::BufMut`] and expose their methods under the upstream names and
+//! signatures (`chunk`, `chunk_mut`, `advance_mut`, `copy_to_bytes`, `get_u16`, `put_u32_le`,
+//! ...). Code written against the upstream crate can be migrated by importing these traits
+//! instead of the upstream ones, then moved to the native names at its own pace.
+//!
+//! The traits of this module and the native traits declare methods with the same names, so
+//! only one of them should be imported in a given scope to avoid ambiguous calls. New buffers
+//! must implement the native traits, the compatible ones can't be implemented directly.
+//!
+//! # Example
+//!
+//! ```
+//! use bytes::compat::{Buf, BufMut};
+//!
+//! let mut dst = Vec::new();
+//! dst.put_u16(0xcafe);
+//! dst.put_u32_le(42);
+//!
+//! let mut src = &dst[..];
+//! assert_eq!(src.chunk(), [0xca, 0xfe, 42, 0, 0, 0]);
+//! assert_eq!(src.get_u16(), 0xcafe);
+//! assert_eq!(src.copy_to_bytes(4), [42, 0, 0, 0][..]);
+//! assert!(!src.has_remaining());
+//! ```
+
+use crate::{Bytes, UninitSlice};
+
+macro_rules! get_impl {
+    ($($(#[$doc:meta])* $name:ident => $ty:ty, $from:ident;)*) => {
+        $(
+            $(#[$doc])*
+            ///
+            /// # Panics
+            ///
+            /// This panics if there is not enough remaining bytes in `self`.
+            #[inline]
+            fn $name(&mut self) -> $ty {
+                let mut buf = [0; core::mem::size_of::<$ty>()];
+                crate::Buf::copy_to_slice(self, &mut buf);
+                <$ty>::$from(buf)
+            }
+        )*
+    };
+}
+
+macro_rules! put_impl {
+    ($($(#[$doc:meta])* $name:ident => $ty:ty, $to:ident;)*) => {
+        $(
+            $(#[$doc])*
+            ///
+            /// # Panics
+            ///
+            /// This panics if there is not enough remaining space in `self`.
+            #[inline]
+            fn $name(&mut self, n: $ty) {
+                crate::BufMut::put_slice(self, &n.$to())
+            }
+        )*
+    };
+}
+
+/// The upstream names of the [`crate::Buf`] methods.
+pub trait Buf: crate::Buf {
+    /// See [`crate::Buf::remaining`].
+    #[inline]
+    fn remaining(&self) -> usize {
+        crate::Buf::remaining(self)
+    }
+
+    /// See [`crate::Buf::chuncks`].
+    #[inline]
+    fn chunk(&self) -> &[u8] {
+        crate::Buf::chuncks(self)
+    }
+
+    /// See [`crate::Buf::advance`].
+    #[inline]
+    fn advance(&mut self, cnt: usize) {
+        crate::Buf::advance(self, cnt)
+    }
+
+    /// See [`crate::Buf::has_remaining`].
+    #[inline]
+    fn has_remaining(&self) -> bool {
+        crate::Buf::has_remaining(self)
+    }
+
+    /// See [`crate::Buf::copy_to_slice`].
+    #[inline]
+    fn copy_to_slice(&mut self, dst: &mut [u8]) {
+        crate::Buf::copy_to_slice(self, dst)
+    }
+
+    /// See [`crate::Buf::copy_to_bytes`].
+    #[inline]
+    fn copy_to_bytes(&mut self, len: usize) -> Bytes {
+        crate::Buf::copy_to_bytes(self, len)
+    }
+
+    get_impl! {
+        /// Read an unsigned 8 bit integer.
+        get_u8 => u8, from_be_bytes;
+        /// Read a signed 8 bit integer.
+        get_i8 => i8, from_be_bytes;
+        /// Read an unsigned 16 bit integer in big-endian byte order.
+        get_u16 => u16, from_be_bytes;
+        /// Read an unsigned 16 bit integer in little-endian byte order.
+        get_u16_le => u16, from_le_bytes;
+        /// Read a signed 16 bit integer in big-endian byte order.
+        get_i16 => i16, from_be_bytes;
+        /// Read a signed 16 bit integer in little-endian byte order.
+        get_i16_le => i16, from_le_bytes;
+        /// Read an unsigned 32 bit integer in big-endian byte order.
+        get_u32 => u32, from_be_bytes;
+        /// Read an unsigned 32 bit integer in little-endian byte order.
+        get_u32_le => u32, from_le_bytes;
+        /// Read a signed 32 bit integer in big-endian byte order.
+        get_i32 => i32, from_be_bytes;
+        /// Read a signed 32 bit integer in little-endian byte order.
+        get_i32_le => i32, from_le_bytes;
+        /// Read an unsigned 64 bit integer in big-endian byte order.
+        get_u64 => u64, from_be_bytes;
+        /// Read an unsigned 64 bit integer in little-endian byte order.
+        get_u64_le => u64, from_le_bytes;
+        /// Read a signed 64 bit integer in big-endian byte order.
+        get_i64 => i64, from_be_bytes;
+        /// Read a signed 64 bit integer in little-endian byte order.
+        get_i64_le => i64, from_le_bytes;
+        /// Read an IEEE754 single-precision float in big-endian byte order.
+        get_f32 => f32, from_be_bytes;
+        /// Read an IEEE754 single-precision float in little-endian byte order.
+        get_f32_le => f32, from_le_bytes;
+        /// Read an IEEE754 double-precision float in big-endian byte order.
+        get_f64 => f64, from_be_bytes;
+        /// Read an IEEE754 double-precision float in little-endian byte order.
+        get_f64_le => f64, from_le_bytes;
+    }
+}
+
+impl<T: crate::Buf + ?Sized> Buf for T {}
+
+/// The upstream names of the [`crate::BufMut`] methods.
+pub trait BufMut: crate::BufMut {
+    /// See [`crate::BufMut::remaining_mut`].
+    #[inline]
+    fn remaining_mut(&self) -> usize {
+        crate::BufMut::remaining_mut(self)
+    }
+
+    /// See [`crate::BufMut::has_remaining_mut`].
+    #[inline]
+    fn has_remaining_mut(&self) -> bool {
+        crate::BufMut::has_remaining_mut(self)
+    }
+
+    /// See [`crate::BufMut::chuncks_mut`].
+    #[inline]
+    fn chunk_mut(&mut self) -> &mut UninitSlice {
+        crate::BufMut::chuncks_mut(self)
+    }
+
+    /// See [`crate::BufMut::advance`].
+    ///
+    /// # Safety
+    ///
+    /// The next `cnt` bytes of the buffer must be initialized.
+    #[inline]
+    unsafe fn advance_mut(&mut self, cnt: usize) {
+        crate::BufMut::advance(self, cnt)
+    }
+
+    /// See [`crate::BufMut::put`].
+    #[inline]
+    fn put<B: crate::Buf>(&mut self, src: B)
+    where
+        Self: Sized,
+    {
+        crate::BufMut::put(self, src)
+    }
+
+    /// See [`crate::BufMut::put_slice`].
+    #[inline]
+    fn put_slice(&mut self, src: &[u8]) {
+        crate::BufMut::put_slice(self, src)
+    }
+
+    /// Write `cnt` times the byte `val`.
+    ///
+    /// # Panics
+    ///
+    /// This panics if there is not enough remaining space in `self`.
+    fn put_bytes(&mut self, val: u8, mut cnt: usize) {
+        let buf = [val; 64];
+
+        while cnt > 0 {
+            let n = cnt.min(buf.len());
+            crate::BufMut::put_slice(self, &buf[..n]);
+            cnt -= n;
+        }
+    }
+
+    put_impl! {
+        /// Write an unsigned 8 bit integer.
+        put_u8 => u8, to_be_bytes;
+        /// Write a signed 8 bit integer.
+        put_i8 => i8, to_be_bytes;
+        /// Write an unsigned 16 bit integer in big-endian byte order.
+        put_u16 => u16, to_be_bytes;
+        /// Write an unsigned 16 bit integer in little-endian byte order.
+        put_u16_le => u16, to_le_bytes;
+        /// Write a signed 16 bit integer in big-endian byte order.
+        put_i16 => i16, to_be_bytes;
+        /// Write a signed 16 bit integer in little-endian byte order.
+        put_i16_le => i16, to_le_bytes;
+        /// Write an unsigned 32 bit integer in big-endian byte order.
+        put_u32 => u32, to_be_bytes;
+        /// Write an unsigned 32 bit integer in little-endian byte order.
+        put_u32_le => u32, to_le_bytes;
+        /// Write a signed 32 bit integer in big-endian byte order.
+        put_i32 => i32, to_be_bytes;
+        /// Write a signed 32 bit integer in little-endian byte order.
+        put_i32_le => i32, to_le_bytes;
+        /// Write an unsigned 64 bit integer in big-endian byte order.
+        put_u64 => u64, to_be_bytes;
+        /// Write an unsigned 64 bit integer in little-endian byte order.
+        put_u64_le => u64, to_le_bytes;
+        /// Write a signed 64 bit integer in big-endian byte order.
+        put_i64 => i64, to_be_bytes;
+        /// Write a signed 64 bit integer in little-endian byte order.
+        put_i64_le => i64, to_le_bytes;
+        /// Write an IEEE754 single-precision float in big-endian byte order.
+        put_f32 => f32, to_be_bytes;
+        /// Write an IEEE754 single-precision float in little-endian byte order.
+        put_f32_le => f32, to_le_bytes;
+        /// Write an IEEE754 double-precision float in big-endian byte order.
+        put_f64 => f64, to_be_bytes;
+        /// Write an IEEE754 double-precision float in little-endian byte order.
+        put_f64_le => f64, to_le_bytes;
+    }
+}
+
+impl<T: crate::BufMut + ?Sized> BufMut for T {}
+
+#[cfg(test)]
+mod test {
+    use super::{Buf, BufMut};
+
+    #[test]
+    fn round_trip() {
+        let mut dst = Vec::new();
+
+        dst.put_i8(-1);
+        dst.put_u64(u64::MAX - 1);
+        dst.put_i32_le(-42);
+        dst.put_f64_le(1.5);
+        dst.put_bytes(0xaa, 100);
+
+        let mut src = &dst[..];
+
+        assert_eq!(src.get_i8(), -1);
+        assert_eq!(src.get_u64(), u64::MAX - 1);
+        assert_eq!(src.get_i32_le(), -42);
+        assert_eq!(src.get_f64_le(), 1.5);
+        assert_eq!(src.copy_to_bytes(100), [0xaa; 100][..]);
+        assert_eq!(src.remaining(), 0);
+    }
+}
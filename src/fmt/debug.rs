@@ -1,7 +1,7 @@
 use core::fmt::Debug;
 
 use super::BytesFmt;
-use crate::Bytes;
+use crate::{Bytes, BytesMut, BytesRef, MaybeOwnedBytes, RcBytes, RcBytesMut};
 
 impl<'a> Debug for BytesFmt<'a> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -30,6 +30,36 @@ impl Debug for Bytes {
     }
 }
 
+impl Debug for BytesMut {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(&BytesFmt(self.as_ref()), f)
+    }
+}
+
+impl Debug for RcBytes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(&BytesFmt(self.as_slice()), f)
+    }
+}
+
+impl Debug for RcBytesMut {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(&BytesFmt(self.as_ref()), f)
+    }
+}
+
+impl Debug for BytesRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(&BytesFmt(self.as_slice()), f)
+    }
+}
+
+impl Debug for MaybeOwnedBytes<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(&BytesFmt(self.as_ref()), f)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -64,6 +94,10 @@ mod test {
             \\xf8\\xf9\\xfa\\xfb\\xfc\\xfd\\xfe\\xff\
             \"";
 
+        let mut bytes_mut = BytesMut::new();
+        bytes_mut.extend_from_slice(&vec);
+        assert_eq!(expected, format!("{:?}", bytes_mut));
+
         let bytes = Bytes::from(vec);
 
         assert_eq!(expected, format!("{:?}", bytes));
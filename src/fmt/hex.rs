@@ -1,25 +1,35 @@
 use core::fmt::{LowerHex, UpperHex};
 
 use super::BytesFmt;
-use crate::Bytes;
+use crate::{simd, Bytes};
 
-impl<'a> LowerHex for BytesFmt<'a> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for b in self.0 {
-            write!(f, "{:2x}", b)?;
+impl BytesFmt<'_> {
+    /// Write the hexadecimal representation of the bytes, by blocks to avoid a call to
+    /// `write_str` for each byte.
+    fn fmt_hex(&self, f: &mut std::fmt::Formatter<'_>, upper: bool) -> std::fmt::Result {
+        let mut buf = [0; 128];
+
+        for chunck in self.0.chunks(buf.len() / 2) {
+            let out = &mut buf[..2 * chunck.len()];
+
+            simd::hex_encode(chunck, out, upper);
+            // The output only contains ASCII hex digits
+            f.write_str(unsafe { core::str::from_utf8_unchecked(out) })?;
         }
 
         Ok(())
     }
 }
 
-impl<'a> UpperHex for BytesFmt<'a> {
+impl LowerHex for BytesFmt<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for b in self.0 {
-            write!(f, "{:2X}", b)?;
-        }
+        self.fmt_hex(f, false)
+    }
+}
 
-        Ok(())
+impl UpperHex for BytesFmt<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.fmt_hex(f, true)
     }
 }
 
@@ -40,3 +50,16 @@ hex_impl!(
     LowerHex => Bytes,
     UpperHex => Bytes
 );
+
+#[cfg(test)]
+mod test {
+    use crate::Bytes;
+
+    #[test]
+    fn zero_padded() {
+        let bytes = Bytes::from_static(&[0x0a, 0xbc, 0x01]);
+
+        assert_eq!(format!("{:x}", bytes), "0abc01");
+        assert_eq!(format!("{:X}", bytes), "0ABC01");
+    }
+}
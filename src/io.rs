@@ -0,0 +1,82 @@
+//! Conversions of `Bytes` into the vectored buffers of `std::io`, enabled by the `std` feature.
+
+use std::io::IoSlice;
+
+use crate::Bytes;
+
+impl Bytes {
+    /// Return an `IoSlice` viewing the bytes of `self`, to pass them to a vectored write.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::io::Write;
+    ///
+    /// use bytes::Bytes;
+    ///
+    /// let bytes = Bytes::from_static(b"hello");
+    /// let mut out = Vec::new();
+    ///
+    /// out.write_vectored(&[bytes.as_io_slice()]).unwrap();
+    /// assert_eq!(out, b"hello");
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn as_io_slice(&self) -> IoSlice<'_> {
+        IoSlice::new(self.as_slice())
+    }
+
+    /// Return an array of `IoSlice` viewing each of `bufs`, e.g. the header and the body of a
+    /// message written with a single vectored write.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::io::Write;
+    ///
+    /// use bytes::Bytes;
+    ///
+    /// let message = [Bytes::from_static(b"HTTP/1.1 200 OK\r\n\r\n"), Bytes::from_static(b"ok")];
+    /// let mut out = Vec::new();
+    ///
+    /// out.write_vectored(&Bytes::io_slices(&message)).unwrap();
+    /// assert_eq!(out, b"HTTP/1.1 200 OK\r\n\r\nok");
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn io_slices<const N: usize>(bufs: &[Bytes; N]) -> [IoSlice<'_>; N] {
+        bufs.each_ref().map(Bytes::as_io_slice)
+    }
+}
+
+impl<'a> From<&'a Bytes> for IoSlice<'a> {
+    #[inline]
+    fn from(value: &'a Bytes) -> IoSlice<'a> {
+        value.as_io_slice()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::{IoSlice, Write};
+
+    use crate::Bytes;
+
+    #[test]
+    fn write_vectored() {
+        let head = Bytes::from(b"head|".to_vec());
+        let body = Bytes::from_static(b"body");
+
+        let bufs = [head.clone(), Bytes::new(), body.clone()];
+        let slices = Bytes::io_slices(&bufs);
+        assert_eq!(slices[0].as_ptr(), head.as_ptr());
+        assert!(slices[1].is_empty());
+
+        let mut out = Vec::new();
+        let written = out
+            .write_vectored(&[IoSlice::from(&head), IoSlice::from(&body)])
+            .unwrap();
+        assert_eq!(written, 9);
+        assert_eq!(out, b"head|body");
+    }
+}
@@ -0,0 +1,164 @@
+use core::iter::FusedIterator;
+use core::mem;
+
+use crate::iter::Lines;
+use crate::ByteStr;
+
+/// An iterator over the substrings of a `ByteStr` separated by whitespace, created by
+/// [`ByteStr::split_whitespace`].
+#[derive(Debug, Clone)]
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct SplitWhitespace {
+    rest: ByteStr,
+}
+
+impl SplitWhitespace {
+    #[inline]
+    pub(crate) fn new(s: ByteStr) -> SplitWhitespace {
+        SplitWhitespace { rest: s }
+    }
+}
+
+impl Iterator for SplitWhitespace {
+    type Item = ByteStr;
+
+    fn next(&mut self) -> Option<ByteStr> {
+        let rest = self.rest.as_str();
+        let start = rest.len() - rest.trim_start().len();
+
+        if start == rest.len() {
+            self.rest = ByteStr::new();
+            return None;
+        }
+
+        let end = rest[start..]
+            .find(char::is_whitespace)
+            .map_or(rest.len(), |len| start + len);
+
+        let word = self.rest.slice(start..end);
+        self.rest = self.rest.slice(end..);
+        Some(word)
+    }
+}
+
+impl FusedIterator for SplitWhitespace {}
+
+/// An iterator over the lines of a `ByteStr`, created by [`ByteStr::lines`].
+#[derive(Debug, Clone)]
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct ByteStrLines {
+    inner: Lines,
+}
+
+impl ByteStrLines {
+    #[inline]
+    pub(crate) fn new(s: ByteStr) -> ByteStrLines {
+        ByteStrLines {
+            inner: s.into_bytes().lines(),
+        }
+    }
+}
+
+impl Iterator for ByteStrLines {
+    type Item = ByteStr;
+
+    #[inline]
+    fn next(&mut self) -> Option<ByteStr> {
+        // SAFETY:
+        // The lines are split before an ASCII `\r` or `\n`, which are never part of a multi-bytes
+        // char, so they are made of valid utf8 like the `ByteStr` they come from
+        self.inner
+            .next()
+            .map(|line| unsafe { ByteStr::from_shared_unchecked(line) })
+    }
+}
+
+impl FusedIterator for ByteStrLines {}
+
+/// An iterator over the fields of a `ByteStr` separated by a char, created by
+/// [`ByteStr::fields`].
+#[derive(Debug, Clone)]
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct Fields {
+    rest: ByteStr,
+    separator: char,
+    finished: bool,
+}
+
+impl Fields {
+    #[inline]
+    pub(crate) fn new(s: ByteStr, separator: char) -> Fields {
+        Fields {
+            rest: s,
+            separator,
+            finished: false,
+        }
+    }
+
+    /// Return the substring not yielded yet, the separator preceding it excluded.
+    #[inline]
+    pub fn remainder(&self) -> &ByteStr {
+        &self.rest
+    }
+}
+
+impl Iterator for Fields {
+    type Item = ByteStr;
+
+    fn next(&mut self) -> Option<ByteStr> {
+        if self.finished {
+            return None;
+        }
+
+        match self.rest.find(self.separator) {
+            Some(pos) => {
+                let field = self.rest.slice(..pos);
+                self.rest = self.rest.slice(pos + self.separator.len_utf8()..);
+                Some(field)
+            }
+            None => {
+                self.finished = true;
+                Some(mem::take(&mut self.rest))
+            }
+        }
+    }
+}
+
+impl FusedIterator for Fields {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Bytes;
+
+    #[test]
+    fn split_whitespace() {
+        let s =
+            ByteStr::try_from(Bytes::from("\t  NICK\u{a0}café \r\n".as_bytes().to_vec())).unwrap();
+
+        let words: Vec<_> = s.split_whitespace().collect();
+        assert_eq!(words, ["NICK", "café"]);
+        assert_eq!(words[0].as_ptr(), s[3..].as_ptr());
+
+        assert_eq!(ByteStr::from_static(" \n ").split_whitespace().count(), 0);
+    }
+
+    #[test]
+    fn lines() {
+        let s = ByteStr::from_static("é\r\n\nà");
+
+        assert_eq!(s.lines().collect::<Vec<_>>(), ["é", "", "à"]);
+    }
+
+    #[test]
+    fn fields() {
+        let s = ByteStr::from_static("a→b→→c");
+        let mut fields = s.fields('→');
+
+        assert_eq!(fields.next().unwrap(), "a");
+        assert_eq!(fields.remainder(), "b→→c");
+        assert_eq!(fields.collect::<Vec<_>>(), ["b", "", "c"]);
+
+        assert_eq!(ByteStr::new().fields(',').collect::<Vec<_>>(), [""]);
+    }
+}
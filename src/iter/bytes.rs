@@ -3,7 +3,7 @@ use crate::Bytes;
 /// # Invariant
 ///
 /// * `self.ptr` is always a valid pointer to a slice of bytes of len at least
-/// `self.len`.
+///   `self.len`.
 /// * `self.pos < self.len`
 pub struct BytesIter {
     ptr: *const u8,
@@ -146,6 +146,49 @@ impl BytesIter {
         }
     }
 
+    /// Peek the next `N` bytes as an array. If less than `N` bytes remain `Option::None` is
+    /// returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// let b = Bytes::from_static(b"\x7fELF\x02\x01");
+    /// let iter = b.into_iter();
+    ///
+    /// assert_eq!(iter.get_n::<4>(), Some(b"\x7fELF"));
+    /// assert_eq!(iter.get_n::<7>(), None);
+    /// ```
+    #[inline]
+    pub fn get_n<const N: usize>(&self) -> Option<&[u8; N]> {
+        self._b[self.pos..].first_chunk()
+    }
+
+    /// Take the next `N` bytes as an array. If less than `N` bytes remain `Option::None` is
+    /// returned and the position is left unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// let b = Bytes::from_static(b"\x7fELF\x02\x01");
+    /// let mut iter = b.into_iter();
+    ///
+    /// assert_eq!(iter.next_array(), Some(b"\x7fELF"));
+    ///
+    /// let [class, endianness] = *iter.next_array().unwrap();
+    /// assert_eq!((class, endianness), (2, 1));
+    /// assert!(iter.is_empty());
+    /// ```
+    #[inline]
+    pub fn next_array<const N: usize>(&mut self) -> Option<&[u8; N]> {
+        let array = self._b[self.pos..].first_chunk()?;
+        self.pos += N;
+        Some(array)
+    }
+
     /// Advance the position cursor of `n`.
     ///
     /// # Safety
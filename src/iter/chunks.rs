@@ -0,0 +1,96 @@
+use core::cmp;
+use core::iter::FusedIterator;
+
+use crate::Bytes;
+
+/// An iterator over `size` bytes chunks of a `Bytes`, created by [`Bytes::chunks`].
+#[derive(Debug, Clone)]
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct Chunks {
+    bytes: Bytes,
+    size: usize,
+}
+
+impl Chunks {
+    #[inline]
+    pub(crate) fn new(bytes: Bytes, size: usize) -> Chunks {
+        assert!(size != 0, "chunk size must be non-zero");
+
+        Chunks { bytes, size }
+    }
+
+    /// Split `self` into the iterators over the first `index` chunks and over the rest.
+    #[cfg(feature = "rayon")]
+    pub(crate) fn split_at_chunk(mut self, index: usize) -> (Chunks, Chunks) {
+        let at = cmp::min(index.saturating_mul(self.size), self.bytes.len());
+        let right = self.bytes.split_off(at);
+
+        let size = self.size;
+        (self, Chunks { bytes: right, size })
+    }
+}
+
+impl Iterator for Chunks {
+    type Item = Bytes;
+
+    fn next(&mut self) -> Option<Bytes> {
+        if self.bytes.is_empty() {
+            return None;
+        }
+
+        let len = cmp::min(self.size, self.bytes.len());
+        Some(self.bytes.split_to(len))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.bytes.len().div_ceil(self.size);
+        (len, Some(len))
+    }
+}
+
+impl DoubleEndedIterator for Chunks {
+    fn next_back(&mut self) -> Option<Bytes> {
+        if self.bytes.is_empty() {
+            return None;
+        }
+
+        let len = match self.bytes.len() % self.size {
+            0 => self.size,
+            rem => rem,
+        };
+        Some(self.bytes.split_off(self.bytes.len() - len))
+    }
+}
+
+impl ExactSizeIterator for Chunks {}
+
+impl FusedIterator for Chunks {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn chunks() {
+        let bytes = Bytes::from(b"abcdefgh".to_vec());
+
+        let mut chunks = bytes.chunks(3);
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks.next_back().unwrap(), "gh");
+
+        let first = chunks.next().unwrap();
+        assert_eq!(first, "abc");
+        assert_eq!(first.as_ptr(), bytes.as_ptr());
+        assert_eq!(chunks.collect::<Vec<_>>(), ["def"]);
+
+        assert_eq!(bytes.chunks(4).collect::<Vec<_>>(), ["abcd", "efgh"]);
+        assert_eq!(Bytes::new().chunks(4).count(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "chunk size must be non-zero")]
+    fn zero_size() {
+        let _ = Bytes::new().chunks(0);
+    }
+}
@@ -0,0 +1,81 @@
+use core::iter::FusedIterator;
+use core::mem;
+
+use crate::{simd, Buf, Bytes};
+
+/// An iterator over the lines of a `Bytes`, created by [`Bytes::lines`].
+#[derive(Debug, Clone)]
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct Lines {
+    rest: Bytes,
+}
+
+impl Lines {
+    #[inline]
+    pub(crate) fn new(bytes: Bytes) -> Lines {
+        Lines { rest: bytes }
+    }
+
+    /// Return the bytes not yielded yet.
+    #[inline]
+    pub fn remainder(&self) -> &Bytes {
+        &self.rest
+    }
+}
+
+impl Iterator for Lines {
+    type Item = Bytes;
+
+    fn next(&mut self) -> Option<Bytes> {
+        if self.rest.is_empty() {
+            return None;
+        }
+
+        let mut line = match simd::memchr(b'\n', &self.rest) {
+            Some(pos) => {
+                let line = self.rest.split_to(pos);
+                self.rest.advance(1);
+                line
+            }
+            None => mem::take(&mut self.rest),
+        };
+
+        if line.last() == Some(b'\r') {
+            line.truncate(line.len() - 1);
+        }
+        Some(line)
+    }
+}
+
+impl FusedIterator for Lines {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn lines(bytes: &'static [u8]) -> Vec<Bytes> {
+        Bytes::from_static(bytes).lines().collect()
+    }
+
+    #[test]
+    fn lines_endings() {
+        assert_eq!(lines(b"a\nb\r\n\nc"), ["a", "b", "", "c"]);
+        assert_eq!(lines(b"a\r\n"), ["a"]);
+        assert_eq!(lines(b"\n"), [""]);
+        assert_eq!(lines(b"a\rb\r"), ["a\rb"]);
+        assert!(lines(b"").is_empty());
+    }
+
+    #[test]
+    fn shared() {
+        let bytes = Bytes::from(b"GET / HTTP/1.1\r\nHost: a\r\n".to_vec());
+        let mut lines = bytes.lines();
+
+        assert_eq!(lines.next().unwrap(), "GET / HTTP/1.1");
+        assert_eq!(lines.remainder(), "Host: a\r\n");
+
+        let host = lines.next().unwrap();
+        assert_eq!(host.as_ptr(), bytes[16..].as_ptr());
+        assert_eq!(lines.next(), None);
+    }
+}
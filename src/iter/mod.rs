@@ -1,3 +1,15 @@
+mod byte_str;
 mod bytes;
+mod chunks;
+mod lines;
+mod split;
+mod utf8_chunks;
+mod windows;
 
+pub use byte_str::{ByteStrLines, Fields, SplitWhitespace};
 pub use bytes::BytesIter;
+pub use chunks::Chunks;
+pub use lines::Lines;
+pub use split::{Delimiter, Split};
+pub use utf8_chunks::{Utf8Chunk, Utf8Chunks};
+pub use windows::Windows;
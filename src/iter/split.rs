@@ -0,0 +1,156 @@
+use core::iter::FusedIterator;
+use core::mem;
+
+use crate::{simd, Buf, Bytes};
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// A delimiter of the segments yielded by [`Bytes::split`], either a single byte or a sequence
+/// of bytes.
+///
+/// This trait is sealed and cannot be implemented outside of the crate.
+pub trait Delimiter: sealed::Sealed {
+    /// Return the position and the len of the first occurrence of `self` in `haystack`.
+    #[doc(hidden)]
+    fn find_in(&self, haystack: &[u8]) -> Option<(usize, usize)>;
+
+    /// Return the len of the longest sequence matched by `self`.
+    #[doc(hidden)]
+    fn delimiter_len(&self) -> usize;
+}
+
+impl sealed::Sealed for u8 {}
+
+impl Delimiter for u8 {
+    #[inline]
+    fn find_in(&self, haystack: &[u8]) -> Option<(usize, usize)> {
+        simd::memchr(*self, haystack).map(|pos| (pos, 1))
+    }
+
+    #[inline]
+    fn delimiter_len(&self) -> usize {
+        1
+    }
+}
+
+impl sealed::Sealed for &[u8] {}
+
+impl Delimiter for &[u8] {
+    #[inline]
+    fn find_in(&self, haystack: &[u8]) -> Option<(usize, usize)> {
+        // An empty delimiter would match everywhere without consuming anything
+        if self.is_empty() {
+            return None;
+        }
+
+        simd::find(self, haystack).map(|pos| (pos, self.len()))
+    }
+
+    #[inline]
+    fn delimiter_len(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<const N: usize> sealed::Sealed for &[u8; N] {}
+
+impl<const N: usize> Delimiter for &[u8; N] {
+    #[inline]
+    fn find_in(&self, haystack: &[u8]) -> Option<(usize, usize)> {
+        (&self[..]).find_in(haystack)
+    }
+
+    #[inline]
+    fn delimiter_len(&self) -> usize {
+        N
+    }
+}
+
+/// An iterator over the segments of a `Bytes` separated by a delimiter, created by
+/// [`Bytes::split`].
+#[derive(Debug, Clone)]
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct Split<D> {
+    rest: Bytes,
+    delimiter: D,
+    finished: bool,
+}
+
+impl<D: Delimiter> Split<D> {
+    #[inline]
+    pub(crate) fn new(bytes: Bytes, delimiter: D) -> Split<D> {
+        Split {
+            rest: bytes,
+            delimiter,
+            finished: false,
+        }
+    }
+
+    /// Return the bytes not yielded yet, the delimiter preceding them excluded.
+    #[inline]
+    pub fn remainder(&self) -> &Bytes {
+        &self.rest
+    }
+}
+
+impl<D: Delimiter> Iterator for Split<D> {
+    type Item = Bytes;
+
+    fn next(&mut self) -> Option<Bytes> {
+        if self.finished {
+            return None;
+        }
+
+        match self.delimiter.find_in(&self.rest) {
+            Some((pos, len)) => {
+                let segment = self.rest.split_to(pos);
+                self.rest.advance(len);
+                Some(segment)
+            }
+            None => {
+                self.finished = true;
+                Some(mem::take(&mut self.rest))
+            }
+        }
+    }
+}
+
+impl<D: Delimiter> FusedIterator for Split<D> {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn segments<D: Delimiter>(bytes: &Bytes, delimiter: D) -> Vec<Bytes> {
+        bytes.split(delimiter).collect()
+    }
+
+    #[test]
+    fn split() {
+        let bytes = Bytes::from(b"a,b,,c,".to_vec());
+
+        let fields = segments(&bytes, b',');
+        assert_eq!(fields, ["a", "b", "", "c", ""]);
+        assert_eq!(fields[1].as_ptr(), bytes[2..].as_ptr());
+
+        let headers = Bytes::from_static(b"Host: a\r\nAccept: */*\r\n\r\n");
+        assert_eq!(
+            segments(&headers, b"\r\n"),
+            ["Host: a", "Accept: */*", "", ""]
+        );
+
+        assert_eq!(segments(&Bytes::new(), b','), [""]);
+        assert_eq!(segments(&bytes, &b""[..]), ["a,b,,c,"]);
+    }
+
+    #[test]
+    fn remainder() {
+        let bytes = Bytes::from_static(b"GET / HTTP/1.1");
+        let mut split = bytes.split(b' ');
+
+        assert_eq!(split.next().unwrap(), "GET");
+        assert_eq!(split.remainder(), "/ HTTP/1.1");
+    }
+}
@@ -0,0 +1,99 @@
+use core::iter::FusedIterator;
+
+use crate::{ByteStr, Bytes};
+
+/// An iterator over the chunks of valid utf8 and of invalid bytes of a `Bytes`, created by
+/// [`Bytes::utf8_chunks`].
+#[derive(Debug, Clone)]
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct Utf8Chunks {
+    rest: Bytes,
+}
+
+impl Utf8Chunks {
+    #[inline]
+    pub(crate) fn new(bytes: Bytes) -> Utf8Chunks {
+        Utf8Chunks { rest: bytes }
+    }
+}
+
+impl Iterator for Utf8Chunks {
+    type Item = Utf8Chunk;
+
+    fn next(&mut self) -> Option<Utf8Chunk> {
+        let chunk = self.rest.as_slice().utf8_chunks().next()?;
+        let (valid_len, invalid_len) = (chunk.valid().len(), chunk.invalid().len());
+
+        // SAFETY:
+        // The first `valid_len` bytes were checked to be valid utf8
+        let valid = unsafe { ByteStr::from_shared_unchecked(self.rest.split_to(valid_len)) };
+        let invalid = self.rest.split_to(invalid_len);
+
+        Some(Utf8Chunk { valid, invalid })
+    }
+}
+
+impl FusedIterator for Utf8Chunks {}
+
+/// A chunk of a `Bytes` made of valid utf8 followed by invalid bytes, yielded by
+/// [`Utf8Chunks`].
+///
+/// Like [`core::str::Utf8Chunk`], the invalid bytes are at most 3 bytes long and are empty only
+/// for the last chunk. Both parts share the buffer of the `Bytes` they come from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Utf8Chunk {
+    valid: ByteStr,
+    invalid: Bytes,
+}
+
+impl Utf8Chunk {
+    /// Return the valid utf8 part of the chunk.
+    #[must_use]
+    #[inline]
+    pub fn valid(&self) -> &ByteStr {
+        &self.valid
+    }
+
+    /// Return the invalid bytes following the valid part.
+    #[must_use]
+    #[inline]
+    pub fn invalid(&self) -> &Bytes {
+        &self.invalid
+    }
+
+    /// Consume `self` and return the valid part and the invalid bytes.
+    #[must_use]
+    #[inline]
+    pub fn into_parts(self) -> (ByteStr, Bytes) {
+        (self.valid, self.invalid)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn utf8_chunks() {
+        let bytes = Bytes::from(b"caf\xc3\xa9\xff\xfe!\xe2\x82".to_vec());
+        let chunks: Vec<_> = bytes.utf8_chunks().map(Utf8Chunk::into_parts).collect();
+
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(
+            chunks[0],
+            (ByteStr::from_static("café"), Bytes::from_static(b"\xff"))
+        );
+        assert_eq!(chunks[1], (ByteStr::new(), Bytes::from_static(b"\xfe")));
+        assert_eq!(
+            chunks[2],
+            (ByteStr::from_static("!"), Bytes::from_static(b"\xe2\x82"))
+        );
+        assert_eq!(chunks[2].0.as_ptr(), bytes[7..].as_ptr());
+
+        let valid: Vec<_> = Bytes::from_static(b"text").utf8_chunks().collect();
+        assert_eq!(valid[0].valid(), "text");
+        assert!(valid[0].invalid().is_empty());
+
+        assert_eq!(Bytes::new().utf8_chunks().count(), 0);
+    }
+}
@@ -0,0 +1,88 @@
+use core::iter::FusedIterator;
+
+use crate::{Buf, Bytes};
+
+/// An iterator over the overlapping windows of `size` bytes of a `Bytes`, created by
+/// [`Bytes::windows`].
+#[derive(Debug, Clone)]
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct Windows {
+    /// The bytes starting with the next window
+    bytes: Bytes,
+    size: usize,
+}
+
+impl Windows {
+    #[inline]
+    pub(crate) fn new(bytes: Bytes, size: usize) -> Windows {
+        assert!(size != 0, "window size must be non-zero");
+
+        Windows { bytes, size }
+    }
+}
+
+impl Iterator for Windows {
+    type Item = Bytes;
+
+    fn next(&mut self) -> Option<Bytes> {
+        if self.bytes.len() < self.size {
+            return None;
+        }
+
+        let window = self.bytes.slice(..self.size);
+        self.bytes.advance(1);
+        Some(window)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (self.bytes.len() + 1).saturating_sub(self.size);
+        (len, Some(len))
+    }
+}
+
+impl DoubleEndedIterator for Windows {
+    fn next_back(&mut self) -> Option<Bytes> {
+        if self.bytes.len() < self.size {
+            return None;
+        }
+
+        let len = self.bytes.len();
+        let window = self.bytes.slice(len - self.size..);
+        self.bytes.truncate(len - 1);
+        Some(window)
+    }
+}
+
+impl ExactSizeIterator for Windows {}
+
+impl FusedIterator for Windows {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn windows() {
+        let bytes = Bytes::from(b"abcde".to_vec());
+
+        let mut windows = bytes.windows(3);
+        assert_eq!(windows.len(), 3);
+        assert_eq!(windows.next_back().unwrap(), "cde");
+
+        let first = windows.next().unwrap();
+        assert_eq!(first, "abc");
+        assert_eq!(first.as_ptr(), bytes.as_ptr());
+        assert_eq!(windows.collect::<Vec<_>>(), ["bcd"]);
+
+        assert_eq!(bytes.windows(5).collect::<Vec<_>>(), ["abcde"]);
+        assert_eq!(bytes.windows(6).len(), 0);
+        assert_eq!(Bytes::new().windows(1).count(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "window size must be non-zero")]
+    fn zero_size() {
+        let _ = Bytes::new().windows(0);
+    }
+}
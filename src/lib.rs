@@ -3,17 +3,79 @@ extern crate alloc;
 #[cfg(feature = "std")]
 extern crate std;
 
+#[cfg(feature = "rkyv")]
+mod archive;
+pub mod budget;
 mod buf;
 mod byte_str;
 mod bytes;
+mod bytes_list;
 mod bytes_mut;
+mod bytes_ref;
+pub mod codec;
+pub mod compat;
 mod fmt;
+#[cfg(feature = "std")]
+mod io;
 mod iter;
+mod maybe_owned;
+#[cfg(feature = "mmap")]
+mod mmap;
+#[cfg(feature = "rayon")]
+mod par;
+mod pipe;
+mod raw;
+mod rc_bytes;
+#[cfg(feature = "serde")]
+mod serde;
+mod shared_append;
+pub mod simd;
+mod sparse;
+#[cfg(test)]
+mod test_alloc;
+pub mod testsuite;
+mod trace;
+#[cfg(feature = "zerocopy")]
+mod view;
+
+pub use crate::byte_str::{ByteStr, CharPolicy, PolicyError, PolicyViolation};
+pub use crate::bytes::{Bytes, FromUtf8Error, OutOfBounds, SliceError};
+pub use crate::bytes_list::{BytesList, BytesListCursor, Gather};
+pub use crate::bytes_mut::{BytesMut, GrowthPolicy, TryReserveError};
+pub use crate::bytes_ref::BytesRef;
+pub use crate::maybe_owned::MaybeOwnedBytes;
+pub use crate::pipe::{pipe, PipeReader, PipeWriter};
+pub use crate::raw::RawParts;
+pub use crate::rc_bytes::{RcBytes, RcBytesMut};
+pub use crate::shared_append::{SharedAppendBuf, SharedAppendReader};
+pub use crate::sparse::SparseBytesMut;
+
+pub use crate::buf::{
+    BitOrder, BitReader, BitWriter, Buf, BufMut, Chain, CountingBuf, CountingBufMut, Finish, Limit,
+    OffsetTrackingBuf, Take, TrackedUninitSlice, UninitSlice,
+};
+
+pub use crate::iter::{
+    ByteStrLines, BytesIter, Chunks, Delimiter, Fields, Lines, Split, SplitWhitespace, Utf8Chunk,
+    Utf8Chunks, Windows,
+};
 
-pub use crate::byte_str::ByteStr;
-pub use crate::bytes::Bytes;
-pub use crate::bytes_mut::BytesMut;
+#[cfg(feature = "rayon")]
+pub use crate::par::ParChunks;
 
-pub use crate::buf::{Buf, BufMut};
+/// The greatest len of a buffer, and so of the remaining bytes of a `Buf` or the remaining space
+/// of a `BufMut`.
+///
+/// The allocator never hands out more than `isize::MAX` bytes and the pointer arithmetic of the
+/// crate relies on it, every len computed from user input is checked against it.
+pub(crate) const MAX_LEN: usize = isize::MAX as usize;
 
-pub use crate::iter::BytesIter;
+/// Fail to compile if `T` is not unwind safe.
+///
+/// The buffers are shared with code isolating panics with `std::panic::catch_unwind`, so losing
+/// the auto traits because of a new field must be caught at compile time.
+pub(crate) const fn assert_unwind_safe<T>()
+where
+    T: core::panic::UnwindSafe + core::panic::RefUnwindSafe,
+{
+}
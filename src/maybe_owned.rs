@@ -0,0 +1,204 @@
+use core::ops::Deref;
+
+use crate::{Buf, Bytes};
+
+/// A byte buffer which is either borrowed or owned as a `Bytes`.
+///
+/// This lets an API defer the decision to copy borrowed data to its caller: the data is only
+/// copied when [`MaybeOwnedBytes::into_owned`] is called on a borrowed value.
+///
+/// # Example
+///
+/// ```
+/// use bytes::{Bytes, MaybeOwnedBytes};
+///
+/// fn header(buf: &[u8]) -> MaybeOwnedBytes<'_> {
+///     MaybeOwnedBytes::from(&buf[..4])
+/// }
+///
+/// let buf = b"HTTP/1.1".to_vec();
+/// let h = header(&buf);
+///
+/// assert!(h.is_borrowed());
+/// assert_eq!(&h[..], b"HTTP");
+///
+/// let owned: Bytes = h.into_owned();
+/// assert_eq!(owned, b"HTTP"[..]);
+/// ```
+#[derive(Clone)]
+pub enum MaybeOwnedBytes<'a> {
+    /// A borrowed slice of bytes.
+    Borrowed(&'a [u8]),
+    /// An owned `Bytes`.
+    Owned(Bytes),
+}
+
+impl<'a> MaybeOwnedBytes<'a> {
+    /// Retrieve the inner bytes as a slice.
+    #[inline]
+    pub fn as_slice(&self) -> &[u8] {
+        match self {
+            MaybeOwnedBytes::Borrowed(b) => b,
+            MaybeOwnedBytes::Owned(b) => b.as_slice(),
+        }
+    }
+
+    /// Return `true` if `self` is borrowed.
+    #[inline]
+    pub fn is_borrowed(&self) -> bool {
+        matches!(self, MaybeOwnedBytes::Borrowed(_))
+    }
+
+    /// Return `true` if `self` is owned.
+    #[inline]
+    pub fn is_owned(&self) -> bool {
+        matches!(self, MaybeOwnedBytes::Owned(_))
+    }
+
+    /// Consume `self` and return an owned `Bytes`. Borrowed bytes are copied into a new buffer
+    /// while owned bytes are returned as is.
+    pub fn into_owned(self) -> Bytes {
+        match self {
+            MaybeOwnedBytes::Borrowed(b) => Bytes::copy_from_slice(b),
+            MaybeOwnedBytes::Owned(b) => b,
+        }
+    }
+
+    /// Make `self` owned, copying the bytes if they are borrowed, and return a reference to the
+    /// owned `Bytes`.
+    pub fn to_mut(&mut self) -> &mut Bytes {
+        if let MaybeOwnedBytes::Borrowed(b) = *self {
+            *self = MaybeOwnedBytes::Owned(Bytes::copy_from_slice(b));
+        }
+
+        match self {
+            MaybeOwnedBytes::Owned(b) => b,
+            MaybeOwnedBytes::Borrowed(_) => unreachable!(),
+        }
+    }
+}
+
+impl Default for MaybeOwnedBytes<'_> {
+    #[inline]
+    fn default() -> Self {
+        MaybeOwnedBytes::Borrowed(&[])
+    }
+}
+
+impl Deref for MaybeOwnedBytes<'_> {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl AsRef<[u8]> for MaybeOwnedBytes<'_> {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl<'a> From<&'a [u8]> for MaybeOwnedBytes<'a> {
+    #[inline]
+    fn from(value: &'a [u8]) -> Self {
+        MaybeOwnedBytes::Borrowed(value)
+    }
+}
+
+impl From<Bytes> for MaybeOwnedBytes<'_> {
+    #[inline]
+    fn from(value: Bytes) -> Self {
+        MaybeOwnedBytes::Owned(value)
+    }
+}
+
+impl From<MaybeOwnedBytes<'_>> for Bytes {
+    #[inline]
+    fn from(value: MaybeOwnedBytes<'_>) -> Bytes {
+        value.into_owned()
+    }
+}
+
+impl PartialEq for MaybeOwnedBytes<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl Eq for MaybeOwnedBytes<'_> {}
+
+impl PartialEq<[u8]> for MaybeOwnedBytes<'_> {
+    fn eq(&self, other: &[u8]) -> bool {
+        self.as_slice() == other
+    }
+}
+
+impl Buf for MaybeOwnedBytes<'_> {
+    #[inline]
+    fn remaining(&self) -> usize {
+        self.len()
+    }
+
+    #[inline]
+    fn chuncks(&self) -> &[u8] {
+        self.as_slice()
+    }
+
+    #[inline]
+    fn remaining_slice(&self) -> Option<&[u8]> {
+        Some(self.as_slice())
+    }
+
+    fn fork(&self) -> Bytes {
+        match self {
+            MaybeOwnedBytes::Borrowed(b) => Bytes::copy_from_slice(b),
+            MaybeOwnedBytes::Owned(b) => b.clone(),
+        }
+    }
+
+    fn copy_to_bytes(&mut self, len: usize) -> Bytes {
+        match self {
+            MaybeOwnedBytes::Borrowed(b) => b.copy_to_bytes(len),
+            MaybeOwnedBytes::Owned(b) => b.copy_to_bytes(len),
+        }
+    }
+
+    fn advance(&mut self, cnt: usize) {
+        match self {
+            MaybeOwnedBytes::Borrowed(b) => b.advance(cnt),
+            MaybeOwnedBytes::Owned(b) => b.advance(cnt),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn to_mut() {
+        let src = [1u8, 2, 3];
+        let mut b = MaybeOwnedBytes::from(&src[..]);
+
+        b.to_mut().truncate(2);
+
+        assert!(b.is_owned());
+        assert_eq!(b, MaybeOwnedBytes::Borrowed(&[1, 2]));
+    }
+
+    #[test]
+    fn buf() {
+        let mut owned = MaybeOwnedBytes::from(Bytes::from_static(b"abc"));
+        let mut borrowed = MaybeOwnedBytes::from(&b"abc"[..]);
+
+        owned.advance(1);
+        borrowed.advance(2);
+
+        assert_eq!(owned.chuncks(), b"bc");
+        assert_eq!(borrowed.get_u8(), b'c');
+        assert!(!borrowed.has_remaining());
+    }
+}
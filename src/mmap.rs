@@ -0,0 +1,84 @@
+//! `Bytes` backed by memory-mapped files, enabled by the `mmap` feature.
+//!
+//! The map is the owner of the `Bytes`, see [`Bytes::from_owner`]: it's unmapped once the
+//! `Bytes` and all its clones are dropped. The pages of the file are only read when the bytes are
+//! accessed, so a file larger than the memory can be processed through the `Bytes` APIs.
+
+use std::fs::File;
+use std::io;
+use std::path::Path;
+
+use memmap2::Mmap;
+
+use crate::Bytes;
+
+impl Bytes {
+    /// Create a new `Bytes` viewing the bytes of `mmap`, which is kept alive until the returned
+    /// `Bytes` and all its clones are dropped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// let file = std::fs::File::open("Cargo.toml").unwrap();
+    ///
+    /// // Safety: the manifest is not modified while mapped
+    /// let mmap = unsafe { memmap2::Mmap::map(&file) }.unwrap();
+    /// let manifest = Bytes::from_mmap(mmap);
+    ///
+    /// assert!(manifest.starts_with(b"[package]"));
+    /// ```
+    #[must_use]
+    pub fn from_mmap(mmap: Mmap) -> Bytes {
+        Bytes::from_owner(mmap)
+    }
+
+    /// Map the file at `path` in memory and create a new `Bytes` viewing its bytes, see
+    /// [`Bytes::from_mmap`].
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified nor truncated, by this process or another one, while the
+    /// returned `Bytes` or any of its clones is alive. The bytes of a `Bytes` must never change
+    /// and accessing the pages of a truncated file raises a `SIGBUS`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the I/O error raised while opening or mapping the file.
+    pub unsafe fn from_path<P: AsRef<Path>>(path: P) -> io::Result<Bytes> {
+        let file = File::open(path)?;
+        let mmap = Mmap::map(&file)?;
+
+        Ok(Bytes::from_mmap(mmap))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn from_path() {
+        let path = std::env::temp_dir().join(format!("bytes-mmap-{}", std::process::id()));
+        fs::write(&path, b"mapped file content").unwrap();
+
+        let bytes = unsafe { Bytes::from_path(&path) }.unwrap();
+        let content = bytes.slice(7..11);
+        drop(bytes);
+
+        assert_eq!(content, "file");
+        assert_eq!(content.ref_count(), Some(1));
+        let content = content.try_into_mut().unwrap_err();
+
+        // The file must not be modified while it's mapped
+        drop(content);
+        fs::write(&path, b"").unwrap();
+        assert!(unsafe { Bytes::from_path(&path) }.unwrap().is_empty());
+
+        fs::remove_file(&path).unwrap();
+        assert!(unsafe { Bytes::from_path(&path) }.is_err());
+    }
+}
@@ -0,0 +1,154 @@
+//! Parallel iteration over `Bytes` with rayon, enabled by the `rayon` feature.
+
+use rayon::iter::plumbing::{bridge, Consumer, Producer, ProducerCallback, UnindexedConsumer};
+use rayon::prelude::*;
+
+use crate::iter::Chunks;
+use crate::{simd, Bytes};
+
+/// The size of the blocks scanned by each task of the parallel searches.
+const BLOCK: usize = 64 * 1024;
+
+impl Bytes {
+    /// Return a parallel iterator over `size` bytes chunks of `self`, the last chunk may be
+    /// shorter. The chunks are `Bytes` sharing the buffer of `self`, nothing is copied.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    /// use rayon::prelude::*;
+    ///
+    /// let bytes = Bytes::from(vec![1u8; 1000]);
+    /// let sums: Vec<u32> = bytes
+    ///     .par_chunks(300)
+    ///     .map(|chunk| chunk.iter().map(|b| *b as u32).sum())
+    ///     .collect();
+    ///
+    /// assert_eq!(sums, [300, 300, 300, 100]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This panics if `size` is `0`.
+    pub fn par_chunks(&self, size: usize) -> ParChunks {
+        assert!(size != 0, "chunk size must be non-zero");
+
+        ParChunks {
+            bytes: self.clone(),
+            size,
+        }
+    }
+
+    /// Return the index of the first occurrence of `byte` in `self`, searching blocks of the
+    /// buffer in parallel.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// let mut buf = vec![0u8; 1 << 20];
+    /// buf[700_000] = b'\n';
+    ///
+    /// assert_eq!(Bytes::from(buf).par_find_byte(b'\n'), Some(700_000));
+    /// ```
+    pub fn par_find_byte(&self, byte: u8) -> Option<usize> {
+        self.as_slice()
+            .par_chunks(BLOCK)
+            .enumerate()
+            .find_map_first(|(i, block)| simd::memchr(byte, block).map(|pos| i * BLOCK + pos))
+    }
+
+    /// Return the number of occurrences of `byte` in `self`, counting blocks of the buffer in
+    /// parallel.
+    pub fn par_count_byte(&self, byte: u8) -> usize {
+        self.as_slice()
+            .par_chunks(BLOCK)
+            .map(|block| simd::count(byte, block))
+            .sum()
+    }
+}
+
+/// A parallel iterator over the chunks of a `Bytes`, see [`Bytes::par_chunks`].
+#[derive(Debug)]
+pub struct ParChunks {
+    bytes: Bytes,
+    size: usize,
+}
+
+impl ParallelIterator for ParChunks {
+    type Item = Bytes;
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Bytes>,
+    {
+        bridge(self, consumer)
+    }
+
+    fn opt_len(&self) -> Option<usize> {
+        Some(IndexedParallelIterator::len(self))
+    }
+}
+
+impl IndexedParallelIterator for ParChunks {
+    fn len(&self) -> usize {
+        self.bytes.len().div_ceil(self.size)
+    }
+
+    fn drive<C: Consumer<Bytes>>(self, consumer: C) -> C::Result {
+        bridge(self, consumer)
+    }
+
+    fn with_producer<CB: ProducerCallback<Bytes>>(self, callback: CB) -> CB::Output {
+        callback.callback(self.bytes.chunks(self.size))
+    }
+}
+
+impl Producer for Chunks {
+    type Item = Bytes;
+    type IntoIter = Chunks;
+
+    fn into_iter(self) -> Chunks {
+        self
+    }
+
+    fn split_at(self, index: usize) -> (Chunks, Chunks) {
+        self.split_at_chunk(index)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn chunks() {
+        let bytes = Bytes::from((0..=255).collect::<Vec<u8>>());
+
+        let chunks: Vec<Bytes> = bytes.par_chunks(100).collect();
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks.concat(), bytes.as_slice());
+
+        let rev: Vec<Bytes> = bytes.par_chunks(64).rev().collect();
+        assert_eq!(rev[0], bytes.slice(192..));
+        assert_eq!(rev[3], bytes.slice(..64));
+
+        assert_eq!(Bytes::new().par_chunks(8).count(), 0);
+    }
+
+    #[test]
+    fn search() {
+        let mut buf = vec![0u8; 3 * BLOCK + 10];
+        buf[BLOCK - 1] = 1;
+        buf[2 * BLOCK] = 1;
+        buf[3 * BLOCK + 9] = 1;
+        let bytes = Bytes::from(buf);
+
+        assert_eq!(bytes.par_find_byte(1), Some(BLOCK - 1));
+        assert_eq!(bytes.par_find_byte(2), None);
+        assert_eq!(bytes.par_count_byte(1), 3);
+        assert_eq!(bytes.par_count_byte(0), 3 * BLOCK + 7);
+    }
+}
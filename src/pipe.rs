@@ -0,0 +1,375 @@
+use core::mem::MaybeUninit;
+use core::ptr;
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+use crate::buf::{Buf, BufMut, UninitSlice};
+use crate::Bytes;
+
+/// Create a single-producer single-consumer byte pipe able to buffer at least `capacity` bytes.
+///
+/// The bytes written into the [`PipeWriter`] are read from the [`PipeReader`] in the same order.
+/// Both ends share a ring buffer and synchronize with atomics only: the pipe never blocks nor
+/// wakes anything up. A full pipe has no remaining space to write and an empty pipe has no
+/// remaining bytes to read, it's up to the caller to retry later, e.g. on the next poll of a
+/// stage of a pipeline.
+///
+/// The capacity is rounded up to the next power of two.
+///
+/// # Example
+///
+/// ```
+/// use bytes::{Buf, BufMut};
+///
+/// let (mut writer, mut reader) = bytes::pipe(16);
+///
+/// writer.put_slice(b"hello");
+/// assert_eq!(reader.chuncks(), b"hello");
+///
+/// reader.advance(5);
+/// drop(writer);
+///
+/// assert!(!reader.has_remaining());
+/// assert!(reader.is_closed());
+/// ```
+///
+/// # Panics
+///
+/// This panics if `capacity` is `0` or if it can't be rounded up to a power of two.
+pub fn pipe(capacity: usize) -> (PipeWriter, PipeReader) {
+    assert!(capacity > 0, "pipe capacity must be non-zero");
+
+    let cap = capacity
+        .checked_next_power_of_two()
+        .filter(|cap| *cap <= crate::MAX_LEN)
+        .expect("pipe capacity overflow");
+
+    let buf = Box::into_raw(Box::<[u8]>::new_uninit_slice(cap)).cast::<u8>();
+
+    let ring = Arc::new(Ring {
+        buf,
+        cap,
+        head: AtomicUsize::new(0),
+        tail: AtomicUsize::new(0),
+        closed: AtomicBool::new(false),
+    });
+
+    let writer = PipeWriter {
+        ring: ring.clone(),
+        tail: 0,
+    };
+    let reader = PipeReader { ring, head: 0 };
+
+    (writer, reader)
+}
+
+/// The writing end of a [`pipe`].
+pub struct PipeWriter {
+    ring: Arc<Ring>,
+    /// The number of bytes written since the creation of the pipe, wrapping on overflow
+    tail: usize,
+}
+
+/// The reading end of a [`pipe`].
+pub struct PipeReader {
+    ring: Arc<Ring>,
+    /// The number of bytes read since the creation of the pipe, wrapping on overflow
+    head: usize,
+}
+
+/// The ring buffer shared by the two ends of a pipe.
+///
+/// `head` and `tail` are positions in the stream, they are reduced modulo `cap` to index the
+/// buffer. `cap` is a power of two so the reduction stays consistent when they wrap.
+struct Ring {
+    buf: *mut u8,
+    cap: usize,
+    /// The position published by the reader, the writer never writes over the bytes after it
+    head: AtomicUsize,
+    /// The position published by the writer, the reader never reads the bytes after it
+    tail: AtomicUsize,
+    /// Set once one of the ends is dropped
+    closed: AtomicBool,
+}
+
+impl Ring {
+    /// Return the slice of `len` bytes starting at the position `pos` of the stream, truncated
+    /// at the end of the buffer.
+    #[inline]
+    fn region(&self, pos: usize, len: usize) -> (*mut u8, usize) {
+        let off = pos & (self.cap - 1);
+        let len = core::cmp::min(len, self.cap - off);
+
+        // SAFETY:
+        // `off < self.cap` so the pointer is in bounds of the buffer
+        (unsafe { self.buf.add(off) }, len)
+    }
+}
+
+impl Drop for Ring {
+    fn drop(&mut self) {
+        let buf = ptr::slice_from_raw_parts_mut(self.buf.cast::<MaybeUninit<u8>>(), self.cap);
+
+        // SAFETY:
+        // The buffer was created from a boxed slice of `cap` bytes
+        drop(unsafe { Box::from_raw(buf) })
+    }
+}
+
+// SAFETY:
+// The writer only writes the bytes between `tail` and `head + cap` while the reader only reads
+// the bytes between `head` and `tail`. Each end publishes its position with a `Release` store
+// once it's done with the bytes, so the two regions never overlap.
+unsafe impl Send for Ring {}
+
+unsafe impl Sync for Ring {}
+
+// The positions are published after the bytes are copied, so a panic while writing never
+// exposes uninitialized bytes to the reader.
+const _: () = crate::assert_unwind_safe::<PipeWriter>();
+const _: () = crate::assert_unwind_safe::<PipeReader>();
+
+impl PipeWriter {
+    /// Return the number of bytes the pipe can buffer.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.ring.cap
+    }
+
+    /// Return `true` if the reader was dropped, the bytes written afterwards are never read.
+    #[inline]
+    pub fn is_closed(&self) -> bool {
+        self.ring.closed.load(Ordering::Acquire)
+    }
+}
+
+impl Drop for PipeWriter {
+    fn drop(&mut self) {
+        self.ring.closed.store(true, Ordering::Release);
+    }
+}
+
+impl BufMut for PipeWriter {
+    #[inline]
+    fn remaining_mut(&self) -> usize {
+        // Synchronizes with the `Release` store of the reader so that the bytes it read are not
+        // overwritten before it's done with them
+        let head = self.ring.head.load(Ordering::Acquire);
+        self.ring.cap - self.tail.wrapping_sub(head)
+    }
+
+    fn chuncks_mut(&mut self) -> &mut UninitSlice {
+        let (ptr, len) = self.ring.region(self.tail, self.remaining_mut());
+
+        // SAFETY:
+        // The bytes after `tail` are not read until the writer publishes them
+        unsafe { UninitSlice::from_raw_parts(ptr, len) }
+    }
+
+    unsafe fn advance(&mut self, count: usize) {
+        let rem = self.remaining_mut();
+
+        assert!(
+            count <= rem,
+            "not enough space to advance: remaining ({}) < count ({})",
+            rem,
+            count
+        );
+
+        self.tail = self.tail.wrapping_add(count);
+        self.ring.tail.store(self.tail, Ordering::Release);
+    }
+}
+
+impl PipeReader {
+    /// Return the number of bytes the pipe can buffer.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.ring.cap
+    }
+
+    /// Return `true` if the writer was dropped. The bytes it wrote before are still readable.
+    #[inline]
+    pub fn is_closed(&self) -> bool {
+        self.ring.closed.load(Ordering::Acquire)
+    }
+
+    /// Return the first `len` readable bytes as the two regions they span in the ring, the
+    /// second one being empty if they don't wrap around the end of the buffer.
+    fn regions(&self, len: usize) -> (&[u8], &[u8]) {
+        let (first, first_len) = self.ring.region(self.head, len);
+        let (second, second_len) = self
+            .ring
+            .region(self.head.wrapping_add(first_len), len - first_len);
+
+        // SAFETY:
+        // See `chuncks`, `len` doesn't exceed the readable bytes which wrap at most once around
+        // the buffer so the second region holds the rest of them
+        unsafe {
+            (
+                core::slice::from_raw_parts(first, first_len),
+                core::slice::from_raw_parts(second, second_len),
+            )
+        }
+    }
+}
+
+impl Drop for PipeReader {
+    fn drop(&mut self) {
+        self.ring.closed.store(true, Ordering::Release);
+    }
+}
+
+impl Buf for PipeReader {
+    #[inline]
+    fn remaining(&self) -> usize {
+        // Synchronizes with the `Release` store of the writer so that the published bytes are
+        // visible
+        let tail = self.ring.tail.load(Ordering::Acquire);
+        tail.wrapping_sub(self.head)
+    }
+
+    fn chuncks(&self) -> &[u8] {
+        let (ptr, len) = self.ring.region(self.head, self.remaining());
+
+        // SAFETY:
+        // The bytes between `head` and `tail` are initialized and not written until the reader
+        // publishes a new `head`, which requires `&mut self`
+        unsafe { core::slice::from_raw_parts(ptr, len) }
+    }
+
+    fn peek_to_slice(&self, dst: &mut [u8]) {
+        let rem = self.remaining();
+
+        assert!(
+            rem >= dst.len(),
+            "not enough remaining bytes: remaining ({}) < needed ({})",
+            rem,
+            dst.len()
+        );
+
+        let (first, second) = self.regions(dst.len());
+        let (dst_first, dst_second) = dst.split_at_mut(first.len());
+
+        dst_first.copy_from_slice(first);
+        dst_second.copy_from_slice(second);
+    }
+
+    fn fork(&self) -> Bytes {
+        let remaining = self.remaining();
+        let (first, second) = self.regions(remaining);
+
+        let mut vec = Vec::with_capacity(remaining);
+        vec.extend_from_slice(first);
+        vec.extend_from_slice(second);
+        Bytes::from(vec)
+    }
+
+    fn advance(&mut self, cnt: usize) {
+        let rem = self.remaining();
+
+        assert!(
+            cnt <= rem,
+            "cannot advance past remaining: cnt ({}) > remaining ({})",
+            cnt,
+            rem
+        );
+
+        self.head = self.head.wrapping_add(cnt);
+        self.ring.head.store(self.head, Ordering::Release);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn wrap_around() {
+        let (mut writer, mut reader) = pipe(6);
+
+        assert_eq!(writer.capacity(), 8);
+
+        writer.put_slice(b"abcdef");
+        assert_eq!(writer.remaining_mut(), 2);
+
+        reader.advance(4);
+        writer.put_slice(b"ghijkl");
+        assert_eq!(writer.remaining_mut(), 0);
+
+        assert_eq!(reader.remaining(), 8);
+        assert_eq!(reader.chuncks(), b"efgh");
+        assert_eq!(reader.fork(), "efghijkl");
+
+        reader.advance(4);
+        assert_eq!(reader.chuncks(), b"ijkl");
+        assert_eq!(writer.remaining_mut(), 4);
+    }
+
+    #[test]
+    fn closed() {
+        let (writer, reader) = pipe(4);
+        assert!(!writer.is_closed());
+
+        drop(reader);
+        assert!(writer.is_closed());
+    }
+
+    #[test]
+    fn laws() {
+        crate::testsuite::assert_buf_laws(
+            || {
+                let (mut writer, mut reader) = pipe(16);
+
+                // Move the positions so that the content wraps around the buffer
+                writer.put_slice(&[0; 10]);
+                reader.advance(10);
+                writer.put_slice(b"hello world");
+                reader
+            },
+            b"hello world",
+        );
+    }
+
+    #[test]
+    fn concurrent() {
+        const LEN: usize = 100_000;
+
+        let (mut writer, mut reader) = pipe(64);
+
+        let producer = std::thread::spawn(move || {
+            let mut n = 0;
+
+            while n < LEN {
+                if writer.has_remaining_mut() {
+                    writer.put_u8(n as u8);
+                    n += 1;
+                } else {
+                    std::thread::yield_now();
+                }
+            }
+        });
+
+        let mut n = 0;
+
+        loop {
+            let closed = reader.is_closed();
+
+            while reader.has_remaining() {
+                assert_eq!(reader.get_u8(), n as u8);
+                n += 1;
+            }
+
+            if closed {
+                break;
+            }
+            std::thread::yield_now();
+        }
+
+        producer.join().unwrap();
+        assert_eq!(n, LEN);
+    }
+}
@@ -0,0 +1,205 @@
+use alloc::boxed::Box;
+
+use crate::Bytes;
+
+/// The raw parts of a buffer shared with code managing its own memory, e.g. a runtime with its
+/// own allocator or a foreign library.
+///
+/// A `Bytes` is created from raw parts with [`Bytes::from_raw_parts`] and turned into raw parts
+/// with [`Bytes::into_raw_parts`]. The internal representation of `Bytes` is free to change
+/// between versions, unlike this struct.
+///
+/// # Stability
+///
+/// The layout of `RawParts` is part of the stable API of the crate. It's `#[repr(C)]`, its
+/// fields keep their order, their types and their meaning, and no field is added nor removed
+/// without a major version bump. It's equivalent to the following C struct:
+///
+/// ```c
+/// struct raw_parts {
+///     const uint8_t *ptr;
+///     size_t len;
+///     void *context;
+///     void (*release)(void *context, const uint8_t *ptr, size_t len);
+/// };
+/// ```
+///
+/// # Example
+///
+/// ```
+/// use bytes::{Bytes, RawParts};
+///
+/// static POOL: [u8; 5] = *b"hello";
+///
+/// unsafe extern "C" fn release(_context: *mut (), _ptr: *const u8, _len: usize) {
+///     // Return the buffer to the pool
+/// }
+///
+/// let parts = RawParts {
+///     ptr: POOL.as_ptr(),
+///     len: POOL.len(),
+///     context: core::ptr::null_mut(),
+///     release,
+/// };
+///
+/// // Safety: the pool outlives the `Bytes` and is never mutated
+/// let bytes = unsafe { Bytes::from_raw_parts(parts) };
+/// assert_eq!(bytes, "hello");
+/// ```
+#[repr(C)]
+#[derive(Debug)]
+pub struct RawParts {
+    /// A pointer to the first byte of the buffer.
+    pub ptr: *const u8,
+    /// The number of bytes of the buffer.
+    pub len: usize,
+    /// An opaque pointer passed to `release`, e.g. the allocation holding the buffer.
+    pub context: *mut (),
+    /// The function releasing the buffer, called once with `context`, `ptr` and `len` when the
+    /// buffer is no longer used.
+    pub release: unsafe extern "C" fn(context: *mut (), ptr: *const u8, len: usize),
+}
+
+/// Call the release function of the parts once dropped.
+struct Release(RawParts);
+
+// SAFETY:
+// The callers of `Bytes::from_raw_parts` guarantee that the parts can be released from any
+// thread
+unsafe impl Send for Release {}
+
+impl Drop for Release {
+    fn drop(&mut self) {
+        let RawParts {
+            ptr,
+            len,
+            context,
+            release,
+        } = self.0;
+
+        // SAFETY:
+        // The parts are released once, when the last `Bytes` viewing them is dropped
+        unsafe { release(context, ptr, len) }
+    }
+}
+
+/// The release function of the parts created by `Bytes::into_raw_parts`.
+unsafe extern "C" fn release_bytes(context: *mut (), _ptr: *const u8, _len: usize) {
+    // SAFETY:
+    // The context was leaked from a boxed `Bytes` by `Bytes::into_raw_parts`
+    drop(unsafe { Box::from_raw(context.cast::<Bytes>()) });
+}
+
+impl Bytes {
+    /// Create a new `Bytes` viewing the buffer described by `parts` without copying it.
+    ///
+    /// `parts.release` is called once the returned `Bytes` and all its clones are dropped, right
+    /// away if the buffer is empty.
+    ///
+    /// # Safety
+    ///
+    /// - `parts.ptr` must be valid for reads of `parts.len` bytes, from any thread, until
+    ///   `parts.release` is called.
+    /// - The bytes must not be mutated until `parts.release` is called.
+    /// - `parts.release` must be safe to call from any thread with the fields of `parts`.
+    #[must_use]
+    pub unsafe fn from_raw_parts(parts: RawParts) -> Bytes {
+        let (ptr, len) = (parts.ptr, parts.len);
+
+        // SAFETY:
+        // The bytes are kept valid and immutable until the owner releases them
+        unsafe { Bytes::with_owner(Release(parts), ptr, len) }
+    }
+
+    /// Consume `self` and return the raw parts of its bytes. The buffer is kept alive until the
+    /// `release` function of the parts is called.
+    ///
+    /// The parts can be passed to [`Bytes::from_raw_parts`] or to code managing its own memory.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    ///
+    /// let bytes = Bytes::from(b"hello".to_vec());
+    /// let parts = bytes.into_raw_parts();
+    ///
+    /// // Safety: the parts come from a `Bytes`
+    /// let bytes = unsafe { Bytes::from_raw_parts(parts) };
+    /// assert_eq!(bytes, "hello");
+    /// ```
+    #[must_use = "the buffer is leaked if the parts are never released"]
+    pub fn into_raw_parts(self) -> RawParts {
+        let (ptr, len) = (self.as_ptr(), self.len());
+
+        RawParts {
+            ptr,
+            len,
+            context: Box::into_raw(Box::new(self)).cast(),
+            release: release_bytes,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    static RELEASED: AtomicUsize = AtomicUsize::new(0);
+
+    unsafe extern "C" fn release(context: *mut (), ptr: *const u8, len: usize) {
+        // SAFETY:
+        // The context was leaked from a boxed vec in `raw_parts`
+        let vec = unsafe { Box::from_raw(context.cast::<Vec<u8>>()) };
+        assert_eq!((vec.as_ptr(), vec.len()), (ptr, len));
+
+        RELEASED.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn raw_parts(content: &[u8]) -> RawParts {
+        let vec = Box::new(content.to_vec());
+
+        RawParts {
+            ptr: vec.as_ptr(),
+            len: vec.len(),
+            context: Box::into_raw(vec).cast(),
+            release,
+        }
+    }
+
+    #[test]
+    fn from_raw_parts() {
+        let bytes = unsafe { Bytes::from_raw_parts(raw_parts(b"hello")) };
+        let clone = bytes.slice(1..);
+
+        drop(bytes);
+        assert_eq!(RELEASED.load(Ordering::Relaxed), 0);
+        assert_eq!(clone, "ello");
+
+        drop(clone);
+        assert_eq!(RELEASED.load(Ordering::Relaxed), 1);
+
+        // An empty buffer is released right away
+        let empty = unsafe { Bytes::from_raw_parts(raw_parts(b"")) };
+        assert_eq!(RELEASED.load(Ordering::Relaxed), 2);
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn into_raw_parts() {
+        let bytes = Bytes::from(b"hello".to_vec());
+        let ptr = bytes.as_ptr();
+
+        let parts = bytes.into_raw_parts();
+        assert_eq!((parts.ptr, parts.len), (ptr, 5));
+
+        let bytes = unsafe { Bytes::from_raw_parts(parts) };
+        assert_eq!(bytes.as_ptr(), ptr);
+        assert_eq!(bytes, "hello");
+
+        let parts = Bytes::new().into_raw_parts();
+        unsafe { (parts.release)(parts.context, parts.ptr, parts.len) };
+    }
+}
@@ -0,0 +1,725 @@
+use alloc::rc::Rc;
+use core::borrow::Borrow;
+use core::marker::PhantomData;
+use core::ops::{Deref, RangeBounds};
+use core::{cmp, ptr, slice};
+
+use crate::buf::UninitSlice;
+use crate::{Buf, BufMut, Bytes, BytesMut};
+
+/// A single-threaded counterpart of [`Bytes`] whose clones share the buffer through a
+/// non-atomic reference count.
+///
+/// Cloning a `Bytes` updates an atomic reference count and the first clone of a buffer created
+/// from a `Vec` allocates the shared state, see [`Bytes::ref_count`]. An `RcBytes` wraps its
+/// buffer once in an [`Rc`], its clones and slices then only bump a plain counter. This suits
+/// pipelines which never send their buffers to another thread, `RcBytes` is neither `Send` nor
+/// `Sync`.
+///
+/// Converting between `Bytes` and `RcBytes` never copies the bytes. An `RcBytes` is built with
+/// an [`RcBytesMut`] and turned back into one with [`RcBytes::try_into_mut`].
+///
+/// # Example
+///
+/// ```
+/// use bytes::{Bytes, RcBytes};
+///
+/// let mut line = RcBytes::from(b"GET /index.html HTTP/1.1".to_vec());
+/// let method = line.split_to(3);
+///
+/// assert_eq!(method, "GET");
+/// assert_eq!(line.ref_count(), Some(2));
+///
+/// let bytes = Bytes::from(method);
+/// assert_eq!(bytes, "GET");
+/// ```
+pub struct RcBytes {
+    /// A pointer to the viewed bytes, into the buffer of `owner`
+    ptr: *const u8,
+
+    /// The len of the viewed bytes
+    len: usize,
+
+    /// The buffer shared by the clones, `None` for static memory
+    owner: Option<Rc<Bytes>>,
+}
+
+impl RcBytes {
+    /// Create a new empty `RcBytes`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::RcBytes;
+    ///
+    /// let bytes = RcBytes::new();
+    /// assert!(bytes.is_empty());
+    /// ```
+    #[must_use]
+    #[inline]
+    pub const fn new() -> RcBytes {
+        RcBytes::from_static(&[])
+    }
+
+    /// Create a new `RcBytes` viewing static memory, cloning it doesn't touch any reference count.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::RcBytes;
+    ///
+    /// let bytes = RcBytes::from_static(b"hello");
+    /// assert_eq!(bytes, "hello");
+    /// ```
+    #[must_use]
+    #[inline]
+    pub const fn from_static(src: &'static [u8]) -> RcBytes {
+        RcBytes {
+            ptr: src.as_ptr(),
+            len: src.len(),
+            owner: None,
+        }
+    }
+
+    /// Create a new `RcBytes` by copying `src` into a new buffer.
+    #[must_use]
+    pub fn copy_from_slice(src: &[u8]) -> RcBytes {
+        RcBytes::from(Bytes::copy_from_slice(src))
+    }
+
+    /// Return the number of bytes.
+    #[must_use]
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Return `true` if there is no bytes.
+    #[must_use]
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Return the bytes as a slice.
+    #[must_use]
+    #[inline]
+    pub const fn as_slice(&self) -> &[u8] {
+        // Safety: the bytes are kept alive by `owner` or are static
+        unsafe { slice::from_raw_parts(self.ptr, self.len) }
+    }
+
+    /// Return the number of `RcBytes` sharing the buffer of `self`, `None` for static memory.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::RcBytes;
+    ///
+    /// let bytes = RcBytes::from(b"hello".to_vec());
+    /// let clone = bytes.clone();
+    ///
+    /// assert_eq!(bytes.ref_count(), Some(2));
+    /// drop(clone);
+    /// assert_eq!(bytes.ref_count(), Some(1));
+    ///
+    /// assert_eq!(RcBytes::from_static(b"static").ref_count(), None);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn ref_count(&self) -> Option<usize> {
+        self.owner.as_ref().map(Rc::strong_count)
+    }
+
+    /// Get a subslice of the `RcBytes` sharing its buffer.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::RcBytes;
+    ///
+    /// let bytes = RcBytes::from(b"hello world".to_vec());
+    /// assert_eq!(bytes.slice(6..), "world");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This panics if the start of the range is greater than its end or if its end is greater
+    /// than the len of the `RcBytes`.
+    #[must_use]
+    pub fn slice(&self, range: impl RangeBounds<usize>) -> RcBytes {
+        let bounds = (range.start_bound().cloned(), range.end_bound().cloned());
+        let subset = &self.as_slice()[bounds];
+
+        if subset.is_empty() {
+            return RcBytes::new();
+        }
+
+        RcBytes {
+            ptr: subset.as_ptr(),
+            len: subset.len(),
+            owner: self.owner.clone(),
+        }
+    }
+
+    /// Split the bytes into two at the given position. Afterwards, `self` contains the elements
+    /// from `0` to `at` and the returned value contains the elements from `at` to the end.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if `at` > `self.len()`
+    #[must_use = "consider RcBytes::truncate if you don't need the other half"]
+    pub fn split_off(&mut self, at: usize) -> RcBytes {
+        let ret = self.slice(at..);
+        self.truncate(at);
+        ret
+    }
+
+    /// Split the bytes into two at the given position. Afterwards, `self` contains the elements
+    /// from `at` to the end and the returned value contains the elements from `0` to `at`.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if `at` > `self.len()`
+    #[must_use = "consider RcBytes::advance if you don't need the other half"]
+    pub fn split_to(&mut self, at: usize) -> RcBytes {
+        let ret = self.slice(..at);
+        self.advance(at);
+        ret
+    }
+
+    /// Shorten the buffer to keep the first `len` bytes, doing nothing if `len` is greater than
+    /// the current len.
+    pub fn truncate(&mut self, len: usize) {
+        if len < self.len {
+            self.len = len;
+            self.normalize_empty();
+        }
+    }
+
+    /// Clear the buffer, releasing its share of the underlying memory.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    /// Try to convert `self` into an `RcBytesMut` reusing its buffer, like
+    /// [`Bytes::try_into_mut`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::{BufMut, RcBytes};
+    ///
+    /// let bytes = RcBytes::from(b"hello".to_vec());
+    /// let clone = bytes.clone();
+    ///
+    /// let bytes = bytes.try_into_mut().unwrap_err();
+    /// drop(clone);
+    ///
+    /// let mut bytes_mut = bytes.try_into_mut().unwrap();
+    /// bytes_mut.put_slice(b" world");
+    /// assert_eq!(bytes_mut.freeze(), "hello world");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `self` if its buffer is shared with other `RcBytes` or `Bytes`, or if
+    /// [`Bytes::try_into_mut`] fails on it.
+    pub fn try_into_mut(self) -> Result<RcBytesMut, RcBytes> {
+        if self.is_empty() {
+            return Ok(RcBytesMut::new());
+        }
+
+        if self.ref_count() != Some(1) {
+            return Err(self);
+        }
+
+        Bytes::from(self)
+            .try_into_mut()
+            .map(RcBytesMut::from)
+            .map_err(RcBytes::from)
+    }
+
+    /// Release the buffer of an empty `RcBytes`, like [`Bytes`] does.
+    fn normalize_empty(&mut self) {
+        if self.len == 0 {
+            *self = RcBytes::new();
+        }
+    }
+}
+
+impl Clone for RcBytes {
+    #[inline]
+    fn clone(&self) -> RcBytes {
+        RcBytes {
+            ptr: self.ptr,
+            len: self.len,
+            owner: self.owner.clone(),
+        }
+    }
+}
+
+impl Default for RcBytes {
+    #[inline]
+    fn default() -> RcBytes {
+        RcBytes::new()
+    }
+}
+
+impl Buf for RcBytes {
+    #[inline]
+    fn remaining(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    fn chuncks(&self) -> &[u8] {
+        self.as_slice()
+    }
+
+    #[inline]
+    fn remaining_slice(&self) -> Option<&[u8]> {
+        Some(self.as_slice())
+    }
+
+    fn fork(&self) -> Bytes {
+        Bytes::from(self.clone())
+    }
+
+    fn copy_to_bytes(&mut self, len: usize) -> Bytes {
+        Bytes::from(self.split_to(len))
+    }
+
+    #[inline]
+    fn advance(&mut self, cnt: usize) {
+        assert!(
+            cnt <= self.len,
+            "cannot advance past the end of the buffer: len ({}) < count ({})",
+            self.len,
+            cnt
+        );
+
+        self.ptr = self.ptr.wrapping_add(cnt);
+        self.len -= cnt;
+        self.normalize_empty();
+    }
+}
+
+impl Deref for RcBytes {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl AsRef<[u8]> for RcBytes {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl Borrow<[u8]> for RcBytes {
+    #[inline]
+    fn borrow(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl std::hash::Hash for RcBytes {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.as_slice().hash(state)
+    }
+}
+
+impl PartialEq for RcBytes {
+    fn eq(&self, other: &RcBytes) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl Eq for RcBytes {}
+
+impl PartialOrd for RcBytes {
+    fn partial_cmp(&self, other: &RcBytes) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for RcBytes {
+    fn cmp(&self, other: &RcBytes) -> cmp::Ordering {
+        self.as_slice().cmp(other.as_slice())
+    }
+}
+
+impl PartialEq<[u8]> for RcBytes {
+    fn eq(&self, other: &[u8]) -> bool {
+        self.as_slice() == other
+    }
+}
+
+impl<'a> PartialEq<&'a [u8]> for RcBytes {
+    fn eq(&self, other: &&'a [u8]) -> bool {
+        self.as_slice() == *other
+    }
+}
+
+impl PartialEq<str> for RcBytes {
+    fn eq(&self, other: &str) -> bool {
+        self.as_slice() == other.as_bytes()
+    }
+}
+
+impl<'a> PartialEq<&'a str> for RcBytes {
+    fn eq(&self, other: &&'a str) -> bool {
+        self.as_slice() == other.as_bytes()
+    }
+}
+
+impl PartialEq<Bytes> for RcBytes {
+    fn eq(&self, other: &Bytes) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl PartialEq<RcBytes> for Bytes {
+    fn eq(&self, other: &RcBytes) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+// === From ===
+
+impl From<Bytes> for RcBytes {
+    /// Wrap the buffer of `value` in an `RcBytes` without copying it. Static memory is viewed
+    /// directly, otherwise `value` is moved in a new `Rc`.
+    fn from(value: Bytes) -> RcBytes {
+        if let Some(src) = value.as_static() {
+            return RcBytes::from_static(src);
+        }
+
+        RcBytes {
+            ptr: value.as_ptr(),
+            len: value.len(),
+            owner: Some(Rc::new(value)),
+        }
+    }
+}
+
+impl From<Vec<u8>> for RcBytes {
+    #[inline]
+    fn from(value: Vec<u8>) -> RcBytes {
+        RcBytes::from(Bytes::from(value))
+    }
+}
+
+impl From<String> for RcBytes {
+    #[inline]
+    fn from(value: String) -> RcBytes {
+        RcBytes::from(Bytes::from(value))
+    }
+}
+
+impl From<&'static [u8]> for RcBytes {
+    #[inline]
+    fn from(value: &'static [u8]) -> RcBytes {
+        RcBytes::from_static(value)
+    }
+}
+
+impl From<&'static str> for RcBytes {
+    #[inline]
+    fn from(value: &'static str) -> RcBytes {
+        RcBytes::from_static(value.as_bytes())
+    }
+}
+
+impl From<RcBytes> for Bytes {
+    /// Convert an `RcBytes` into a `Bytes` viewing the same bytes without copying them. The
+    /// underlying `Bytes` is moved out of the `Rc` if `value` was its only owner and cloned
+    /// otherwise.
+    fn from(value: RcBytes) -> Bytes {
+        let Some(owner) = value.owner else {
+            // Safety: an `RcBytes` without owner views static memory
+            return Bytes::from_static(unsafe { slice::from_raw_parts(value.ptr, value.len) });
+        };
+
+        let mut bytes = Rc::try_unwrap(owner).unwrap_or_else(|owner| Bytes::clone(&owner));
+
+        if !ptr::eq(bytes.as_ptr(), value.ptr) || bytes.len() != value.len {
+            bytes.advance(value.ptr as usize - bytes.as_ptr() as usize);
+            bytes.truncate(value.len);
+        }
+
+        bytes
+    }
+}
+
+// === RcBytesMut ===
+
+/// The mutable counterpart of [`RcBytes`]: a growable buffer frozen into an `RcBytes` once
+/// written.
+///
+/// A [`BytesMut`] uniquely owns its buffer and doesn't count references, an `RcBytesMut` wraps
+/// one and only ties it to the current thread so that the single-threaded pipelines can build
+/// their buffers without converting them. Converting between `BytesMut` and `RcBytesMut` is
+/// free.
+///
+/// # Example
+///
+/// ```
+/// use bytes::{BufMut, RcBytesMut};
+///
+/// let mut buf = RcBytesMut::with_capacity(16);
+/// buf.put_slice(b"hello");
+/// buf.put_u8(b'!');
+///
+/// let frozen = buf.freeze();
+/// let clone = frozen.clone();
+///
+/// assert_eq!(clone, "hello!");
+/// assert_eq!(frozen.ref_count(), Some(2));
+/// ```
+pub struct RcBytesMut {
+    inner: BytesMut,
+
+    /// An `RcBytesMut` is frozen into an `RcBytes` so it's neither `Send` nor `Sync` either
+    _local: PhantomData<*const ()>,
+}
+
+impl RcBytesMut {
+    /// Create a new empty `RcBytesMut` without allocating.
+    #[must_use]
+    #[inline]
+    pub const fn new() -> RcBytesMut {
+        RcBytesMut {
+            inner: BytesMut::new(),
+            _local: PhantomData,
+        }
+    }
+
+    /// Create an empty `RcBytesMut` with the given capacity, see [`BytesMut::with_capacity`].
+    ///
+    /// # Panics
+    ///
+    /// This panics if `cap` exceeds `isize::MAX` or the memory budget.
+    #[must_use]
+    #[inline]
+    pub fn with_capacity(cap: usize) -> RcBytesMut {
+        RcBytesMut::from(BytesMut::with_capacity(cap))
+    }
+
+    /// Return the number of bytes written to the buffer.
+    #[must_use]
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Return `true` if no bytes were written to the buffer.
+    #[must_use]
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Return the number of bytes the buffer can hold without reallocating.
+    #[must_use]
+    #[inline]
+    pub const fn capacity(&self) -> usize {
+        self.inner.capacity()
+    }
+
+    /// Append `b` to the buffer, growing it if needed.
+    #[inline]
+    pub fn push(&mut self, b: u8) {
+        self.inner.push(b)
+    }
+
+    /// Remove the last byte of the buffer and return it, `None` if it is empty.
+    #[inline]
+    pub fn pop(&mut self) -> Option<u8> {
+        self.inner.pop()
+    }
+
+    /// Append `slice` to the buffer, growing it if needed.
+    #[inline]
+    pub fn extend_from_slice(&mut self, slice: &[u8]) {
+        self.inner.extend_from_slice(slice)
+    }
+
+    /// Reserve the capacity for at least `res` more bytes, see [`BytesMut::reserve`].
+    ///
+    /// # Panics
+    ///
+    /// This panics if the new capacity exceeds `isize::MAX` or the memory budget.
+    #[inline]
+    pub fn reserve(&mut self, res: usize) {
+        self.inner.reserve(res)
+    }
+
+    /// Convert `self` into an immutable `RcBytes` reusing its buffer.
+    #[must_use]
+    #[inline]
+    pub fn freeze(self) -> RcBytes {
+        RcBytes::from(self.inner.freeze())
+    }
+}
+
+impl Default for RcBytesMut {
+    #[inline]
+    fn default() -> RcBytesMut {
+        RcBytesMut::new()
+    }
+}
+
+impl BufMut for RcBytesMut {
+    #[inline]
+    fn remaining_mut(&self) -> usize {
+        self.inner.remaining_mut()
+    }
+
+    #[inline]
+    unsafe fn advance(&mut self, count: usize) {
+        self.inner.advance(count)
+    }
+
+    #[inline]
+    fn chuncks_mut(&mut self) -> &mut UninitSlice {
+        self.inner.chuncks_mut()
+    }
+
+    #[inline]
+    fn put_slice(&mut self, src: &[u8]) {
+        self.inner.put_slice(src)
+    }
+
+    #[inline]
+    fn put_u8(&mut self, byte: u8) {
+        self.inner.put_u8(byte)
+    }
+
+    #[inline]
+    fn reserve_hint(&mut self, additional: usize) {
+        self.inner.reserve_hint(additional)
+    }
+}
+
+impl AsRef<[u8]> for RcBytesMut {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self.inner.as_ref()
+    }
+}
+
+impl From<BytesMut> for RcBytesMut {
+    #[inline]
+    fn from(value: BytesMut) -> RcBytesMut {
+        RcBytesMut {
+            inner: value,
+            _local: PhantomData,
+        }
+    }
+}
+
+impl From<RcBytesMut> for BytesMut {
+    #[inline]
+    fn from(value: RcBytesMut) -> BytesMut {
+        value.inner
+    }
+}
+
+impl From<RcBytesMut> for RcBytes {
+    #[inline]
+    fn from(value: RcBytesMut) -> RcBytes {
+        value.freeze()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_alloc;
+
+    #[test]
+    fn clone_and_slice() {
+        let vec = b"hello world".to_vec();
+        let ptr = vec.as_ptr();
+
+        let (mut bytes, allocs) = test_alloc::count_allocs(|| RcBytes::from(vec));
+        assert_eq!(allocs, 1);
+        assert_eq!(bytes.as_ptr(), ptr);
+
+        let (world, allocs) = test_alloc::count_allocs(|| bytes.split_off(6));
+        assert_eq!(allocs, 0);
+        assert_eq!(bytes, "hello ");
+        assert_eq!(world, "world");
+        assert_eq!(world.ref_count(), Some(2));
+
+        // The shared `Bytes` is never cloned so it is never promoted
+        assert_eq!(bytes.owner.as_deref().unwrap().ref_count(), Some(1));
+
+        bytes.clear();
+        assert_eq!(world.ref_count(), Some(1));
+        assert_eq!(bytes.ref_count(), None);
+
+        assert_eq!(world.slice(1..1), RcBytes::new());
+        assert_eq!(world.slice(1..1).ref_count(), None);
+    }
+
+    #[test]
+    fn into_bytes() {
+        let bytes = RcBytes::from(b"hello world".to_vec());
+        let world = bytes.slice(6..);
+
+        // `bytes` is still alive so the underlying `Bytes` is cloned
+        let shared = Bytes::from(world.clone());
+        assert_eq!(shared, "world");
+        assert_eq!(shared.as_ptr(), world.as_ptr());
+
+        drop(bytes);
+        drop(shared);
+
+        // `world` is the only owner so the underlying `Bytes` is moved out
+        let ptr = world.as_ptr();
+        let (unique, allocs) = test_alloc::count_allocs(|| Bytes::from(world));
+        assert_eq!(unique, "world");
+        assert_eq!(unique.as_ptr(), ptr);
+        assert_eq!(allocs, 0);
+
+        let bytes = Bytes::from(RcBytes::from_static(b"static"));
+        assert!(bytes.is_static());
+
+        let bytes = RcBytes::from(Bytes::from_static(b"static"));
+        assert_eq!(bytes.ref_count(), None);
+    }
+
+    #[test]
+    fn try_into_mut() {
+        let mut buf = RcBytesMut::with_capacity(32);
+        buf.put_slice(b"hello world");
+        let ptr = buf.as_ref().as_ptr();
+
+        let mut bytes = buf.freeze();
+        let hello = bytes.split_to(5);
+
+        let bytes = bytes.try_into_mut().unwrap_err();
+        drop(hello);
+
+        // The bytes are moved to the start of the buffer, which is reused
+        let bytes_mut = bytes.try_into_mut().unwrap();
+        assert_eq!(bytes_mut.as_ref(), b" world");
+        assert_eq!(bytes_mut.as_ref().as_ptr(), ptr);
+
+        // A buffer also viewed by a `Bytes` is not unique
+        let shared = Bytes::from(b"shared".to_vec());
+        let bytes = RcBytes::from(shared.clone());
+        assert_eq!(bytes.try_into_mut().unwrap_err(), "shared");
+
+        assert!(RcBytes::from_static(b"static").try_into_mut().is_err());
+        assert!(RcBytes::new().try_into_mut().unwrap().is_empty());
+    }
+}
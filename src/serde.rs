@@ -0,0 +1,175 @@
+//! `serde` support for `Bytes` and `BytesMut`, enabled by the `serde` feature.
+//!
+//! The buffers are serialized with `serialize_bytes` and deserialized with
+//! `deserialize_byte_buf`, so binary formats write them as a single byte string instead of a
+//! sequence of integers. Formats without a byte string type, like JSON, still write a sequence,
+//! which is accepted when deserializing along with strings.
+//!
+//! A `Bytes` deserialized from an owned byte buffer reuses its allocation, the other inputs are
+//! copied.
+//!
+//! # Example
+//!
+//! ```
+//! use bytes::Bytes;
+//!
+//! #[derive(serde::Serialize, serde::Deserialize)]
+//! struct Message {
+//!     id: u32,
+//!     payload: Bytes,
+//! }
+//!
+//! let message = Message {
+//!     id: 7,
+//!     payload: Bytes::from_static(b"\x00\x2a"),
+//! };
+//!
+//! let json = serde_json::to_string(&message).unwrap();
+//! assert_eq!(json, r#"{"id":7,"payload":[0,42]}"#);
+//!
+//! let message: Message = serde_json::from_str(&json).unwrap();
+//! assert_eq!(message.payload, b"\x00\x2a"[..]);
+//! ```
+
+use core::{cmp, fmt};
+
+use alloc::{string::String, vec::Vec};
+use serde::de::{Deserialize, Deserializer, Error, SeqAccess, Visitor};
+use serde::ser::{Serialize, Serializer};
+
+use crate::{BufMut, Bytes, BytesMut};
+
+/// The greatest capacity reserved upfront from the size hint of a sequence, the hint comes from
+/// the input and can't be trusted.
+const MAX_PREALLOC: usize = 4096;
+
+impl Serialize for Bytes {
+    #[inline]
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(self.as_slice())
+    }
+}
+
+impl Serialize for BytesMut {
+    #[inline]
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(self.as_ref())
+    }
+}
+
+impl<'de> Deserialize<'de> for Bytes {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Bytes, D::Error> {
+        deserializer.deserialize_byte_buf(BytesVisitor)
+    }
+}
+
+impl<'de> Deserialize<'de> for BytesMut {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<BytesMut, D::Error> {
+        deserializer.deserialize_byte_buf(BytesMutVisitor)
+    }
+}
+
+struct BytesVisitor;
+
+impl<'de> Visitor<'de> for BytesVisitor {
+    type Value = Bytes;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a byte string")
+    }
+
+    fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<Bytes, E> {
+        Ok(Bytes::copy_from_slice(v))
+    }
+
+    fn visit_byte_buf<E: Error>(self, v: Vec<u8>) -> Result<Bytes, E> {
+        Ok(Bytes::from(v))
+    }
+
+    fn visit_str<E: Error>(self, v: &str) -> Result<Bytes, E> {
+        Ok(Bytes::copy_from_slice(v.as_bytes()))
+    }
+
+    fn visit_string<E: Error>(self, v: String) -> Result<Bytes, E> {
+        Ok(Bytes::from(v))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Bytes, A::Error> {
+        let cap = cmp::min(seq.size_hint().unwrap_or(0), MAX_PREALLOC);
+        let mut vec = Vec::with_capacity(cap);
+
+        while let Some(byte) = seq.next_element()? {
+            vec.push(byte);
+        }
+
+        Ok(Bytes::from(vec))
+    }
+}
+
+struct BytesMutVisitor;
+
+impl<'de> Visitor<'de> for BytesMutVisitor {
+    type Value = BytesMut;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a byte string")
+    }
+
+    fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<BytesMut, E> {
+        let mut buf = BytesMut::with_capacity(v.len());
+        buf.extend_from_slice(v);
+        Ok(buf)
+    }
+
+    fn visit_str<E: Error>(self, v: &str) -> Result<BytesMut, E> {
+        self.visit_bytes(v.as_bytes())
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<BytesMut, A::Error> {
+        let cap = cmp::min(seq.size_hint().unwrap_or(0), MAX_PREALLOC);
+        let mut buf = BytesMut::with_capacity(cap);
+
+        while let Some(byte) = seq.next_element::<u8>()? {
+            buf.put_u8(byte);
+        }
+
+        Ok(buf)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_test::{assert_de_tokens, assert_tokens, Token};
+
+    use super::*;
+
+    #[test]
+    fn tokens() {
+        let bytes = Bytes::from_static(b"\x00\xffabc");
+
+        assert_tokens(&bytes, &[Token::Bytes(b"\x00\xffabc")]);
+        assert_de_tokens(&bytes, &[Token::ByteBuf(b"\x00\xffabc")]);
+        assert_de_tokens(&bytes, &[Token::BorrowedBytes(b"\x00\xffabc")]);
+        assert_de_tokens(&Bytes::from_static(b"abc"), &[Token::Str("abc")]);
+        assert_de_tokens(
+            &Bytes::from_static(b"\x01\x02"),
+            &[
+                Token::Seq { len: Some(2) },
+                Token::U8(1),
+                Token::U8(2),
+                Token::SeqEnd,
+            ],
+        );
+    }
+
+    #[test]
+    fn bytes_mut() {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"hello");
+
+        serde_test::assert_ser_tokens(&buf, &[Token::Bytes(b"hello")]);
+
+        let de: BytesMut = serde_json::from_str("[104,105]").unwrap();
+        assert_eq!(de.as_ref(), b"hi");
+    }
+}
@@ -0,0 +1,240 @@
+use core::mem;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+use crate::buf::{BufMut, UninitSlice};
+use crate::bytes::{release_shared, shallow_clone_arc, Shared};
+use crate::Bytes;
+
+/// An append-only buffer with a single writer and many readers.
+///
+/// The writer appends bytes through the `BufMut` trait while readers, created with
+/// [`SharedAppendBuf::reader`], take [`Bytes`] snapshots of the bytes written so far without
+/// locking. A snapshot is a consistent prefix of the buffer: it never sees a partial write and
+/// it is never modified afterwards.
+///
+/// The buffer has a fixed capacity, the underlying allocation is never moved so that snapshots
+/// stay valid.
+///
+/// # Example
+///
+/// ```
+/// use bytes::{BufMut, SharedAppendBuf};
+///
+/// let mut log = SharedAppendBuf::with_capacity(64);
+/// let reader = log.reader();
+///
+/// log.put_slice(b"first line\n");
+/// let snapshot = reader.snapshot();
+///
+/// log.put_slice(b"second line\n");
+///
+/// assert_eq!(snapshot, b"first line\n"[..]);
+/// assert_eq!(reader.snapshot(), b"first line\nsecond line\n"[..]);
+/// ```
+pub struct SharedAppendBuf {
+    log: Arc<Log>,
+    len: usize,
+}
+
+/// A handle to take snapshots of a [`SharedAppendBuf`].
+#[derive(Clone)]
+pub struct SharedAppendReader {
+    log: Arc<Log>,
+}
+
+struct Log {
+    shared: *mut Shared,
+    /// The number of bytes published by the writer
+    len: AtomicUsize,
+}
+
+impl SharedAppendBuf {
+    /// Create a new `SharedAppendBuf` able to hold `cap` bytes.
+    pub fn with_capacity(cap: usize) -> SharedAppendBuf {
+        let mut buf = mem::ManuallyDrop::new(Vec::<u8>::with_capacity(cap));
+
+        let shared = Box::into_raw(Box::new(Shared::new(buf.as_mut_ptr(), buf.capacity(), 1)));
+
+        SharedAppendBuf {
+            log: Arc::new(Log {
+                shared,
+                len: AtomicUsize::new(0),
+            }),
+            len: 0,
+        }
+    }
+
+    /// Return the number of bytes written.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Return `true` if nothing has been written yet.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Return the total capacity of the buffer.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.log.cap()
+    }
+
+    /// Create a new reader of `self`.
+    #[inline]
+    pub fn reader(&self) -> SharedAppendReader {
+        SharedAppendReader {
+            log: self.log.clone(),
+        }
+    }
+
+    /// Return a `Bytes` containing all the bytes written so far.
+    #[inline]
+    pub fn snapshot(&self) -> Bytes {
+        self.log.snapshot(self.len)
+    }
+}
+
+impl SharedAppendReader {
+    /// Return a `Bytes` containing all the bytes published by the writer so far. The returned
+    /// value shares the underlying buffer of the writer.
+    #[inline]
+    pub fn snapshot(&self) -> Bytes {
+        // Synchronizes with the `Release` store of the writer so that the published bytes are
+        // visible
+        let len = self.log.len.load(Ordering::Acquire);
+        self.log.snapshot(len)
+    }
+}
+
+impl Log {
+    #[inline]
+    fn cap(&self) -> usize {
+        unsafe { (*self.shared).cap }
+    }
+
+    fn snapshot(&self, len: usize) -> Bytes {
+        if len == 0 {
+            return Bytes::new();
+        }
+
+        // SAFETY:
+        // `self` holds a reference on `self.shared` so it is still alive and the `len` first
+        // bytes of the buffer are initialized and never written again
+        unsafe { shallow_clone_arc(self.shared, (*self.shared).buf, len) }
+    }
+}
+
+impl Drop for Log {
+    fn drop(&mut self) {
+        unsafe { release_shared(self.shared) }
+    }
+}
+
+// SAFETY:
+// The bytes are only written by the unique `SharedAppendBuf` in the unpublished part of the
+// buffer, and readers only access the published part.
+unsafe impl Send for Log {}
+
+unsafe impl Sync for Log {}
+
+// The writer publishes its length with a single atomic store after the bytes are written, so a
+// panic while writing never exposes uninitialized bytes to the readers.
+const _: () = crate::assert_unwind_safe::<SharedAppendBuf>();
+const _: () = crate::assert_unwind_safe::<SharedAppendReader>();
+
+impl BufMut for SharedAppendBuf {
+    #[inline]
+    fn remaining_mut(&self) -> usize {
+        self.log.cap() - self.len
+    }
+
+    fn chuncks_mut(&mut self) -> &mut UninitSlice {
+        let cap = self.log.cap();
+
+        // SAFETY:
+        // The bytes after `self.len` are never accessed by the readers
+        unsafe {
+            let ptr = (*self.log.shared).buf.add(self.len);
+            UninitSlice::from_raw_parts(ptr, cap - self.len)
+        }
+    }
+
+    unsafe fn advance(&mut self, count: usize) {
+        let rem = self.remaining_mut();
+
+        assert!(
+            count <= rem,
+            "not enough space to advance: remaining ({}) < count ({})",
+            rem,
+            count
+        );
+
+        self.len += count;
+        self.log.len.store(self.len, Ordering::Release);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn snapshot_outlives_writer() {
+        let mut log = SharedAppendBuf::with_capacity(8);
+        let reader = log.reader();
+
+        log.put_slice(b"abc");
+        let snapshot = log.snapshot();
+        drop(log);
+
+        assert_eq!(snapshot, b"abc"[..]);
+        assert_eq!(reader.snapshot(), b"abc"[..]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn full() {
+        let mut log = SharedAppendBuf::with_capacity(2);
+
+        log.put_slice(b"abc");
+    }
+
+    #[test]
+    fn concurrent_readers() {
+        const LINE: &[u8] = b"0123456789";
+
+        let mut log = SharedAppendBuf::with_capacity(LINE.len() * 100);
+
+        let readers: Vec<_> = (0..4)
+            .map(|_| {
+                let reader = log.reader();
+                std::thread::spawn(move || loop {
+                    let snapshot = reader.snapshot();
+
+                    assert_eq!(snapshot.len() % LINE.len(), 0);
+                    for line in snapshot.chunks(LINE.len()) {
+                        assert_eq!(line, LINE);
+                    }
+
+                    if snapshot.len() == LINE.len() * 100 {
+                        break;
+                    }
+                })
+            })
+            .collect();
+
+        for _ in 0..100 {
+            log.put_slice(LINE);
+        }
+
+        for reader in readers {
+            reader.join().unwrap();
+        }
+    }
+}
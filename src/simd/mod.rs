@@ -0,0 +1,325 @@
+//! Vectorized implementations of the byte scanning and conversion routines of the crate.
+//!
+//! Every routine has a portable scalar implementation. When the `simd` feature is enabled on
+//! `x86_64`, the fastest implementation supported by the running CPU is selected at runtime so
+//! that binaries stay portable. The selection can be overridden with [`set_force_scalar`], e.g.
+//! to get reproducible benchmarks or to rule out a vectorized path while debugging.
+//!
+//! # Example
+//!
+//! ```
+//! use bytes::simd;
+//!
+//! assert_eq!(simd::memchr(b'\n', b"GET / HTTP/1.1\r\n"), Some(15));
+//! assert_eq!(simd::count(b'/', b"GET / HTTP/1.1\r\n"), 2);
+//! ```
+
+use core::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+
+mod scalar;
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+mod x86;
+
+/// The instruction sets usable by the routines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    /// Portable scalar code.
+    Scalar,
+    /// SSE2 and SSSE3 instructions.
+    Sse,
+    /// AVX2 instructions.
+    Avx2,
+}
+
+static FORCE_SCALAR: AtomicBool = AtomicBool::new(false);
+
+/// The detected level, `0` if the detection didn't run yet.
+static DETECTED: AtomicU8 = AtomicU8::new(0);
+
+/// Force the use of the scalar implementations, even if the CPU supports a faster instruction
+/// set.
+#[inline]
+pub fn set_force_scalar(force: bool) {
+    FORCE_SCALAR.store(force, Ordering::Relaxed)
+}
+
+/// Return the instruction set currently used by the routines.
+pub fn level() -> Level {
+    if FORCE_SCALAR.load(Ordering::Relaxed) {
+        return Level::Scalar;
+    }
+
+    match DETECTED.load(Ordering::Relaxed) {
+        1 => Level::Scalar,
+        2 => Level::Sse,
+        3 => Level::Avx2,
+        _ => {
+            let level = detect();
+            DETECTED.store(level as u8 + 1, Ordering::Relaxed);
+            level
+        }
+    }
+}
+
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+fn detect() -> Level {
+    if std::is_x86_feature_detected!("avx2") {
+        Level::Avx2
+    } else if std::is_x86_feature_detected!("ssse3") {
+        Level::Sse
+    } else {
+        Level::Scalar
+    }
+}
+
+#[cfg(not(all(feature = "simd", target_arch = "x86_64")))]
+fn detect() -> Level {
+    Level::Scalar
+}
+
+/// Dispatch a call to the implementation matching the current level.
+macro_rules! dispatch {
+    ($name:ident($($arg:expr),*)) => {{
+        #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+        {
+            // SAFETY:
+            // The level is only higher than `Level::Scalar` if the CPU supports the required
+            // instruction set
+            match level() {
+                Level::Avx2 => return unsafe { x86::avx2::$name($($arg),*) },
+                Level::Sse => return unsafe { x86::sse::$name($($arg),*) },
+                Level::Scalar => (),
+            }
+        }
+
+        scalar::$name($($arg),*)
+    }};
+}
+
+/// Return the index of the first occurrence of `needle` in `haystack`.
+#[inline]
+pub fn memchr(needle: u8, haystack: &[u8]) -> Option<usize> {
+    dispatch!(memchr(needle, haystack))
+}
+
+/// Return the index of the first occurrence of the subsequence `needle` in `haystack`. An empty
+/// needle is found at index `0`.
+///
+/// The candidates are located with [`memchr`] on the first byte of the needle, so the search
+/// runs at the speed of `memchr` unless that byte is frequent in `haystack`.
+pub fn find(needle: &[u8], haystack: &[u8]) -> Option<usize> {
+    let (&first, rest) = match needle.split_first() {
+        Some(split) => split,
+        None => return Some(0),
+    };
+
+    if needle.len() > haystack.len() {
+        return None;
+    }
+
+    // The last position where the needle fits
+    let last = haystack.len() - needle.len();
+    let mut pos = 0;
+
+    while pos <= last {
+        pos += memchr(first, &haystack[pos..=last])?;
+
+        if haystack[pos + 1..pos + needle.len()] == *rest {
+            return Some(pos);
+        }
+        pos += 1;
+    }
+
+    None
+}
+
+/// The greatest number of needles compared in a single pass by the vectorized [`find_any`], the
+/// scalar implementation is used for bigger sets.
+const FIND_ANY_MAX: usize = 8;
+
+/// Return the index of the first byte of `haystack` which is one of `needles`.
+#[inline]
+pub fn find_any(needles: &[u8], haystack: &[u8]) -> Option<usize> {
+    match needles {
+        [] => None,
+        [needle] => memchr(*needle, haystack),
+        _ if needles.len() > FIND_ANY_MAX => {
+            let mut table = [false; 256];
+            for b in needles {
+                table[*b as usize] = true;
+            }
+
+            let span = scalar::span_of(&table.map(|found| !found), haystack);
+            (span < haystack.len()).then_some(span)
+        }
+        _ => dispatch!(find_any(needles, haystack)),
+    }
+}
+
+/// Return the len of the longest prefix of `haystack` made of bytes `b` for which `table[b]` is
+/// `true`.
+///
+/// The table lookups don't have a vectorized implementation yet, this always runs the scalar
+/// implementation.
+#[inline]
+pub fn span_of(table: &[bool; 256], haystack: &[u8]) -> usize {
+    scalar::span_of(table, haystack)
+}
+
+/// Return the number of occurrences of `needle` in `haystack`.
+#[inline]
+pub fn count(needle: u8, haystack: &[u8]) -> usize {
+    dispatch!(count(needle, haystack))
+}
+
+/// Convert the ASCII lowercase letters of `buf` to uppercase in place.
+#[inline]
+pub fn make_ascii_uppercase(buf: &mut [u8]) {
+    dispatch!(make_ascii_uppercase(buf))
+}
+
+/// Convert the ASCII uppercase letters of `buf` to lowercase in place.
+#[inline]
+pub fn make_ascii_lowercase(buf: &mut [u8]) {
+    dispatch!(make_ascii_lowercase(buf))
+}
+
+/// Write the hexadecimal representation of `src` into `dst`, using lowercase digits unless
+/// `upper` is `true`.
+///
+/// # Panics
+///
+/// This panics if `dst.len() != 2 * src.len()`.
+#[inline]
+pub fn hex_encode(src: &[u8], dst: &mut [u8], upper: bool) {
+    assert!(
+        dst.len() == 2 * src.len(),
+        "invalid destination len: dst ({}) != 2 * src ({})",
+        dst.len(),
+        src.len()
+    );
+
+    dispatch!(hex_encode(src, dst, upper))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Call the implementation of `level` directly, the tests can't rely on `level()` since
+    /// `force_scalar` may override it while they run.
+    macro_rules! call {
+        ($level:expr, $name:ident($($arg:expr),*)) => {{
+            match $level {
+                // SAFETY:
+                // The tests only use the levels up to the detected one
+                #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+                Level::Avx2 => unsafe { x86::avx2::$name($($arg),*) },
+                #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+                Level::Sse => unsafe { x86::sse::$name($($arg),*) },
+                _ => scalar::$name($($arg),*),
+            }
+        }};
+    }
+
+    /// Return a buffer with every byte value and some repetitions
+    fn sample(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i * 7 + i / 3) as u8).collect()
+    }
+
+    /// Return the levels supported by the CPU.
+    fn levels() -> impl Iterator<Item = Level> {
+        [Level::Scalar, Level::Sse, Level::Avx2]
+            .into_iter()
+            .filter(|level| *level <= detect())
+    }
+
+    #[test]
+    fn matches_scalar() {
+        for level in levels() {
+            for len in 0..300 {
+                let buf = sample(len);
+
+                for needle in [0u8, b'a', b'Z', 0xff, 42] {
+                    assert_eq!(
+                        call!(level, memchr(needle, &buf)),
+                        scalar::memchr(needle, &buf)
+                    );
+                    assert_eq!(
+                        call!(level, count(needle, &buf)),
+                        scalar::count(needle, &buf)
+                    );
+                }
+
+                for needles in [&b""[..], b"\r\n", b"\0\xff ", b"0123456789abcdef"] {
+                    assert_eq!(
+                        call!(level, find_any(needles, &buf)),
+                        scalar::find_any(needles, &buf)
+                    );
+                }
+
+                let (mut upper, mut expected) = (buf.clone(), buf.clone());
+                call!(level, make_ascii_uppercase(&mut upper));
+                expected.make_ascii_uppercase();
+                assert_eq!(upper, expected);
+
+                let (mut lower, mut expected) = (buf.clone(), buf.clone());
+                call!(level, make_ascii_lowercase(&mut lower));
+                expected.make_ascii_lowercase();
+                assert_eq!(lower, expected);
+
+                for upper in [false, true] {
+                    let mut hex = vec![0; len * 2];
+                    let mut expected = vec![0; len * 2];
+                    call!(level, hex_encode(&buf, &mut hex, upper));
+                    scalar::hex_encode(&buf, &mut expected, upper);
+                    assert_eq!(hex, expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn find() {
+        let buf = sample(300);
+
+        for needle in [
+            &b""[..],
+            b"a",
+            &buf[10..14],
+            &buf[296..],
+            &buf[..300],
+            b"\0\0\0",
+        ] {
+            let expected = if needle.is_empty() {
+                Some(0)
+            } else {
+                buf.windows(needle.len()).position(|w| w == needle)
+            };
+            assert_eq!(super::find(needle, &buf), expected);
+        }
+
+        assert_eq!(super::find(b"aab", b"aaab"), Some(1));
+        assert_eq!(super::find(b"abc", b"ab"), None);
+    }
+
+    #[test]
+    fn force_scalar() {
+        set_force_scalar(true);
+        assert_eq!(level(), Level::Scalar);
+        assert_eq!(memchr(b'c', b"abc"), Some(2));
+
+        set_force_scalar(false);
+        assert_eq!(level(), detect());
+    }
+
+    #[test]
+    fn scalar_hex() {
+        let mut dst = [0; 6];
+
+        scalar::hex_encode(&[0x0a, 0xbc, 0xff], &mut dst, false);
+        assert_eq!(&dst, b"0abcff");
+        scalar::hex_encode(&[0x0a, 0xbc, 0xff], &mut dst, true);
+        assert_eq!(&dst, b"0ABCFF");
+    }
+}
@@ -0,0 +1,47 @@
+//! Portable implementations of the routines.
+
+pub(super) const HEX_LOWER: &[u8; 16] = b"0123456789abcdef";
+pub(super) const HEX_UPPER: &[u8; 16] = b"0123456789ABCDEF";
+
+#[inline]
+pub(super) fn memchr(needle: u8, haystack: &[u8]) -> Option<usize> {
+    haystack.iter().position(|b| *b == needle)
+}
+
+#[inline]
+pub(super) fn find_any(needles: &[u8], haystack: &[u8]) -> Option<usize> {
+    haystack.iter().position(|b| needles.contains(b))
+}
+
+#[inline]
+pub(super) fn span_of(table: &[bool; 256], haystack: &[u8]) -> usize {
+    haystack
+        .iter()
+        .position(|b| !table[*b as usize])
+        .unwrap_or(haystack.len())
+}
+
+#[inline]
+pub(super) fn count(needle: u8, haystack: &[u8]) -> usize {
+    haystack.iter().filter(|b| **b == needle).count()
+}
+
+#[inline]
+pub(super) fn make_ascii_uppercase(buf: &mut [u8]) {
+    buf.make_ascii_uppercase()
+}
+
+#[inline]
+pub(super) fn make_ascii_lowercase(buf: &mut [u8]) {
+    buf.make_ascii_lowercase()
+}
+
+#[inline]
+pub(super) fn hex_encode(src: &[u8], dst: &mut [u8], upper: bool) {
+    let table = if upper { HEX_UPPER } else { HEX_LOWER };
+
+    for (b, out) in src.iter().zip(dst.chunks_exact_mut(2)) {
+        out[0] = table[(b >> 4) as usize];
+        out[1] = table[(b & 0xf) as usize];
+    }
+}
@@ -0,0 +1,219 @@
+//! `x86_64` implementations of the routines.
+//!
+//! # Safety
+//!
+//! The functions of the `sse` module require the `ssse3` target feature and the functions of
+//! the `avx2` module require the `avx2` target feature.
+
+use core::arch::x86_64::*;
+
+use super::scalar;
+
+pub(super) mod sse {
+    use super::*;
+
+    const LANES: usize = 16;
+
+    #[target_feature(enable = "sse2")]
+    pub(in crate::simd) unsafe fn memchr(needle: u8, haystack: &[u8]) -> Option<usize> {
+        let splat = _mm_set1_epi8(needle as i8);
+        let mut i = 0;
+
+        while i + LANES <= haystack.len() {
+            let block = _mm_loadu_si128(haystack.as_ptr().add(i).cast());
+            let mask = _mm_movemask_epi8(_mm_cmpeq_epi8(block, splat));
+
+            if mask != 0 {
+                return Some(i + mask.trailing_zeros() as usize);
+            }
+            i += LANES;
+        }
+
+        scalar::memchr(needle, &haystack[i..]).map(|pos| i + pos)
+    }
+
+    #[target_feature(enable = "sse2")]
+    pub(in crate::simd) unsafe fn find_any(needles: &[u8], haystack: &[u8]) -> Option<usize> {
+        let mut i = 0;
+
+        while i + LANES <= haystack.len() {
+            let block = _mm_loadu_si128(haystack.as_ptr().add(i).cast());
+            let mut matches = _mm_setzero_si128();
+
+            for &needle in needles {
+                let eq = _mm_cmpeq_epi8(block, _mm_set1_epi8(needle as i8));
+                matches = _mm_or_si128(matches, eq);
+            }
+
+            let mask = _mm_movemask_epi8(matches);
+            if mask != 0 {
+                return Some(i + mask.trailing_zeros() as usize);
+            }
+            i += LANES;
+        }
+
+        scalar::find_any(needles, &haystack[i..]).map(|pos| i + pos)
+    }
+
+    #[target_feature(enable = "sse2")]
+    pub(in crate::simd) unsafe fn count(needle: u8, haystack: &[u8]) -> usize {
+        let splat = _mm_set1_epi8(needle as i8);
+        let mut i = 0;
+        let mut count = 0;
+
+        while i + LANES <= haystack.len() {
+            let block = _mm_loadu_si128(haystack.as_ptr().add(i).cast());
+            let mask = _mm_movemask_epi8(_mm_cmpeq_epi8(block, splat));
+
+            count += mask.count_ones() as usize;
+            i += LANES;
+        }
+
+        count + scalar::count(needle, &haystack[i..])
+    }
+
+    /// Flip the case of the bytes in `first..=first + 25`.
+    #[target_feature(enable = "sse2")]
+    unsafe fn flip_case(buf: &mut [u8], first: u8) {
+        // Bytes in `first..first + 26` are mapped to `-128..-102` in the signed domain
+        let shift = _mm_set1_epi8(0x80u8.wrapping_sub(first) as i8);
+        let bound = _mm_set1_epi8(-128 + 26);
+        let bit = _mm_set1_epi8(0x20);
+        let mut i = 0;
+
+        while i + LANES <= buf.len() {
+            let ptr = buf.as_mut_ptr().add(i).cast();
+            let block = _mm_loadu_si128(ptr);
+            let in_range = _mm_cmplt_epi8(_mm_add_epi8(block, shift), bound);
+
+            _mm_storeu_si128(ptr, _mm_xor_si128(block, _mm_and_si128(in_range, bit)));
+            i += LANES;
+        }
+
+        if first == b'a' {
+            scalar::make_ascii_uppercase(&mut buf[i..])
+        } else {
+            scalar::make_ascii_lowercase(&mut buf[i..])
+        }
+    }
+
+    #[target_feature(enable = "sse2")]
+    pub(in crate::simd) unsafe fn make_ascii_uppercase(buf: &mut [u8]) {
+        flip_case(buf, b'a')
+    }
+
+    #[target_feature(enable = "sse2")]
+    pub(in crate::simd) unsafe fn make_ascii_lowercase(buf: &mut [u8]) {
+        flip_case(buf, b'A')
+    }
+
+    #[target_feature(enable = "ssse3")]
+    pub(in crate::simd) unsafe fn hex_encode(src: &[u8], dst: &mut [u8], upper: bool) {
+        let table = if upper {
+            scalar::HEX_UPPER
+        } else {
+            scalar::HEX_LOWER
+        };
+        let table = _mm_loadu_si128(table.as_ptr().cast());
+        let nibble = _mm_set1_epi8(0x0f);
+        let mut i = 0;
+
+        while i + LANES <= src.len() {
+            let block = _mm_loadu_si128(src.as_ptr().add(i).cast());
+            let hi = _mm_and_si128(_mm_srli_epi16(block, 4), nibble);
+            let lo = _mm_and_si128(block, nibble);
+
+            let hi = _mm_shuffle_epi8(table, hi);
+            let lo = _mm_shuffle_epi8(table, lo);
+
+            let out = dst.as_mut_ptr().add(2 * i);
+            _mm_storeu_si128(out.cast(), _mm_unpacklo_epi8(hi, lo));
+            _mm_storeu_si128(out.add(LANES).cast(), _mm_unpackhi_epi8(hi, lo));
+            i += LANES;
+        }
+
+        scalar::hex_encode(&src[i..], &mut dst[2 * i..], upper)
+    }
+}
+
+pub(super) mod avx2 {
+    use super::*;
+
+    const LANES: usize = 32;
+
+    #[target_feature(enable = "avx2")]
+    pub(in crate::simd) unsafe fn memchr(needle: u8, haystack: &[u8]) -> Option<usize> {
+        let splat = _mm256_set1_epi8(needle as i8);
+        let mut i = 0;
+
+        while i + LANES <= haystack.len() {
+            let block = _mm256_loadu_si256(haystack.as_ptr().add(i).cast());
+            let mask = _mm256_movemask_epi8(_mm256_cmpeq_epi8(block, splat));
+
+            if mask != 0 {
+                return Some(i + mask.trailing_zeros() as usize);
+            }
+            i += LANES;
+        }
+
+        sse::memchr(needle, &haystack[i..]).map(|pos| i + pos)
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub(in crate::simd) unsafe fn find_any(needles: &[u8], haystack: &[u8]) -> Option<usize> {
+        let mut i = 0;
+
+        while i + LANES <= haystack.len() {
+            let block = _mm256_loadu_si256(haystack.as_ptr().add(i).cast());
+            let mut matches = _mm256_setzero_si256();
+
+            for &needle in needles {
+                let eq = _mm256_cmpeq_epi8(block, _mm256_set1_epi8(needle as i8));
+                matches = _mm256_or_si256(matches, eq);
+            }
+
+            let mask = _mm256_movemask_epi8(matches);
+            if mask != 0 {
+                return Some(i + mask.trailing_zeros() as usize);
+            }
+            i += LANES;
+        }
+
+        sse::find_any(needles, &haystack[i..]).map(|pos| i + pos)
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub(in crate::simd) unsafe fn count(needle: u8, haystack: &[u8]) -> usize {
+        let splat = _mm256_set1_epi8(needle as i8);
+        let mut i = 0;
+        let mut count = 0;
+
+        while i + LANES <= haystack.len() {
+            let block = _mm256_loadu_si256(haystack.as_ptr().add(i).cast());
+            let mask = _mm256_movemask_epi8(_mm256_cmpeq_epi8(block, splat));
+
+            count += mask.count_ones() as usize;
+            i += LANES;
+        }
+
+        count + sse::count(needle, &haystack[i..])
+    }
+
+    // The case conversion and the hex encoding are bound by memory accesses, the SSE versions
+    // are used as is.
+
+    #[target_feature(enable = "avx2")]
+    pub(in crate::simd) unsafe fn make_ascii_uppercase(buf: &mut [u8]) {
+        sse::make_ascii_uppercase(buf)
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub(in crate::simd) unsafe fn make_ascii_lowercase(buf: &mut [u8]) {
+        sse::make_ascii_lowercase(buf)
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub(in crate::simd) unsafe fn hex_encode(src: &[u8], dst: &mut [u8], upper: bool) {
+        sse::hex_encode(src, dst, upper)
+    }
+}
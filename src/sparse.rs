@@ -0,0 +1,172 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use crate::bytes::OutOfBounds;
+use crate::Bytes;
+
+/// A buffer of a known len filled by writes at arbitrary offsets, in any order.
+///
+/// The filled ranges are tracked so that the buffer is only turned into a `Bytes` once every
+/// byte was written. This is the reassembly buffer of protocols delivering the pieces of a
+/// payload out of order, e.g. the stream frames of QUIC or the blocks of a BitTorrent piece.
+///
+/// Overlapping writes are allowed, the last write wins.
+///
+/// # Example
+///
+/// ```
+/// use bytes::SparseBytesMut;
+///
+/// let mut buf = SparseBytesMut::new(11);
+///
+/// buf.write(6, b"world").unwrap();
+/// assert_eq!(buf.missing().collect::<Vec<_>>(), [0..6]);
+///
+/// buf.write(0, b"hello ").unwrap();
+/// assert_eq!(buf.try_freeze().ok().unwrap(), "hello world");
+/// ```
+#[derive(Debug, Clone)]
+pub struct SparseBytesMut {
+    buf: Vec<u8>,
+    /// The filled ranges, sorted and neither overlapping nor adjacent.
+    filled: Vec<Range<usize>>,
+}
+
+impl SparseBytesMut {
+    /// Create a new `SparseBytesMut` of `len` bytes, none of them filled.
+    pub fn new(len: usize) -> SparseBytesMut {
+        SparseBytesMut {
+            buf: vec![0; len],
+            filled: Vec::new(),
+        }
+    }
+
+    /// Return the len of the buffer, filled or not.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Return `true` if the buffer has a len of `0`.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.buf.is_empty()
+    }
+
+    /// Copy `src` into the buffer at `offset`.
+    ///
+    /// # Errors
+    ///
+    /// This returns an error if the end of the write is greater than the len of the buffer,
+    /// nothing is written.
+    pub fn write(&mut self, offset: usize, src: &[u8]) -> Result<(), OutOfBounds> {
+        let end = offset.saturating_add(src.len());
+        OutOfBounds::check(end, self.buf.len())?;
+
+        if src.is_empty() {
+            return Ok(());
+        }
+
+        self.buf[offset..end].copy_from_slice(src);
+
+        // Merge the new range with the ranges it overlaps or touches
+        let first = self.filled.partition_point(|range| range.end < offset);
+        let last = self.filled.partition_point(|range| range.start <= end);
+
+        let mut merged = offset..end;
+        if first < last {
+            merged.start = merged.start.min(self.filled[first].start);
+            merged.end = merged.end.max(self.filled[last - 1].end);
+        }
+
+        self.filled.splice(first..last, [merged]);
+        Ok(())
+    }
+
+    /// Return the number of bytes filled.
+    pub fn filled_len(&self) -> usize {
+        self.filled.iter().map(|range| range.len()).sum()
+    }
+
+    /// Return the number of bytes filled from the start of the buffer without any gap, i.e.
+    /// the bytes which could already be consumed in order.
+    pub fn contiguous_len(&self) -> usize {
+        match self.filled.first() {
+            Some(range) if range.start == 0 => range.end,
+            _ => 0,
+        }
+    }
+
+    /// Return `true` if every byte of the buffer was written.
+    #[inline]
+    pub fn is_complete(&self) -> bool {
+        self.contiguous_len() == self.buf.len()
+    }
+
+    /// Return an iterator over the ranges which were not written yet, in order.
+    pub fn missing(&self) -> impl Iterator<Item = Range<usize>> + '_ {
+        let starts = core::iter::once(0).chain(self.filled.iter().map(|range| range.end));
+        let ends = self
+            .filled
+            .iter()
+            .map(|range| range.start)
+            .chain(core::iter::once(self.buf.len()));
+
+        starts
+            .zip(ends)
+            .map(|(start, end)| start..end)
+            .filter(|range| !range.is_empty())
+    }
+
+    /// Convert `self` into a `Bytes` if every byte was written, otherwise `self` is returned.
+    pub fn try_freeze(self) -> Result<Bytes, SparseBytesMut> {
+        if self.is_complete() {
+            Ok(Bytes::from(self.buf))
+        } else {
+            Err(self)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn out_of_order() {
+        let mut buf = SparseBytesMut::new(16);
+
+        buf.write(12, b"mnop").unwrap();
+        buf.write(4, b"efgh").unwrap();
+        assert_eq!(buf.filled, [4..8, 12..16]);
+        assert_eq!(buf.contiguous_len(), 0);
+        assert_eq!(buf.missing().collect::<Vec<_>>(), [0..4, 8..12]);
+
+        // Adjacent and overlapping writes are merged
+        buf.write(8, b"ij").unwrap();
+        buf.write(6, b"ghijkl").unwrap();
+        assert_eq!(buf.filled.len(), 1);
+        assert_eq!(buf.filled[0], 4..16);
+        assert_eq!(buf.filled_len(), 12);
+
+        let mut buf = buf.try_freeze().err().unwrap();
+
+        buf.write(0, b"abcd").unwrap();
+        assert!(buf.is_complete());
+        assert_eq!(buf.missing().count(), 0);
+        assert_eq!(buf.try_freeze().ok().unwrap(), "abcdefghijklmnop");
+    }
+
+    #[test]
+    fn out_of_bounds() {
+        let mut buf = SparseBytesMut::new(4);
+
+        assert!(buf.write(2, b"abc").is_err());
+        assert!(buf.write(usize::MAX, b"a").is_err());
+        assert!(buf.write(4, b"").is_ok());
+        assert_eq!(buf.filled_len(), 0);
+
+        assert!(SparseBytesMut::new(0).is_complete());
+    }
+}
@@ -0,0 +1,214 @@
+//! A global allocator interposer used by the tests of the crate.
+//!
+//! It forwards to the system allocator and lets the current thread inject faults: failing
+//! allocations, reallocations always moving the memory, byte buffers at odd addresses, and it
+//! poisons the freed memory so that a use after free reads garbage instead of the old content.
+//! The knobs are thread local so the tests running in parallel don't interfere with each other.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::ptr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[global_allocator]
+static ALLOCATOR: TestAlloc = TestAlloc;
+
+/// The byte written over the freed memory.
+pub(crate) const POISON: u8 = 0xa5;
+
+thread_local! {
+    /// The number of allocations allowed before failing, `usize::MAX` to never fail.
+    static FAIL_AFTER: Cell<usize> = const { Cell::new(usize::MAX) };
+    /// `true` if reallocations must move the memory.
+    static MOVE_ON_REALLOC: Cell<bool> = const { Cell::new(false) };
+    /// The number of allocations and reallocations made by the current thread.
+    static COUNT: Cell<usize> = const { Cell::new(0) };
+    /// `true` if the allocations aligned on 1 byte must be at an odd address.
+    static ODD_ALLOCS: Cell<bool> = const { Cell::new(false) };
+}
+
+/// The odd addresses handed out while `ODD_ALLOCS` was set, `0` for a free slot. They can be
+/// freed by any thread once the knob is reset so they are tracked globally, without allocating.
+static ODD: [AtomicUsize; 16] = [const { AtomicUsize::new(0) }; 16];
+
+struct TestAlloc;
+
+impl TestAlloc {
+    /// Count an allocation and return `false` if it must fail.
+    fn allowed(&self) -> bool {
+        // The thread locals may be unavailable while the thread is destroyed
+        let _ = COUNT.try_with(|count| count.set(count.get() + 1));
+
+        FAIL_AFTER
+            .try_with(|left| match left.get() {
+                0 => false,
+                usize::MAX => true,
+                n => {
+                    left.set(n - 1);
+                    true
+                }
+            })
+            .unwrap_or(true)
+    }
+}
+
+/// Allocate one more byte than `layout` and return the odd address following the first byte.
+unsafe fn alloc_odd(layout: Layout) -> *mut u8 {
+    let base = System.alloc(Layout::from_size_align_unchecked(layout.size() + 1, 2));
+
+    if base.is_null() {
+        return base;
+    }
+
+    let odd = base.add(1);
+    let tracked = ODD.iter().any(|slot| {
+        slot.compare_exchange(0, odd as usize, Ordering::Relaxed, Ordering::Relaxed)
+            .is_ok()
+    });
+    assert!(tracked, "too many odd allocations alive");
+
+    odd
+}
+
+/// Forget `ptr` and return `true` if it was returned by `alloc_odd`.
+fn take_odd(ptr: *mut u8) -> bool {
+    ptr as usize & 1 == 1
+        && ODD.iter().any(|slot| {
+            slot.compare_exchange(ptr as usize, 0, Ordering::Relaxed, Ordering::Relaxed)
+                .is_ok()
+        })
+}
+
+unsafe impl GlobalAlloc for TestAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if !self.allowed() {
+            return ptr::null_mut();
+        }
+
+        if layout.align() == 1 && ODD_ALLOCS.try_with(Cell::get).unwrap_or(false) {
+            return alloc_odd(layout);
+        }
+
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        ptr::write_bytes(ptr, POISON, layout.size());
+
+        if take_odd(ptr) {
+            let layout = Layout::from_size_align_unchecked(layout.size() + 1, 2);
+            return System.dealloc(ptr.sub(1), layout);
+        }
+
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        if !self.allowed() {
+            return ptr::null_mut();
+        }
+
+        let is_odd = ptr as usize & 1 == 1
+            && ODD
+                .iter()
+                .any(|slot| slot.load(Ordering::Relaxed) == ptr as usize);
+
+        if !is_odd && !MOVE_ON_REALLOC.try_with(Cell::get).unwrap_or(false) {
+            return System.realloc(ptr, layout, new_size);
+        }
+
+        let new_layout = Layout::from_size_align_unchecked(new_size, layout.align());
+        let new_ptr = if layout.align() == 1 && ODD_ALLOCS.try_with(Cell::get).unwrap_or(false) {
+            alloc_odd(new_layout)
+        } else {
+            System.alloc(new_layout)
+        };
+
+        if !new_ptr.is_null() {
+            ptr::copy_nonoverlapping(ptr, new_ptr, layout.size().min(new_size));
+            self.dealloc(ptr, layout);
+        }
+
+        new_ptr
+    }
+}
+
+/// Restore the previous value of a knob when dropped, even if the test panics.
+struct Restore<T: Copy + 'static> {
+    knob: &'static std::thread::LocalKey<Cell<T>>,
+    old: T,
+}
+
+impl<T: Copy + 'static> Restore<T> {
+    fn set(knob: &'static std::thread::LocalKey<Cell<T>>, value: T) -> Restore<T> {
+        Restore {
+            knob,
+            old: knob.replace(value),
+        }
+    }
+}
+
+impl<T: Copy + 'static> Drop for Restore<T> {
+    fn drop(&mut self) {
+        self.knob.set(self.old)
+    }
+}
+
+/// Run `f`, making the allocations of the current thread fail after `n` successful ones.
+pub(crate) fn fail_after<R>(n: usize, f: impl FnOnce() -> R) -> R {
+    let _restore = Restore::set(&FAIL_AFTER, n);
+    f()
+}
+
+/// Run `f`, making the reallocations of the current thread always move the memory.
+pub(crate) fn move_on_realloc<R>(f: impl FnOnce() -> R) -> R {
+    let _restore = Restore::set(&MOVE_ON_REALLOC, true);
+    f()
+}
+
+/// Run `f`, placing the byte buffers it allocates at odd addresses.
+pub(crate) fn odd_allocs<R>(f: impl FnOnce() -> R) -> R {
+    let _restore = Restore::set(&ODD_ALLOCS, true);
+    f()
+}
+
+/// Run `f` and return its result with the number of allocations and reallocations it made.
+pub(crate) fn count_allocs<R>(f: impl FnOnce() -> R) -> (R, usize) {
+    let before = COUNT.get();
+    let res = f();
+    (res, COUNT.get() - before)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn knobs() {
+        let (vec, count) = count_allocs(|| {
+            let mut vec = Vec::<u8>::with_capacity(8);
+            vec.reserve(64);
+            vec
+        });
+        assert_eq!(count, 2);
+
+        let old = vec.as_ptr();
+        let vec = move_on_realloc(|| {
+            let mut vec = vec;
+            vec.reserve(256);
+            vec
+        });
+        assert_ne!(vec.as_ptr(), old);
+
+        let res = fail_after(1, || {
+            let _ok = Vec::<u8>::with_capacity(8);
+            Vec::<u8>::new().try_reserve(8)
+        });
+        assert!(res.is_err());
+
+        let mut vec = odd_allocs(|| b"odd".to_vec());
+        assert_eq!(vec.as_ptr() as usize & 1, 1);
+        vec.reserve(64);
+        assert_eq!(vec, b"odd");
+    }
+}
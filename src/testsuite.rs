@@ -0,0 +1,333 @@
+//! Conformance tests for the implementations of [`Buf`] and [`BufMut`].
+//!
+//! The buffers of the crate and its adapters rely on the invariants checked here, e.g. that a
+//! non-empty buffer never returns an empty chunck. Third-party implementations can call these
+//! functions from their own tests to validate themselves.
+//!
+//! # Example
+//!
+//! ```
+//! use bytes::testsuite;
+//! use bytes::Buf;
+//!
+//! testsuite::assert_buf_laws(|| (&b"hello "[..]).chain(&b"world"[..]), b"hello world");
+//! testsuite::assert_buf_mut_laws(Vec::new, |vec| vec);
+//! ```
+
+use alloc::vec::Vec;
+use core::cmp;
+
+use crate::{Buf, BufMut};
+
+/// Check that the buffers created by `make` behave as a `Buf` holding `content`.
+///
+/// `make` is called several times and must return a new buffer holding `content` each time.
+///
+/// # Panics
+///
+/// This panics with a message describing the broken law if the buffers don't behave as
+/// expected.
+pub fn assert_buf_laws<B, F>(make: F, content: &[u8])
+where
+    B: Buf,
+    F: Fn() -> B,
+{
+    // `remaining` and `has_remaining` agree with the content
+    let buf = make();
+    assert_eq!(buf.remaining(), content.len(), "remaining() != content len");
+    assert!(
+        buf.remaining() <= crate::MAX_LEN,
+        "remaining() > isize::MAX"
+    );
+    assert_eq!(
+        buf.has_remaining(),
+        !content.is_empty(),
+        "has_remaining() != (remaining() > 0)"
+    );
+
+    // Walking the chuncks yields the content, a non-empty buffer never has an empty chunck and
+    // advancing decreases `remaining` by the same count
+    let mut buf = make();
+    let mut walked = Vec::new();
+
+    while buf.has_remaining() {
+        let chunck = buf.chuncks();
+        let remaining = buf.remaining();
+
+        assert!(!chunck.is_empty(), "empty chunck with remaining bytes");
+        assert!(chunck.len() <= remaining, "chunck len > remaining()");
+
+        if let Some(contiguous) = buf.remaining_slice() {
+            assert_eq!(
+                contiguous,
+                &content[walked.len()..],
+                "invalid contiguous bytes"
+            );
+        }
+
+        // Advance in two steps to check partial advances
+        let len = chunck.len();
+        let half = len / 2;
+
+        walked.extend_from_slice(&chunck[..half]);
+        buf.advance(half);
+        assert_eq!(
+            buf.remaining(),
+            remaining - half,
+            "advance() != remaining() delta"
+        );
+
+        walked.extend_from_slice(&buf.chuncks()[..len - half]);
+        buf.advance(len - half);
+        assert_eq!(
+            buf.remaining(),
+            remaining - len,
+            "advance() != remaining() delta"
+        );
+    }
+
+    assert_eq!(walked, content, "chuncks don't yield the content");
+    assert!(
+        buf.chuncks().is_empty(),
+        "non-empty chunck without remaining bytes"
+    );
+
+    // `advance(0)` is a no-op
+    let mut buf = make();
+    buf.advance(0);
+    assert_eq!(
+        buf.remaining(),
+        content.len(),
+        "advance(0) changed remaining()"
+    );
+
+    // `copy_to_slice` copies the content, whatever the split
+    for split in [0, 1, content.len() / 2, content.len()] {
+        let split = cmp::min(split, content.len());
+        let mut buf = make();
+        let mut dst = vec![0; content.len()];
+
+        buf.copy_to_slice(&mut dst[..split]);
+        buf.copy_to_slice(&mut dst[split..]);
+
+        assert_eq!(dst, content, "copy_to_slice() doesn't copy the content");
+        assert!(!buf.has_remaining(), "copy_to_slice() doesn't advance");
+    }
+
+    // `peek_to_slice` copies the first bytes and doesn't advance
+    for skip in [0, content.len() / 2] {
+        let mut buf = make();
+        buf.advance(skip);
+
+        let mut dst = vec![0; content.len() - skip];
+        buf.peek_to_slice(&mut dst);
+        assert_eq!(
+            dst,
+            content[skip..],
+            "peek_to_slice() doesn't copy the first bytes"
+        );
+        assert_eq!(
+            buf.remaining(),
+            content.len() - skip,
+            "peek_to_slice() advanced"
+        );
+    }
+
+    // `fork` holds the remaining bytes and doesn't advance
+    for skip in [0, content.len() / 2] {
+        let mut buf = make();
+        buf.advance(skip);
+
+        let fork = buf.fork();
+        assert_eq!(
+            fork,
+            content[skip..],
+            "fork() doesn't hold the remaining bytes"
+        );
+        assert_eq!(buf.remaining(), content.len() - skip, "fork() advanced");
+    }
+}
+
+/// Check that the buffers created by `make` behave as a `BufMut` starting empty, using `written`
+/// to retrieve the bytes written into a buffer.
+///
+/// `make` is called several times and must return a new empty buffer each time. Up to 300
+/// bytes are written in each buffer, or less if its remaining space is smaller.
+///
+/// # Panics
+///
+/// This panics with a message describing the broken law if the buffers don't behave as
+/// expected.
+pub fn assert_buf_mut_laws<M, F, W>(make: F, written: W)
+where
+    M: BufMut,
+    F: Fn() -> M,
+    W: Fn(M) -> Vec<u8>,
+{
+    assert!(
+        make().remaining_mut() <= crate::MAX_LEN,
+        "remaining_mut() > isize::MAX"
+    );
+
+    let len = cmp::min(make().remaining_mut(), 300);
+    let content: Vec<u8> = (0..len).map(|i| (i * 7) as u8).collect();
+
+    // `remaining_mut` decreases by the number of bytes written
+    let mut buf = make();
+    let remaining = buf.remaining_mut();
+    buf.put_slice(&content);
+    assert_remaining_mut_delta(
+        remaining,
+        buf.remaining_mut(),
+        len,
+        "put_slice() != remaining_mut() delta",
+    );
+    assert_eq!(written(buf), content, "put_slice() doesn't write the bytes");
+
+    // Writing through the chuncks is equivalent to `put_slice`, a buffer with remaining space
+    // never has an empty chunck
+    let mut buf = make();
+    let mut off = 0;
+
+    while off < len {
+        let remaining = buf.remaining_mut();
+        let chunck = buf.chuncks_mut();
+
+        assert!(!chunck.is_empty(), "empty chunck with remaining space");
+        assert!(chunck.len() <= remaining, "chunck len > remaining_mut()");
+
+        let count = cmp::min(chunck.len(), len - off);
+        chunck[..count].copy_from_slice(&content[off..off + count]);
+        unsafe { buf.advance(count) };
+
+        assert_remaining_mut_delta(
+            remaining,
+            buf.remaining_mut(),
+            count,
+            "advance() != remaining_mut() delta",
+        );
+        off += count;
+    }
+
+    assert_eq!(
+        written(buf),
+        content,
+        "chuncks_mut() doesn't write the bytes"
+    );
+
+    // `put_u8` and `put` append to the bytes already written
+    if len >= 2 {
+        let mut buf = make();
+
+        buf.put_u8(content[0]);
+        buf.put(&content[1..]);
+        assert_eq!(written(buf), content, "put_u8() and put() don't append");
+    }
+
+    // `advance(0)` is a no-op
+    let mut buf = make();
+    unsafe { buf.advance(0) };
+    assert_eq!(
+        buf.remaining_mut(),
+        remaining,
+        "advance(0) changed remaining_mut()"
+    );
+}
+
+/// Check that writing `count` bytes decreased the remaining space from `before` to `after`.
+///
+/// A buffer made of several growable buffers, e.g. a chain of vectors, caps its remaining space
+/// to `isize::MAX`. Writing into it doesn't decrease the capped value by the written count, it
+/// only has to stay in range.
+fn assert_remaining_mut_delta(before: usize, after: usize, count: usize, msg: &str) {
+    if before < crate::MAX_LEN {
+        assert_eq!(after, before - count, "{}", msg);
+    } else {
+        assert!(after >= before - count, "{}", msg);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Bytes, BytesMut, CountingBuf, CountingBufMut, MaybeOwnedBytes, SharedAppendBuf};
+
+    const CONTENT: &[u8] = b"the quick brown fox jumps over the lazy dog";
+
+    /// A third-party `Buf` made of chuncks of at most `size` bytes, which doesn't override any
+    /// provided method.
+    #[derive(Clone)]
+    struct Chuncked<'a> {
+        bytes: &'a [u8],
+        size: usize,
+    }
+
+    impl Buf for Chuncked<'_> {
+        fn remaining(&self) -> usize {
+            self.bytes.len()
+        }
+
+        fn chuncks(&self) -> &[u8] {
+            &self.bytes[..cmp::min(self.size, self.bytes.len())]
+        }
+
+        fn advance(&mut self, cnt: usize) {
+            self.bytes = &self.bytes[cnt..];
+        }
+
+        fn fork(&self) -> Bytes {
+            let mut copy = self.clone();
+            copy.copy_to_bytes(copy.remaining())
+        }
+    }
+
+    #[test]
+    fn buf_laws() {
+        assert_buf_laws(|| CONTENT, CONTENT);
+        assert_buf_laws(|| &CONTENT[..0], b"");
+        assert_buf_laws(|| Bytes::from(CONTENT.to_vec()), CONTENT);
+        assert_buf_laws(|| MaybeOwnedBytes::from(CONTENT), CONTENT);
+        assert_buf_laws(|| CONTENT.take(9), b"the quick");
+        assert_buf_laws(|| CountingBuf::new(CONTENT), CONTENT);
+        assert_buf_laws(|| CONTENT[..4].chain(&CONTENT[4..]), CONTENT);
+        assert_buf_laws(|| (&b""[..]).chain(CONTENT).take(20), &CONTENT[..20]);
+        assert_buf_laws(
+            || Chuncked {
+                bytes: CONTENT,
+                size: 3,
+            },
+            CONTENT,
+        );
+    }
+
+    #[test]
+    fn buf_mut_laws() {
+        assert_buf_mut_laws(Vec::new, |vec| vec);
+        assert_buf_mut_laws(BytesMut::new, |buf| buf.freeze().to_vec());
+        assert_buf_mut_laws(|| Vec::new().limit(100), |buf| buf.into_inner());
+        assert_buf_mut_laws(
+            || Vec::new().limit(10).chain_mut(Vec::new()),
+            |buf| {
+                let (mut first, last) = buf.into_inner();
+                first.get_mut().extend_from_slice(&last);
+                first.into_inner()
+            },
+        );
+        assert_buf_mut_laws(|| CountingBufMut::new(Vec::new()), |buf| buf.into_inner());
+        assert_buf_mut_laws(
+            || SharedAppendBuf::with_capacity(64),
+            |buf| buf.snapshot().to_vec(),
+        );
+    }
+
+    #[test]
+    fn max_len() {
+        let chain = Vec::new().chain_mut(Vec::new());
+        assert_eq!(chain.remaining_mut(), isize::MAX as usize);
+
+        let mut chain = BytesMut::new().chain_mut(Vec::new());
+        chain.put_slice(b"hello");
+        assert_eq!(chain.remaining_mut(), isize::MAX as usize);
+        assert_eq!(chain.first_ref().remaining_mut(), isize::MAX as usize - 5);
+    }
+}
@@ -0,0 +1,92 @@
+//! Trace events emitted over the lifecycle of the `Bytes` buffers.
+//!
+//! With the `tracing` feature enabled, the crate emits trace level events with the
+//! `bytes::lifecycle` target and an `event` field set to one of:
+//!
+//! * `"alloc"`: a buffer was moved into a `Bytes`, with its `len`, `cap` and `callsite`.
+//! * `"promote"`: an unshared buffer was promoted to a reference counted one, with its `cap`.
+//! * `"clone"`: a `Bytes` was cloned, with its `len` and `callsite`.
+//! * `"drop"`: the last `Bytes` of a buffer was dropped and the buffer freed, with its `cap`.
+//!
+//! Matching the `"alloc"` events without a `"drop"` event for the same `ptr` finds the
+//! callsites creating the leaked buffers. Without the feature the events compile to nothing.
+
+/// Emit a lifecycle event when the `tracing` feature is enabled.
+macro_rules! lifecycle {
+    ($event:literal, $($field:tt)*) => {
+        #[cfg(feature = "tracing")]
+        ::tracing::trace!(target: "bytes::lifecycle", event = $event, $($field)*);
+    };
+}
+
+pub(crate) use lifecycle;
+
+#[cfg(all(test, feature = "tracing"))]
+mod test {
+    use std::sync::{Arc, Mutex};
+    use std::vec::Vec;
+
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata, Subscriber};
+
+    use crate::Bytes;
+
+    #[derive(Clone, Default)]
+    struct Events(Arc<Mutex<Vec<&'static str>>>);
+
+    impl Visit for Events {
+        fn record_str(&mut self, field: &Field, value: &str) {
+            if field.name() == "event" {
+                let event = ["alloc", "promote", "clone", "drop"]
+                    .into_iter()
+                    .find(|e| *e == value)
+                    .unwrap();
+                self.0.lock().unwrap().push(event);
+            }
+        }
+
+        fn record_debug(&mut self, _: &Field, _: &dyn core::fmt::Debug) {}
+    }
+
+    impl Subscriber for Events {
+        fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+            metadata.target() == "bytes::lifecycle"
+        }
+
+        fn new_span(&self, _: &Attributes<'_>) -> Id {
+            Id::from_u64(1)
+        }
+
+        fn record(&self, _: &Id, _: &Record<'_>) {}
+
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+        fn event(&self, event: &Event<'_>) {
+            event.record(&mut self.clone());
+        }
+
+        fn enter(&self, _: &Id) {}
+
+        fn exit(&self, _: &Id) {}
+    }
+
+    #[test]
+    fn lifecycle_events() {
+        let events = Events::default();
+
+        tracing::subscriber::with_default(events.clone(), || {
+            let bytes = Bytes::from(b"hello".to_vec());
+            let clone = bytes.clone();
+
+            drop(bytes);
+            drop(clone);
+            drop(Bytes::from_static(b"static").clone());
+        });
+
+        assert_eq!(
+            *events.0.lock().unwrap(),
+            ["alloc", "clone", "promote", "drop", "clone"]
+        );
+    }
+}
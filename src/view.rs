@@ -0,0 +1,85 @@
+//! Typed views of the headers of `Bytes`, enabled by the `zerocopy` feature.
+
+use core::mem;
+
+use zerocopy::{FromBytes, Immutable, KnownLayout};
+
+use crate::Bytes;
+
+impl Bytes {
+    /// Return a reference to the `T` stored at the start of `self` and the bytes following it.
+    ///
+    /// The header is read in place and the payload shares the buffer of `self`, nothing is
+    /// copied. `None` is returned if `self` is shorter than `T` or if its start is not aligned
+    /// for `T`, deriving `zerocopy::Unaligned` on `T` lifts the alignment requirement.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    /// use zerocopy::byteorder::network_endian::U16;
+    /// use zerocopy::{FromBytes, Immutable, KnownLayout, Unaligned};
+    ///
+    /// #[derive(FromBytes, Immutable, KnownLayout, Unaligned)]
+    /// #[repr(C)]
+    /// struct Header {
+    ///     kind: u8,
+    ///     flags: u8,
+    ///     len: U16,
+    /// }
+    ///
+    /// let frame = Bytes::from_static(b"\x01\x80\x00\x05hello");
+    /// let (header, payload) = frame.view::<Header>().unwrap();
+    ///
+    /// assert_eq!(header.kind, 1);
+    /// assert_eq!(header.len.get(), 5);
+    /// assert_eq!(payload, "hello");
+    /// ```
+    pub fn view<T>(&self) -> Option<(&T, Bytes)>
+    where
+        T: FromBytes + Immutable + KnownLayout,
+    {
+        let (header, _) = T::ref_from_prefix(self.as_slice()).ok()?;
+
+        Some((header, self.slice(mem::size_of::<T>()..)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use zerocopy::{FromBytes, Immutable, KnownLayout, Unaligned};
+
+    use crate::Bytes;
+
+    #[derive(FromBytes, Immutable, KnownLayout, Unaligned)]
+    #[repr(C)]
+    struct Header {
+        tag: [u8; 2],
+        len: u8,
+    }
+
+    #[test]
+    fn view() {
+        let bytes = Bytes::from(b"ab\x03xyz".to_vec());
+        let (header, payload) = bytes.view::<Header>().unwrap();
+
+        assert_eq!(&header.tag, b"ab");
+        assert_eq!(header.len, 3);
+        assert_eq!(payload, "xyz");
+        assert_eq!(payload.as_ptr(), bytes[3..].as_ptr());
+
+        let (_, payload) = bytes.slice(..3).view::<Header>().unwrap();
+        assert!(payload.is_empty());
+
+        assert!(bytes.slice(..2).view::<Header>().is_none());
+    }
+
+    #[test]
+    fn view_aligned() {
+        let words = Bytes::from(vec![0u8; 9]);
+        let start = words.as_ptr().align_offset(4);
+
+        assert!(words.slice(start..).view::<u32>().is_some());
+        assert!(words.slice(start + 1..).view::<u32>().is_none());
+    }
+}